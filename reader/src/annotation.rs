@@ -0,0 +1,52 @@
+/// Models an annotation applied to a class, a field or a method
+#[derive(Debug, PartialEq, Clone)]
+pub struct Annotation {
+    /// Descriptor of the annotation's type, i.e. something like `Ljava/lang/Deprecated;`
+    pub type_descriptor: String,
+    /// Whether the annotation is retained at runtime, i.e. it comes from the
+    /// `RuntimeVisibleAnnotations` attribute rather than `RuntimeInvisibleAnnotations`.
+    /// Annotations used as element values have the same visibility as the enclosing one.
+    pub runtime_visible: bool,
+    /// The elements set explicitly: those with their default value are not stored
+    pub elements: Vec<AnnotationElement>,
+}
+
+impl Annotation {
+    /// Returns the value of the element with the given name, if it was set explicitly
+    pub fn element(&self, name: &str) -> Option<&AnnotationElementValue> {
+        self.elements
+            .iter()
+            .find(|element| element.name == name)
+            .map(|element| &element.value)
+    }
+}
+
+/// A name-value pair of an annotation
+#[derive(Debug, PartialEq, Clone)]
+pub struct AnnotationElement {
+    pub name: String,
+    pub value: AnnotationElementValue,
+}
+
+/// Possible values of an annotation element
+#[derive(Debug, PartialEq, Clone)]
+pub enum AnnotationElementValue {
+    Byte(i8),
+    Char(u16),
+    Double(f64),
+    Float(f32),
+    Int(i32),
+    Long(i64),
+    Short(i16),
+    Boolean(bool),
+    String(String),
+    Enum {
+        /// Descriptor of the enum's type, i.e. something like `Ljava/lang/annotation/RetentionPolicy;`
+        type_descriptor: String,
+        constant_name: String,
+    },
+    /// The return descriptor of the class, i.e. something like `Ljava/lang/String;` or `V`
+    Class(String),
+    Annotation(Annotation),
+    Array(Vec<AnnotationElementValue>),
+}
@@ -0,0 +1,9 @@
+/// Models an entry of the `BootstrapMethods` class attribute. Entries are referred to, by
+/// their index, from the `InvokeDynamic` and `Dynamic` constants.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BootstrapMethod {
+    /// Index of a `MethodHandle` constant, which is the bootstrap method to invoke
+    pub method_ref: u16,
+    /// Indexes of the constants passed as static arguments to the bootstrap method
+    pub arguments: Vec<u16>,
+}
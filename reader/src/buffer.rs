@@ -103,4 +103,18 @@ mod tests {
 
         assert!(buffer.read_u32().is_err());
     }
+
+    #[test]
+    fn floating_point_numbers_keep_their_exact_bits() {
+        let data = vec![
+            0x80, 0x00, 0x00, 0x00, // -0.0f
+            0x00, 0x00, 0x00, 0x01, // smallest denormal float
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, // smallest denormal double
+        ];
+        let mut buffer = Buffer::new(&data);
+
+        assert_eq!(0x80000000u32, buffer.read_f32().unwrap().to_bits());
+        assert_eq!(1u32, buffer.read_f32().unwrap().to_bits());
+        assert_eq!(1u64, buffer.read_f64().unwrap().to_bits());
+    }
 }
@@ -1,6 +1,7 @@
 use std::fmt;
 
 use crate::{
+    annotation::Annotation, bootstrap_method::BootstrapMethod,
     class_access_flags::ClassAccessFlags, class_file_field::ClassFileField,
     class_file_method::ClassFileMethod, class_file_version::ClassFileVersion,
     constant_pool::ConstantPool,
@@ -19,6 +20,9 @@ pub struct ClassFile {
     pub methods: Vec<ClassFileMethod>,
     pub deprecated: bool,
     pub source_file: Option<String>,
+    /// Entries of the `BootstrapMethods` attribute, used by `invokedynamic`
+    pub bootstrap_methods: Vec<BootstrapMethod>,
+    pub annotations: Vec<Annotation>,
 }
 
 impl fmt::Display for ClassFile {
@@ -43,6 +47,14 @@ impl fmt::Display for ClassFile {
         for method in self.methods.iter() {
             writeln!(f, "  - {method}")?;
         }
+        writeln!(f, "bootstrap methods:")?;
+        for (index, bootstrap_method) in self.bootstrap_methods.iter().enumerate() {
+            writeln!(
+                f,
+                "  {index}: {} {:?}",
+                bootstrap_method.method_ref, bootstrap_method.arguments
+            )?;
+        }
         Ok(())
     }
 }
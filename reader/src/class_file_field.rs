@@ -1,6 +1,6 @@
 use std::{fmt, fmt::Formatter};
 
-use crate::{field_flags::FieldFlags, field_type::FieldType};
+use crate::{annotation::Annotation, field_flags::FieldFlags, field_type::FieldType};
 
 /// Models a field in a class
 #[derive(Debug, PartialEq)]
@@ -11,6 +11,7 @@ pub struct ClassFileField {
     /// Fields which model a constant (final) will have an attribute specifying the value
     pub constant_value: Option<FieldConstantValue>,
     pub deprecated: bool,
+    pub annotations: Vec<Annotation>,
 }
 
 impl fmt::Display for ClassFileField {
@@ -1,13 +1,18 @@
 use std::{fmt, fmt::Formatter};
 
 use crate::{
+    annotation::Annotation,
     attribute::Attribute,
+    class_reader_error::ClassReaderError,
+    control_flow_graph::ControlFlowGraph,
     exception_table::ExceptionTable,
     field_type::{BaseType, FieldType},
-    instruction::Instruction,
+    instruction::{DecodedInstruction, Instruction},
     line_number_table::LineNumberTable,
     method_descriptor::MethodDescriptor,
     method_flags::MethodFlags,
+    method_parameter::MethodParameter,
+    program_counter::ProgramCounter,
 };
 
 /// Models a method in a class
@@ -26,6 +31,10 @@ pub struct ClassFileMethod {
     pub deprecated: bool,
     /// List of exceptions in the `throws` clause of the method
     pub thrown_exceptions: Vec<String>,
+    /// Names and flags of the parameters, if the class was compiled with `-parameters`.
+    /// Otherwise, it will be empty.
+    pub parameters: Vec<MethodParameter>,
+    pub annotations: Vec<Annotation>,
 }
 
 impl fmt::Display for ClassFileMethod {
@@ -55,6 +64,10 @@ impl ClassFileMethod {
         self.flags.contains(MethodFlags::NATIVE)
     }
 
+    pub fn is_abstract(&self) -> bool {
+        self.flags.contains(MethodFlags::ABSTRACT)
+    }
+
     pub fn is_void(&self) -> bool {
         self.parsed_type_descriptor.return_type.is_none()
     }
@@ -90,6 +103,24 @@ pub struct ClassFileMethodCode {
     pub attributes: Vec<Attribute>,
 }
 
+impl ClassFileMethodCode {
+    /// Decodes all the instructions of the code
+    pub fn instructions(&self) -> Result<Vec<DecodedInstruction>, ClassReaderError> {
+        Ok(Instruction::parse_instructions(&self.code)?
+            .into_iter()
+            .map(|(address, instruction)| DecodedInstruction {
+                pc: ProgramCounter(address as u16),
+                instruction,
+            })
+            .collect())
+    }
+
+    /// Computes the control flow graph of the code, decoding its instructions
+    pub fn control_flow_graph(&self) -> Result<ControlFlowGraph, ClassReaderError> {
+        ControlFlowGraph::build(self)
+    }
+}
+
 impl fmt::Display for ClassFileMethodCode {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         writeln!(
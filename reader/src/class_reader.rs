@@ -2,7 +2,9 @@ use log::warn;
 use result::prelude::*;
 
 use crate::{
+    annotation::{Annotation, AnnotationElement, AnnotationElementValue},
     attribute::Attribute,
+    bootstrap_method::BootstrapMethod,
     class_access_flags::ClassAccessFlags,
     class_file::ClassFile,
     class_file_field::{ClassFileField, FieldConstantValue},
@@ -17,6 +19,7 @@ use crate::{
     line_number_table::{LineNumberTable, LineNumberTableEntry},
     method_descriptor::MethodDescriptor,
     method_flags::MethodFlags,
+    method_parameter::{MethodParameter, MethodParameterFlags},
     program_counter::ProgramCounter,
 };
 use crate::{buffer::Buffer, type_conversion::ToUsizeSafe};
@@ -31,7 +34,7 @@ struct ClassFileReader<'a> {
 
 /// Reference: https://docs.oracle.com/javase/specs/jvms/se7/html/jvms-4.html
 impl<'a> ClassFileReader<'a> {
-    fn new(data: &[u8]) -> ClassFileReader {
+    fn new(data: &[u8]) -> ClassFileReader<'_> {
         ClassFileReader {
             buffer: Buffer::new(data),
             class_file: Default::default(),
@@ -94,6 +97,10 @@ impl<'a> ClassFileReader<'a> {
                 10 => self.read_method_reference_constant()?,
                 11 => self.read_interface_method_reference_constant()?,
                 12 => self.read_name_and_type_constant()?,
+                15 => self.read_method_handle_constant()?,
+                16 => self.read_method_type_constant()?,
+                17 => self.read_dynamic_constant()?,
+                18 => self.read_invoke_dynamic_constant()?,
                 // For newer versions of java, there are more constant types
                 _ => {
                     warn!("invalid entry in constant pool at index {} tag {}", i, tag);
@@ -192,6 +199,32 @@ impl<'a> ClassFileReader<'a> {
         ))
     }
 
+    fn read_method_handle_constant(&mut self) -> Result<ConstantPoolEntry> {
+        let reference_kind = self.buffer.read_u8()?;
+        let reference = self.buffer.read_u16()?;
+        Ok(ConstantPoolEntry::MethodHandle(reference_kind, reference))
+    }
+
+    fn read_method_type_constant(&mut self) -> Result<ConstantPoolEntry> {
+        let descriptor = self.buffer.read_u16()?;
+        Ok(ConstantPoolEntry::MethodType(descriptor))
+    }
+
+    fn read_dynamic_constant(&mut self) -> Result<ConstantPoolEntry> {
+        let bootstrap_method = self.buffer.read_u16()?;
+        let name_and_type = self.buffer.read_u16()?;
+        Ok(ConstantPoolEntry::Dynamic(bootstrap_method, name_and_type))
+    }
+
+    fn read_invoke_dynamic_constant(&mut self) -> Result<ConstantPoolEntry> {
+        let bootstrap_method = self.buffer.read_u16()?;
+        let name_and_type = self.buffer.read_u16()?;
+        Ok(ConstantPoolEntry::InvokeDynamic(
+            bootstrap_method,
+            name_and_type,
+        ))
+    }
+
     fn read_access_flags(&mut self) -> Result<()> {
         let num = self.buffer.read_u16()?;
         match ClassAccessFlags::from_bits(num) {
@@ -254,6 +287,7 @@ impl<'a> ClassFileReader<'a> {
         let raw_attributes = self.read_raw_attributes()?;
         let constant_value = self.extract_constant_value(&raw_attributes)?;
         let deprecated = self.search_deprecated_attribute(&raw_attributes);
+        let annotations = self.extract_annotations(&raw_attributes)?;
 
         Ok(ClassFileField {
             flags,
@@ -261,6 +295,7 @@ impl<'a> ClassFileReader<'a> {
             type_descriptor,
             constant_value,
             deprecated,
+            annotations,
         })
     }
 
@@ -339,6 +374,8 @@ impl<'a> ClassFileReader<'a> {
         };
         let deprecated = self.search_deprecated_attribute(&raw_attributes);
         let thrown_exceptions = self.extract_thrown_exceptions(&raw_attributes)?;
+        let parameters = self.extract_method_parameters(&raw_attributes)?;
+        let annotations = self.extract_annotations(&raw_attributes)?;
 
         Ok(ClassFileMethod {
             flags,
@@ -349,6 +386,8 @@ impl<'a> ClassFileReader<'a> {
             code,
             deprecated,
             thrown_exceptions,
+            parameters,
+            annotations,
         })
     }
 
@@ -463,13 +502,169 @@ impl<'a> ClassFileReader<'a> {
             .unwrap_or(Ok(Vec::new()))
     }
 
+    fn extract_method_parameters(
+        &self,
+        raw_attributes: &[Attribute],
+    ) -> Result<Vec<MethodParameter>> {
+        raw_attributes
+            .iter()
+            .find(|attr| attr.name == "MethodParameters")
+            .map(|attr| {
+                let mut buf = Buffer::new(&attr.bytes);
+                let num_entries = buf.read_u8()?.into_usize_safe();
+                let mut parameters = Vec::with_capacity(num_entries);
+                for _ in 0..num_entries {
+                    let name_index = buf.read_u16()?;
+                    let name = if name_index == 0 {
+                        None
+                    } else {
+                        Some(self.read_string_reference(name_index)?)
+                    };
+                    let flags_bits = buf.read_u16()?;
+                    let flags = MethodParameterFlags::from_bits(flags_bits).ok_or_else(|| {
+                        ClassReaderError::invalid_class_data(format!(
+                            "invalid method parameter flags: {flags_bits:#0x}"
+                        ))
+                    })?;
+                    parameters.push(MethodParameter { name, flags });
+                }
+                Ok(parameters)
+            })
+            .unwrap_or(Ok(Vec::new()))
+    }
+
     fn read_class_attributes(&mut self) -> Result<()> {
         let raw_attributes = self.read_raw_attributes()?;
         self.class_file.deprecated = self.search_deprecated_attribute(&raw_attributes);
         self.class_file.source_file = self.search_source_file_attribute(&raw_attributes)?;
+        self.class_file.bootstrap_methods =
+            self.extract_bootstrap_methods_attribute(&raw_attributes)?;
+        self.class_file.annotations = self.extract_annotations(&raw_attributes)?;
         Ok(())
     }
 
+    fn extract_annotations(&self, raw_attributes: &[Attribute]) -> Result<Vec<Annotation>> {
+        let mut annotations = Vec::new();
+        for attr in raw_attributes.iter() {
+            let runtime_visible = match attr.name.as_str() {
+                "RuntimeVisibleAnnotations" => true,
+                "RuntimeInvisibleAnnotations" => false,
+                _ => continue,
+            };
+            let mut buf = Buffer::new(&attr.bytes);
+            let num_annotations = buf.read_u16()?;
+            for _ in 0..num_annotations {
+                annotations.push(self.read_annotation(&mut buf, runtime_visible)?);
+            }
+        }
+        Ok(annotations)
+    }
+
+    fn read_annotation(&self, buf: &mut Buffer, runtime_visible: bool) -> Result<Annotation> {
+        let type_descriptor = self.read_string_reference(buf.read_u16()?)?;
+        let num_elements = buf.read_u16()?.into_usize_safe();
+        let mut elements = Vec::with_capacity(num_elements);
+        for _ in 0..num_elements {
+            let name = self.read_string_reference(buf.read_u16()?)?;
+            let value = self.read_annotation_element_value(buf, runtime_visible)?;
+            elements.push(AnnotationElement { name, value });
+        }
+        Ok(Annotation {
+            type_descriptor,
+            runtime_visible,
+            elements,
+        })
+    }
+
+    fn read_annotation_element_value(
+        &self,
+        buf: &mut Buffer,
+        runtime_visible: bool,
+    ) -> Result<AnnotationElementValue> {
+        let tag = buf.read_u8()? as char;
+        let value = match tag {
+            'B' | 'C' | 'I' | 'S' | 'Z' => {
+                let value = match self.class_file.constants.get(buf.read_u16()?)? {
+                    ConstantPoolEntry::Integer(value) => *value,
+                    entry => {
+                        return Err(ClassReaderError::invalid_class_data(format!(
+                            "invalid constant for annotation element of type {tag}: {entry:?}"
+                        )))
+                    }
+                };
+                match tag {
+                    'B' => AnnotationElementValue::Byte(value as i8),
+                    'C' => AnnotationElementValue::Char(value as u16),
+                    'S' => AnnotationElementValue::Short(value as i16),
+                    'Z' => AnnotationElementValue::Boolean(value != 0),
+                    _ => AnnotationElementValue::Int(value),
+                }
+            }
+            'D' | 'F' | 'J' => match (tag, self.class_file.constants.get(buf.read_u16()?)?) {
+                ('D', ConstantPoolEntry::Double(value)) => AnnotationElementValue::Double(*value),
+                ('F', ConstantPoolEntry::Float(value)) => AnnotationElementValue::Float(*value),
+                ('J', ConstantPoolEntry::Long(value)) => AnnotationElementValue::Long(*value),
+                (_, entry) => {
+                    return Err(ClassReaderError::invalid_class_data(format!(
+                        "invalid constant for annotation element of type {tag}: {entry:?}"
+                    )))
+                }
+            },
+            's' => AnnotationElementValue::String(self.read_string_reference(buf.read_u16()?)?),
+            'e' => {
+                let type_descriptor = self.read_string_reference(buf.read_u16()?)?;
+                let constant_name = self.read_string_reference(buf.read_u16()?)?;
+                AnnotationElementValue::Enum {
+                    type_descriptor,
+                    constant_name,
+                }
+            }
+            'c' => AnnotationElementValue::Class(self.read_string_reference(buf.read_u16()?)?),
+            '@' => AnnotationElementValue::Annotation(self.read_annotation(buf, runtime_visible)?),
+            '[' => {
+                let num_values = buf.read_u16()?.into_usize_safe();
+                let mut values = Vec::with_capacity(num_values);
+                for _ in 0..num_values {
+                    values.push(self.read_annotation_element_value(buf, runtime_visible)?);
+                }
+                AnnotationElementValue::Array(values)
+            }
+            _ => {
+                return Err(ClassReaderError::invalid_class_data(format!(
+                    "invalid annotation element tag: {tag}"
+                )))
+            }
+        };
+        Ok(value)
+    }
+
+    fn extract_bootstrap_methods_attribute(
+        &self,
+        raw_attributes: &[Attribute],
+    ) -> Result<Vec<BootstrapMethod>> {
+        raw_attributes
+            .iter()
+            .find(|attr| attr.name == "BootstrapMethods")
+            .map(|attr| {
+                let mut buf = Buffer::new(&attr.bytes);
+                let num_entries = buf.read_u16()?.into_usize_safe();
+                let mut bootstrap_methods = Vec::with_capacity(num_entries);
+                for _ in 0..num_entries {
+                    let method_ref = buf.read_u16()?;
+                    let num_arguments = buf.read_u16()?.into_usize_safe();
+                    let arguments = (0..num_arguments)
+                        .map(|_| buf.read_u16())
+                        .collect::<std::result::Result<Vec<u16>, _>>()?;
+                    bootstrap_methods.push(BootstrapMethod {
+                        method_ref,
+                        arguments,
+                    });
+                }
+                Ok(bootstrap_methods)
+            })
+            .unwrap_or(Ok(Vec::new()))
+    }
+
     fn search_source_file_attribute(&self, raw_attributes: &[Attribute]) -> Result<Option<String>> {
         raw_attributes
             .iter()
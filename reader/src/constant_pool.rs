@@ -16,6 +16,14 @@ pub enum ConstantPoolEntry {
     MethodReference(u16, u16),
     InterfaceMethodReference(u16, u16),
     NameAndTypeDescriptor(u16, u16),
+    /// Reference kind and index of the referred field or method
+    MethodHandle(u8, u16),
+    /// Index of the method descriptor
+    MethodType(u16),
+    /// Index in the bootstrap methods table and name and type of the constant
+    Dynamic(u16, u16),
+    /// Index in the bootstrap methods table and name and type of the invoked method
+    InvokeDynamic(u16, u16),
 }
 
 /// Constants in the pool generally take one slot, but long and double take two. We do not use
@@ -65,6 +73,16 @@ impl ConstantPool {
         }
     }
 
+    /// Returns the number of slots of the pool, i.e. the maximum valid index, since
+    /// long and double entries count twice
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
     /// Accesses an entry given its index. Note that it must be 1-based!
     pub fn get(
         &self,
@@ -134,6 +152,18 @@ impl ConstantPool {
                     self.fmt_entry(j)?
                 )
             }
+            ConstantPoolEntry::MethodHandle(kind, n) => {
+                format!("MethodHandle: {}, {} => ({})", kind, n, self.fmt_entry(*n)?)
+            }
+            ConstantPoolEntry::MethodType(n) => {
+                format!("MethodType: {} => ({})", n, self.fmt_entry(*n)?)
+            }
+            ConstantPoolEntry::Dynamic(i, j) => {
+                format!("Dynamic: {}, {} => ({})", i, j, self.fmt_entry(*j)?)
+            }
+            ConstantPoolEntry::InvokeDynamic(i, j) => {
+                format!("InvokeDynamic: {}, {} => ({})", i, j, self.fmt_entry(*j)?)
+            }
         };
         Ok(text)
     }
@@ -160,6 +190,11 @@ impl ConstantPool {
             ConstantPoolEntry::NameAndTypeDescriptor(i, j) => {
                 format!("{}: {}", self.text_of(*i)?, self.text_of(*j)?)
             }
+            ConstantPoolEntry::MethodHandle(_, n) => self.text_of(*n)?,
+            ConstantPoolEntry::MethodType(n) => self.text_of(*n)?,
+            ConstantPoolEntry::Dynamic(i, j) | ConstantPoolEntry::InvokeDynamic(i, j) => {
+                format!("#{}:{}", i, self.text_of(*j)?)
+            }
         };
         Ok(text)
     }
@@ -170,7 +205,7 @@ impl fmt::Display for ConstantPool {
         writeln!(f, "Constant pool: (size: {})", self.entries.len())?;
         for (raw_idx, _) in self.entries.iter().enumerate() {
             let index = (raw_idx + 1) as u16;
-            let entry_text = self.fmt_entry(index).map_err(|_| fmt::Error::default())?;
+            let entry_text = self.fmt_entry(index).map_err(|_| fmt::Error)?;
             writeln!(f, "    {}, {}", index, entry_text)?;
         }
         Ok(())
@@ -0,0 +1,160 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    class_file_method::ClassFileMethodCode,
+    class_reader_error::ClassReaderError,
+    instruction::{Instruction, WideInstruction},
+    program_counter::ProgramCounter,
+};
+
+/// A sequence of instructions that is always executed from the first to the last,
+/// i.e. there are no jumps into the middle of it, nor out of it except at the end.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BasicBlock {
+    /// Address of the first instruction of the block
+    pub start: ProgramCounter,
+    /// Address following the last instruction of the block
+    pub end: ProgramCounter,
+    /// Indexes, in [ControlFlowGraph::blocks], of the blocks that can be executed
+    /// after this one, including the exception handlers covering it
+    pub successors: Vec<usize>,
+}
+
+/// The control flow graph of a method, whose nodes are the basic blocks of its code
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ControlFlowGraph {
+    /// Blocks, sorted by address. The first one is the entry point of the method.
+    pub blocks: Vec<BasicBlock>,
+}
+
+impl ControlFlowGraph {
+    pub(crate) fn build(code: &ClassFileMethodCode) -> Result<Self, ClassReaderError> {
+        let instructions = Instruction::parse_instructions(&code.code)?;
+        let code_end = code.code.len() as u16;
+
+        // A block starts at the method's entry, at every jump target, after every
+        // instruction that jumps, and where the exception handlers coverage changes
+        let mut leaders = BTreeSet::from([0u16]);
+        for (index, (_, instruction)) in instructions.iter().enumerate() {
+            let (targets, falls_through) = Self::jump_targets(instruction);
+            let ends_block = !targets.is_empty() || !falls_through;
+            leaders.extend(targets);
+            if ends_block {
+                if let Some((next_address, _)) = instructions.get(index + 1) {
+                    leaders.insert(*next_address as u16);
+                }
+            }
+        }
+        for entry in code.exception_table.entries() {
+            leaders.insert(entry.range.start.0);
+            leaders.insert(entry.range.end.0);
+            leaders.insert(entry.handler_pc.0);
+        }
+        leaders.retain(|address| *address < code_end);
+
+        let starts: Vec<u16> = leaders.into_iter().collect();
+        let block_index_at = |address: u16| starts.binary_search(&address).ok();
+
+        let blocks = starts
+            .iter()
+            .enumerate()
+            .map(|(index, &start)| {
+                let end = starts.get(index + 1).copied().unwrap_or(code_end);
+                let (_, last_instruction) = instructions
+                    .iter()
+                    .rev()
+                    .find(|(address, _)| (*address as u16) < end)
+                    .expect("blocks should not be empty");
+
+                let (targets, falls_through) = Self::jump_targets(last_instruction);
+                let mut successors: Vec<usize> =
+                    targets.into_iter().filter_map(block_index_at).collect();
+                if falls_through && end < code_end {
+                    successors.push(index + 1);
+                }
+                for entry in code.exception_table.entries() {
+                    if entry.range.contains(&ProgramCounter(start)) {
+                        successors.extend(block_index_at(entry.handler_pc.0));
+                    }
+                }
+                let mut seen = BTreeSet::new();
+                successors.retain(|successor| seen.insert(*successor));
+
+                BasicBlock {
+                    start: ProgramCounter(start),
+                    end: ProgramCounter(end),
+                    successors,
+                }
+            })
+            .collect();
+        Ok(Self { blocks })
+    }
+
+    /// Returns the addresses an instruction can jump to, and whether the execution
+    /// can also continue with the following instruction
+    fn jump_targets(instruction: &Instruction) -> (Vec<u16>, bool) {
+        match instruction {
+            Instruction::Goto(target) | Instruction::Jsr(target) => (vec![*target], false),
+            Instruction::If_acmpeq(target)
+            | Instruction::If_acmpne(target)
+            | Instruction::If_icmpeq(target)
+            | Instruction::If_icmpne(target)
+            | Instruction::If_icmplt(target)
+            | Instruction::If_icmpge(target)
+            | Instruction::If_icmpgt(target)
+            | Instruction::If_icmple(target)
+            | Instruction::Ifeq(target)
+            | Instruction::Ifne(target)
+            | Instruction::Iflt(target)
+            | Instruction::Ifge(target)
+            | Instruction::Ifgt(target)
+            | Instruction::Ifle(target)
+            | Instruction::Ifnonnull(target)
+            | Instruction::Ifnull(target) => (vec![*target], true),
+            Instruction::Tableswitch(table) => {
+                let mut targets = table.targets.clone();
+                targets.push(table.default);
+                (targets, false)
+            }
+            Instruction::Lookupswitch(lookup) => {
+                let mut targets: Vec<u16> = lookup.pairs.iter().map(|(_, target)| *target).collect();
+                targets.push(lookup.default);
+                (targets, false)
+            }
+            Instruction::Areturn
+            | Instruction::Dreturn
+            | Instruction::Freturn
+            | Instruction::Ireturn
+            | Instruction::Lreturn
+            | Instruction::Return
+            | Instruction::Athrow
+            // The target of ret is stored in a local variable, so we cannot know it
+            | Instruction::Ret(_)
+            | Instruction::Wide(WideInstruction::Ret(_)) => (vec![], false),
+            _ => (vec![], true),
+        }
+    }
+
+    /// Returns the index of the block containing the given address
+    pub fn block_containing(&self, pc: ProgramCounter) -> Option<usize> {
+        self.blocks
+            .iter()
+            .position(|block| block.start <= pc && pc < block.end)
+    }
+
+    /// Returns the edges, as pairs of block indexes, that jump backwards, i.e. to a block
+    /// that does not follow the source one. These are typically generated by loops.
+    pub fn back_edges(&self) -> Vec<(usize, usize)> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .flat_map(|(index, block)| {
+                block
+                    .successors
+                    .iter()
+                    .filter(move |successor| **successor <= index)
+                    .map(move |successor| (index, *successor))
+            })
+            .collect()
+    }
+}
@@ -43,7 +43,32 @@ pub enum BaseType {
     Boolean,
 }
 
+impl BaseType {
+    /// Returns the character used for this type in descriptors, i.e. `I` for `Int`
+    pub fn descriptor(&self) -> char {
+        match self {
+            BaseType::Byte => 'B',
+            BaseType::Char => 'C',
+            BaseType::Double => 'D',
+            BaseType::Float => 'F',
+            BaseType::Int => 'I',
+            BaseType::Long => 'J',
+            BaseType::Short => 'S',
+            BaseType::Boolean => 'Z',
+        }
+    }
+}
+
 impl FieldType {
+    /// Returns the type descriptor, i.e. the inverse of [FieldType::parse]
+    pub fn to_descriptor(&self) -> String {
+        match self {
+            FieldType::Base(base_type) => base_type.descriptor().to_string(),
+            FieldType::Object(class_name) => format!("L{class_name};"),
+            FieldType::Array(component_type) => format!("[{}", component_type.to_descriptor()),
+        }
+    }
+
     /// Parses a type descriptor as specified in the JVM specs:
     /// https://docs.oracle.com/javase/specs/jvms/se7/html/jvms-4.html#jvms-4.3.2
     pub fn parse(type_descriptor: &str) -> Result<FieldType, ClassReaderError> {
@@ -171,6 +196,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_convert_back_to_descriptor() {
+        for descriptor in ["[Ljava/lang/String;", "J", "Lrjvm/Test;", "[[Z"] {
+            assert_eq!(
+                descriptor,
+                FieldType::parse(descriptor).unwrap().to_descriptor()
+            );
+        }
+    }
+
     #[test]
     fn can_format_base_type() {
         assert_eq!("Long", format!("{}", FieldType::parse("J").unwrap()));
@@ -1,9 +1,9 @@
-use crate::class_reader_error::ClassReaderError;
+use crate::{class_reader_error::ClassReaderError, program_counter::ProgramCounter};
 
 /// Represents a Java bytecode instruction.
 //noinspection SpellCheckingInspection
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Instruction {
     Aaload,
     Aastore,
@@ -175,7 +175,7 @@ pub enum Instruction {
     Lload_3,
     Lmul,
     Lneg,
-    Lookupswitch,
+    Lookupswitch(LookupSwitch),
     Lor,
     Lrem,
     Lreturn,
@@ -205,8 +205,65 @@ pub enum Instruction {
     Sastore,
     Sipush(i16),
     Swap,
-    Tableswitch,
-    Wide,
+    Tableswitch(TableSwitch),
+    Wide(WideInstruction),
+}
+
+/// Instructions modified by the `wide` prefix, which take a 16 bits local variable index
+/// (and a 16 bits increment, in the case of `iinc`)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WideInstruction {
+    Aload(u16),
+    Astore(u16),
+    Dload(u16),
+    Dstore(u16),
+    Fload(u16),
+    Fstore(u16),
+    Iinc(u16, i16),
+    Iload(u16),
+    Istore(u16),
+    Lload(u16),
+    Lstore(u16),
+    Ret(u16),
+}
+
+/// Operands of `tableswitch`, with the jump targets already converted to addresses
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TableSwitch {
+    pub default: u16,
+    /// The key jumping to the first target; the following ones are consecutive
+    pub low: i32,
+    /// The key jumping to the last target
+    pub high: i32,
+    pub targets: Vec<u16>,
+}
+
+impl TableSwitch {
+    /// Returns the address to jump to for the given key
+    pub fn target(&self, key: i32) -> u16 {
+        usize::try_from(i64::from(key) - i64::from(self.low))
+            .ok()
+            .and_then(|index| self.targets.get(index))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+/// Operands of `lookupswitch`, with the jump targets already converted to addresses
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LookupSwitch {
+    pub default: u16,
+    /// Pairs of key and target, sorted by key
+    pub pairs: Vec<(i32, u16)>,
+}
+
+impl LookupSwitch {
+    /// Returns the address to jump to for the given key
+    pub fn target(&self, key: i32) -> u16 {
+        self.pairs
+            .binary_search_by_key(&key, |(pair_key, _)| *pair_key)
+            .map_or(self.default, |index| self.pairs[index].1)
+    }
 }
 
 /// Possible arguments of instruction `newarray`
@@ -222,7 +279,143 @@ pub enum NewArrayType {
     Long,
 }
 
+/// A typed operand of an instruction
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Operand {
+    /// Index of a local variable
+    LocalVariable(u16),
+    /// Index of an entry of the constant pool
+    ConstantPoolIndex(u16),
+    /// Address of the target of a jump
+    JumpTarget(ProgramCounter),
+    /// A constant encoded in the instruction itself, such as the value pushed by `bipush`
+    /// or the number of dimensions of `multianewarray`
+    Immediate(i32),
+    /// The type of the elements of the array created by `newarray`
+    ArrayType(NewArrayType),
+}
+
+/// An instruction decoded from the bytecode of a method, along with its address
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DecodedInstruction {
+    pub pc: ProgramCounter,
+    pub instruction: Instruction,
+}
+
+impl DecodedInstruction {
+    pub fn operands(&self) -> Vec<Operand> {
+        self.instruction.operands()
+    }
+}
+
 impl Instruction {
+    /// Returns the operands of the instruction, in the order they appear in the bytecode
+    pub fn operands(&self) -> Vec<Operand> {
+        match *self {
+            Instruction::Aload(index)
+            | Instruction::Astore(index)
+            | Instruction::Dload(index)
+            | Instruction::Dstore(index)
+            | Instruction::Fload(index)
+            | Instruction::Fstore(index)
+            | Instruction::Iload(index)
+            | Instruction::Istore(index)
+            | Instruction::Lload(index)
+            | Instruction::Lstore(index)
+            | Instruction::Ret(index) => vec![Operand::LocalVariable(index as u16)],
+            Instruction::Iinc(index, constant) => vec![
+                Operand::LocalVariable(index as u16),
+                Operand::Immediate(constant as i32),
+            ],
+            Instruction::Ldc(index) => vec![Operand::ConstantPoolIndex(index as u16)],
+            Instruction::Anewarray(index)
+            | Instruction::Checkcast(index)
+            | Instruction::Getfield(index)
+            | Instruction::Getstatic(index)
+            | Instruction::Instanceof(index)
+            | Instruction::Invokedynamic(index)
+            | Instruction::Invokespecial(index)
+            | Instruction::Invokestatic(index)
+            | Instruction::Invokevirtual(index)
+            | Instruction::Ldc_w(index)
+            | Instruction::Ldc2_w(index)
+            | Instruction::New(index)
+            | Instruction::Putfield(index)
+            | Instruction::Putstatic(index) => vec![Operand::ConstantPoolIndex(index)],
+            Instruction::Invokeinterface(index, count) => vec![
+                Operand::ConstantPoolIndex(index),
+                Operand::Immediate(count as i32),
+            ],
+            Instruction::Multianewarray(index, dimensions) => vec![
+                Operand::ConstantPoolIndex(index),
+                Operand::Immediate(dimensions as i32),
+            ],
+            Instruction::Goto(target)
+            | Instruction::Jsr(target)
+            | Instruction::If_acmpeq(target)
+            | Instruction::If_acmpne(target)
+            | Instruction::If_icmpeq(target)
+            | Instruction::If_icmpne(target)
+            | Instruction::If_icmplt(target)
+            | Instruction::If_icmpge(target)
+            | Instruction::If_icmpgt(target)
+            | Instruction::If_icmple(target)
+            | Instruction::Ifeq(target)
+            | Instruction::Ifne(target)
+            | Instruction::Iflt(target)
+            | Instruction::Ifge(target)
+            | Instruction::Ifgt(target)
+            | Instruction::Ifle(target)
+            | Instruction::Ifnonnull(target)
+            | Instruction::Ifnull(target) => vec![Operand::JumpTarget(ProgramCounter(target))],
+            Instruction::Tableswitch(ref table) => {
+                let mut operands = vec![
+                    Operand::JumpTarget(ProgramCounter(table.default)),
+                    Operand::Immediate(table.low),
+                    Operand::Immediate(table.high),
+                ];
+                operands.extend(
+                    table
+                        .targets
+                        .iter()
+                        .map(|target| Operand::JumpTarget(ProgramCounter(*target))),
+                );
+                operands
+            }
+            Instruction::Lookupswitch(ref lookup) => {
+                let mut operands = vec![Operand::JumpTarget(ProgramCounter(lookup.default))];
+                operands.extend(lookup.pairs.iter().flat_map(|(key, target)| {
+                    [
+                        Operand::Immediate(*key),
+                        Operand::JumpTarget(ProgramCounter(*target)),
+                    ]
+                }));
+                operands
+            }
+            Instruction::Bipush(value) => vec![Operand::Immediate(value as i8 as i32)],
+            Instruction::Sipush(value) => vec![Operand::Immediate(value as i32)],
+            Instruction::Newarray(array_type) => vec![Operand::ArrayType(array_type)],
+            Instruction::Wide(wide_instruction) => match wide_instruction {
+                WideInstruction::Aload(index)
+                | WideInstruction::Astore(index)
+                | WideInstruction::Dload(index)
+                | WideInstruction::Dstore(index)
+                | WideInstruction::Fload(index)
+                | WideInstruction::Fstore(index)
+                | WideInstruction::Iload(index)
+                | WideInstruction::Istore(index)
+                | WideInstruction::Lload(index)
+                | WideInstruction::Lstore(index)
+                | WideInstruction::Ret(index) => vec![Operand::LocalVariable(index)],
+                WideInstruction::Iinc(index, constant) => vec![
+                    Operand::LocalVariable(index),
+                    Operand::Immediate(constant as i32),
+                ],
+            },
+            _ => vec![],
+        }
+    }
+
     /// Reads one instruction from the bytecode, and returns it along
     /// with the address of the start of the next instruction
     pub fn parse(raw_code: &[u8], address: usize) -> Result<(Self, usize), ClassReaderError> {
@@ -419,7 +612,7 @@ impl Instruction {
             0x21 => Instruction::Lload_3,
             0x69 => Instruction::Lmul,
             0x75 => Instruction::Lneg,
-            0xab => todo!("OpCode::Lookupswitch"),
+            0xab => Instruction::Lookupswitch(Self::read_lookup_switch(raw_code, &mut address)?),
             0x81 => Instruction::Lor,
             0x71 => Instruction::Lrem,
             0xad => Instruction::Lreturn,
@@ -470,8 +663,8 @@ impl Instruction {
             0x56 => Instruction::Sastore,
             0x11 => Instruction::Sipush(Self::read_i16(raw_code, &mut address)?),
             0x5f => Instruction::Swap,
-            0xaa => todo!("OpCode::Tableswitch"),
-            0xc4 => todo!("OpCode::Wide"),
+            0xaa => Instruction::Tableswitch(Self::read_table_switch(raw_code, &mut address)?),
+            0xc4 => Instruction::Wide(Self::read_wide_instruction(raw_code, &mut address)?),
             _ => {
                 return Err(ClassReaderError::invalid_class_data(format!(
                     "invalid op code: {op_byte:#04x} at address {address}"
@@ -482,6 +675,107 @@ impl Instruction {
         Ok((op_code, address))
     }
 
+    fn read_wide_instruction(
+        raw_code: &[u8],
+        address: &mut usize,
+    ) -> Result<WideInstruction, ClassReaderError> {
+        let op_byte = Self::read_u8(raw_code, address)?;
+        let index = Self::read_u16(raw_code, address)?;
+        Ok(match op_byte {
+            0x19 => WideInstruction::Aload(index),
+            0x3a => WideInstruction::Astore(index),
+            0x18 => WideInstruction::Dload(index),
+            0x39 => WideInstruction::Dstore(index),
+            0x17 => WideInstruction::Fload(index),
+            0x38 => WideInstruction::Fstore(index),
+            0x84 => WideInstruction::Iinc(index, Self::read_i16(raw_code, address)?),
+            0x15 => WideInstruction::Iload(index),
+            0x36 => WideInstruction::Istore(index),
+            0x16 => WideInstruction::Lload(index),
+            0x37 => WideInstruction::Lstore(index),
+            0xa9 => WideInstruction::Ret(index),
+            _ => {
+                return Err(ClassReaderError::invalid_class_data(format!(
+                    "invalid instruction {op_byte:#x} after wide prefix at address {address}"
+                )))
+            }
+        })
+    }
+
+    fn read_table_switch(
+        raw_code: &[u8],
+        address: &mut usize,
+    ) -> Result<TableSwitch, ClassReaderError> {
+        let instruction_address = *address - 1;
+        Self::skip_switch_padding(address);
+        let default = Self::read_switch_target(raw_code, address, instruction_address)?;
+        let low = Self::read_i32(raw_code, address)?;
+        let high = Self::read_i32(raw_code, address)?;
+        if high < low {
+            return Err(ClassReaderError::invalid_class_data(format!(
+                "invalid tableswitch range {low}..{high} at address {instruction_address}"
+            )));
+        }
+        let targets = (low..=high)
+            .map(|_| Self::read_switch_target(raw_code, address, instruction_address))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(TableSwitch {
+            default,
+            low,
+            high,
+            targets,
+        })
+    }
+
+    fn read_lookup_switch(
+        raw_code: &[u8],
+        address: &mut usize,
+    ) -> Result<LookupSwitch, ClassReaderError> {
+        let instruction_address = *address - 1;
+        Self::skip_switch_padding(address);
+        let default = Self::read_switch_target(raw_code, address, instruction_address)?;
+        let pairs_count = Self::read_i32(raw_code, address)?;
+        let pairs_count = usize::try_from(pairs_count).map_err(|_| {
+            ClassReaderError::invalid_class_data(format!(
+                "invalid lookupswitch pairs count {pairs_count} at address {instruction_address}"
+            ))
+        })?;
+        let pairs = (0..pairs_count)
+            .map(|_| {
+                let key = Self::read_i32(raw_code, address)?;
+                let target = Self::read_switch_target(raw_code, address, instruction_address)?;
+                Ok((key, target))
+            })
+            .collect::<Result<Vec<_>, ClassReaderError>>()?;
+        if !pairs.windows(2).all(|window| window[0].0 < window[1].0) {
+            return Err(ClassReaderError::invalid_class_data(format!(
+                "lookupswitch keys are not sorted at address {instruction_address}"
+            )));
+        }
+        Ok(LookupSwitch { default, pairs })
+    }
+
+    /// The operands of the switch instructions start at an address that is a multiple of
+    /// four, counting from the start of the method's code
+    fn skip_switch_padding(address: &mut usize) {
+        *address = address.next_multiple_of(4);
+    }
+
+    /// Reads a 32 bits offset, relative to the switch instruction, and returns the address
+    fn read_switch_target(
+        raw_code: &[u8],
+        address: &mut usize,
+        instruction_address: usize,
+    ) -> Result<u16, ClassReaderError> {
+        let offset = Self::read_i32(raw_code, address)?;
+        let jump_address = (instruction_address as i64) + (offset as i64);
+        u16::try_from(jump_address).map_err(|_| {
+            ClassReaderError::invalid_class_data(format!(
+                "invalid jump offset at address {instruction_address}"
+            ))
+        })
+    }
+
     /// Parses all instructions in the given raw code.
     pub(crate) fn parse_instructions(
         raw_code: &[u8],
@@ -519,7 +813,7 @@ impl Instruction {
 
     fn read_i8(raw_code: &[u8], address: &mut usize) -> Result<i8, ClassReaderError> {
         let value = Self::read_u8(raw_code, address)?;
-        Ok(unsafe { std::mem::transmute(value) })
+        Ok(value as i8)
     }
 
     fn read_u16(raw_code: &[u8], address: &mut usize) -> Result<u16, ClassReaderError> {
@@ -530,7 +824,13 @@ impl Instruction {
 
     fn read_i16(raw_code: &[u8], address: &mut usize) -> Result<i16, ClassReaderError> {
         let value = Self::read_u16(raw_code, address)?;
-        Ok(unsafe { std::mem::transmute(value) })
+        Ok(value as i16)
+    }
+
+    fn read_i32(raw_code: &[u8], address: &mut usize) -> Result<i32, ClassReaderError> {
+        let high = Self::read_u16(raw_code, address)? as u32;
+        let low = Self::read_u16(raw_code, address)? as u32;
+        Ok(((high << 16) | low) as i32)
     }
 
     fn read_offset(raw_code: &[u8], address: &mut usize) -> Result<u16, ClassReaderError> {
@@ -1,7 +1,9 @@
 #[macro_use]
 extern crate bitflags;
 
+pub mod annotation;
 mod attribute;
+pub mod bootstrap_method;
 mod buffer;
 pub mod class_access_flags;
 pub mod class_file;
@@ -11,6 +13,7 @@ pub mod class_file_version;
 pub mod class_reader;
 pub mod class_reader_error;
 pub mod constant_pool;
+pub mod control_flow_graph;
 pub mod exception_table;
 pub mod field_flags;
 pub mod field_type;
@@ -19,5 +22,6 @@ pub mod line_number;
 pub mod line_number_table;
 pub mod method_descriptor;
 pub mod method_flags;
+pub mod method_parameter;
 pub mod program_counter;
 pub mod type_conversion;
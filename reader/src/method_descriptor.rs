@@ -83,6 +83,20 @@ impl MethodDescriptor {
         }
     }
 
+    /// Returns the method descriptor, i.e. the inverse of [MethodDescriptor::parse]
+    pub fn to_descriptor(&self) -> String {
+        let parameters: String = self
+            .parameters
+            .iter()
+            .map(FieldType::to_descriptor)
+            .collect();
+        let return_type = self
+            .return_type
+            .as_ref()
+            .map_or("V".to_string(), FieldType::to_descriptor);
+        format!("({parameters}){return_type}")
+    }
+
     pub fn num_arguments(&self) -> usize {
         self.parameters.len()
     }
@@ -181,6 +195,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_convert_back_to_descriptor() {
+        for descriptor in ["(IJ)V", "()V", "(Ljava/lang/String;[[I)[J"] {
+            assert_eq!(
+                descriptor,
+                MethodDescriptor::parse(descriptor).unwrap().to_descriptor()
+            );
+        }
+    }
+
     #[test]
     fn can_get_num_arguments() {
         assert_eq!(
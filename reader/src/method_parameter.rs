@@ -0,0 +1,23 @@
+bitflags! {
+    /// Possible flags of a method parameter
+    pub struct MethodParameterFlags: u16 {
+        const FINAL = 0x0010;
+        const SYNTHETIC = 0x1000;
+        const MANDATED = 0x8000;
+    }
+}
+
+impl Default for MethodParameterFlags {
+    fn default() -> MethodParameterFlags {
+        MethodParameterFlags::empty()
+    }
+}
+
+/// Models an entry of the `MethodParameters` attribute, which is generated by
+/// `javac` only when compiling with the `-parameters` option
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MethodParameter {
+    /// Name of the parameter. Can be missing, if the compiler did not record it.
+    pub name: Option<String>,
+    pub flags: MethodParameterFlags,
+}
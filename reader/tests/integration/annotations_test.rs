@@ -0,0 +1,115 @@
+extern crate rjvm_reader;
+
+use rjvm_reader::annotation::{Annotation, AnnotationElement, AnnotationElementValue};
+
+use crate::utils;
+use utils::read_class_from_bytes;
+
+fn nested(value: &str) -> AnnotationElementValue {
+    AnnotationElementValue::Annotation(Annotation {
+        type_descriptor: "Lrjvm/Annotations$Nested;".to_string(),
+        // Nested annotations are stored in the same attribute as the enclosing one
+        runtime_visible: true,
+        elements: vec![AnnotationElement {
+            name: "value".to_string(),
+            value: AnnotationElementValue::String(value.to_string()),
+        }],
+    })
+}
+
+fn element_type(constant_name: &str) -> AnnotationElementValue {
+    AnnotationElementValue::Enum {
+        type_descriptor: "Ljava/lang/annotation/ElementType;".to_string(),
+        constant_name: constant_name.to_string(),
+    }
+}
+
+#[test_log::test]
+fn can_read_class_annotations() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/Annotations.class"));
+    assert_eq!(1, class.annotations.len());
+    let info = &class.annotations[0];
+    assert_eq!("Lrjvm/Annotations$Info;", info.type_descriptor);
+    assert!(info.runtime_visible);
+
+    assert_eq!(
+        Some(&AnnotationElementValue::String("annotated".to_string())),
+        info.element("name")
+    );
+    assert_eq!(
+        Some(&AnnotationElementValue::Array(vec![
+            AnnotationElementValue::Int(1),
+            AnnotationElementValue::Int(2),
+        ])),
+        info.element("numbers")
+    );
+    assert_eq!(
+        Some(&AnnotationElementValue::Array(vec![
+            element_type("TYPE"),
+            element_type("METHOD"),
+        ])),
+        info.element("targets")
+    );
+    assert_eq!(
+        Some(&AnnotationElementValue::Class(
+            "Ljava/lang/String;".to_string()
+        )),
+        info.element("type")
+    );
+    assert_eq!(Some(&nested("single")), info.element("nested"));
+    assert_eq!(
+        Some(&AnnotationElementValue::Array(vec![
+            nested("first"),
+            nested("second"),
+        ])),
+        info.element("all")
+    );
+    // Elements with their default value are not stored
+    assert_eq!(None, info.element("letter"));
+}
+
+#[test_log::test]
+fn can_read_enum_element_of_retention() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/Annotations$Info.class"));
+    assert_eq!(
+        vec![Annotation {
+            type_descriptor: "Ljava/lang/annotation/Retention;".to_string(),
+            runtime_visible: true,
+            elements: vec![AnnotationElement {
+                name: "value".to_string(),
+                value: AnnotationElementValue::Enum {
+                    type_descriptor: "Ljava/lang/annotation/RetentionPolicy;".to_string(),
+                    constant_name: "RUNTIME".to_string(),
+                },
+            }],
+        }],
+        class.annotations
+    );
+}
+
+#[test_log::test]
+fn can_read_method_annotations() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/Annotations.class"));
+    let method = class
+        .methods
+        .iter()
+        .find(|method| method.name == "annotatedMethod")
+        .expect("should find method annotatedMethod");
+    let descriptors: Vec<(&str, bool)> = method
+        .annotations
+        .iter()
+        .map(|annotation| {
+            (
+                annotation.type_descriptor.as_str(),
+                annotation.runtime_visible,
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            ("Ljava/lang/Deprecated;", true),
+            ("Lrjvm/Annotations$Nested;", false),
+        ],
+        descriptors
+    );
+}
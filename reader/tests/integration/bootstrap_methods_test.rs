@@ -0,0 +1,60 @@
+extern crate rjvm_reader;
+
+use rjvm_reader::constant_pool::ConstantPoolEntry;
+
+use crate::utils;
+use utils::read_class_from_bytes;
+
+#[test_log::test]
+fn can_read_bootstrap_methods_attribute() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/BootstrapMethods.class"));
+    assert_eq!(2, class.bootstrap_methods.len());
+
+    let lambda_bodies: Vec<String> = class
+        .bootstrap_methods
+        .iter()
+        .map(|bootstrap_method| {
+            assert!(matches!(
+                class.constants.get(bootstrap_method.method_ref),
+                Ok(ConstantPoolEntry::MethodHandle(6, _))
+            ));
+            assert_eq!(
+                "java/lang/invoke/LambdaMetafactory.metafactory: (Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;Ljava/lang/invoke/MethodType;Ljava/lang/invoke/MethodHandle;Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/CallSite;",
+                class.constants.text_of(bootstrap_method.method_ref).unwrap()
+            );
+
+            // The metafactory gets the erased signature, the implementation, and the
+            // instantiated signature of the lambda
+            assert_eq!(3, bootstrap_method.arguments.len());
+            class
+                .constants
+                .text_of(bootstrap_method.arguments[1])
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            "rjvm/BootstrapMethods.lambda$greeting$0: ()Ljava/lang/String;",
+            "rjvm/BootstrapMethods.lambda$constant$1: (I)I",
+        ],
+        lambda_bodies
+    );
+
+    // Each invokedynamic constant refers to its bootstrap method by index
+    let invoke_dynamic_texts: Vec<String> = (1..100)
+        .filter(|index| {
+            matches!(
+                class.constants.get(*index),
+                Ok(ConstantPoolEntry::InvokeDynamic(_, _))
+            )
+        })
+        .map(|index| class.constants.text_of(index).unwrap())
+        .collect();
+    assert_eq!(
+        vec![
+            "#0:get: ()Ljava/util/function/Supplier;",
+            "#1:getAsInt: (I)Ljava/util/function/IntSupplier;",
+        ],
+        invoke_dynamic_texts
+    );
+}
@@ -0,0 +1,72 @@
+extern crate rjvm_reader;
+
+use rjvm_reader::{
+    class_file::ClassFile, control_flow_graph::ControlFlowGraph, program_counter::ProgramCounter,
+};
+
+use crate::utils;
+use utils::read_class_from_bytes;
+
+fn control_flow_graph_of(class: &ClassFile, method_name: &str) -> ControlFlowGraph {
+    class
+        .methods
+        .iter()
+        .find(|m| m.name == method_name)
+        .expect("should find method")
+        .code
+        .as_ref()
+        .expect("method should have code")
+        .control_flow_graph()
+        .expect("should be able to build the control flow graph")
+}
+
+#[test_log::test]
+fn can_build_control_flow_graph_of_loop() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/ControlFlow.class"));
+    let graph = control_flow_graph_of(&class, "sum");
+
+    // Initialization, loop condition, loop body, return
+    assert_eq!(4, graph.blocks.len());
+    assert_eq!(
+        vec![
+            ProgramCounter(0),
+            ProgramCounter(4),
+            ProgramCounter(9),
+            ProgramCounter(19)
+        ],
+        graph.blocks.iter().map(|b| b.start).collect::<Vec<_>>()
+    );
+    assert_eq!(vec![1], graph.blocks[0].successors);
+    assert_eq!(vec![3, 2], graph.blocks[1].successors);
+    assert_eq!(vec![1], graph.blocks[2].successors);
+    assert!(graph.blocks[3].successors.is_empty());
+
+    // The goto at the end of the body jumps back to the condition
+    assert_eq!(vec![(2, 1)], graph.back_edges());
+    assert_eq!(Some(2), graph.block_containing(ProgramCounter(16)));
+}
+
+#[test_log::test]
+fn control_flow_graph_includes_exception_handlers() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/ControlFlow.class"));
+    let graph = control_flow_graph_of(&class, "parse");
+
+    // The try block, which can continue or jump to the handler, the return that is
+    // not covered by the handler, and the catch block
+    assert_eq!(3, graph.blocks.len());
+    assert_eq!(vec![1, 2], graph.blocks[0].successors);
+    assert!(graph.blocks[1].successors.is_empty());
+    assert_eq!(ProgramCounter(5), graph.blocks[2].start);
+    assert!(graph.blocks[2].successors.is_empty());
+    assert!(graph.back_edges().is_empty());
+}
+
+#[test_log::test]
+fn switches_end_blocks_with_an_edge_to_each_target() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/ControlFlow.class"));
+    let graph = control_flow_graph_of(&class, "dense");
+
+    // The switch, one block per case, and the default
+    assert_eq!(5, graph.blocks.len());
+    assert_eq!(vec![1, 2, 3, 4], graph.blocks[0].successors);
+}
@@ -0,0 +1,165 @@
+extern crate rjvm_reader;
+
+use rjvm_reader::{
+    class_file::ClassFile,
+    constant_pool::ConstantPoolEntry,
+    instruction::{DecodedInstruction, Instruction, LookupSwitch, Operand, TableSwitch},
+    program_counter::ProgramCounter,
+};
+
+use crate::utils;
+use utils::read_class_from_bytes;
+
+fn instructions_of(class: &ClassFile, method_name: &str) -> Vec<DecodedInstruction> {
+    class
+        .methods
+        .iter()
+        .find(|m| m.name == method_name)
+        .expect("should find method")
+        .code
+        .as_ref()
+        .expect("method should have code")
+        .instructions()
+        .expect("should be able to decode the instructions")
+}
+
+#[test_log::test]
+fn can_decode_instructions_with_their_address() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/ControlFlow.class"));
+    let instructions = instructions_of(&class, "sum");
+
+    let expected = vec![
+        (0, Instruction::Iconst_0),
+        (1, Instruction::Istore_1),
+        (2, Instruction::Iconst_0),
+        (3, Instruction::Istore_2),
+        (4, Instruction::Iload_2),
+        (5, Instruction::Iload_0),
+        (6, Instruction::If_icmpge(19)),
+        (9, Instruction::Iload_1),
+        (10, Instruction::Iload_2),
+        (11, Instruction::Iadd),
+        (12, Instruction::Istore_1),
+        (13, Instruction::Iinc(2, 1)),
+        (16, Instruction::Goto(4)),
+        (19, Instruction::Iload_1),
+        (20, Instruction::Ireturn),
+    ]
+    .into_iter()
+    .map(|(pc, instruction)| DecodedInstruction {
+        pc: ProgramCounter(pc),
+        instruction,
+    })
+    .collect::<Vec<_>>();
+    assert_eq!(expected, instructions);
+
+    assert!(instructions[0].operands().is_empty());
+    assert_eq!(
+        vec![Operand::JumpTarget(ProgramCounter(19))],
+        instructions[6].operands()
+    );
+    assert_eq!(
+        vec![Operand::LocalVariable(2), Operand::Immediate(1)],
+        instructions[11].operands()
+    );
+}
+
+#[test_log::test]
+fn operands_refer_to_the_constant_pool() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/ControlFlow.class"));
+    let instructions = instructions_of(&class, "parse");
+
+    let operands = instructions
+        .iter()
+        .find(|decoded| matches!(decoded.instruction, Instruction::Invokestatic(_)))
+        .expect("should invoke a static method")
+        .operands();
+    let [Operand::ConstantPoolIndex(index)] = operands[..] else {
+        panic!("invokestatic should refer to a constant, got {operands:?}");
+    };
+    assert!(matches!(
+        class.constants.get(index),
+        Ok(ConstantPoolEntry::MethodReference(..))
+    ));
+    assert_eq!(
+        "java/lang/Integer.parseInt: (Ljava/lang/String;)I",
+        class.constants.text_of(index).unwrap()
+    );
+}
+
+#[test_log::test]
+fn can_decode_switches() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/ControlFlow.class"));
+
+    // The operands are aligned to 4 bytes, so the instruction after the switch at
+    // address 1 starts at 4 + 4 * 3 + 4 * 3 = 28
+    let table = TableSwitch {
+        default: 37,
+        low: 1,
+        high: 3,
+        targets: vec![28, 31, 34],
+    };
+    let instructions = instructions_of(&class, "dense");
+    assert_eq!(
+        Instruction::Tableswitch(table.clone()),
+        instructions[1].instruction
+    );
+    assert_eq!(ProgramCounter(28), instructions[2].pc);
+    assert_eq!(
+        vec![
+            Operand::JumpTarget(ProgramCounter(37)),
+            Operand::Immediate(1),
+            Operand::Immediate(3),
+            Operand::JumpTarget(ProgramCounter(28)),
+            Operand::JumpTarget(ProgramCounter(31)),
+            Operand::JumpTarget(ProgramCounter(34)),
+        ],
+        instructions[1].operands()
+    );
+    assert_eq!(31, table.target(2));
+    assert_eq!(37, table.target(0));
+    assert_eq!(37, table.target(i32::MIN));
+
+    let lookup = LookupSwitch {
+        default: 32,
+        pairs: vec![(-100, 28), (1000, 30)],
+    };
+    let instructions = instructions_of(&class, "sparse");
+    assert_eq!(
+        Instruction::Lookupswitch(lookup.clone()),
+        instructions[1].instruction
+    );
+    assert_eq!(28, lookup.target(-100));
+    assert_eq!(32, lookup.target(999));
+}
+
+#[test_log::test]
+fn can_decode_table_switch_up_to_the_maximum_key() {
+    // tableswitch at address 0, padded to 4, with default 8, low and high i32::MAX,
+    // and a single target 12
+    let raw_code = [
+        0xaa, 0, 0, 0, 0, 0, 0, 8, 0x7f, 0xff, 0xff, 0xff, 0x7f, 0xff, 0xff, 0xff, 0, 0, 0, 12,
+    ];
+    let (instruction, next_address) = Instruction::parse(&raw_code, 0).unwrap();
+    assert_eq!(20, next_address);
+    assert_eq!(
+        vec![
+            Operand::JumpTarget(ProgramCounter(8)),
+            Operand::Immediate(i32::MAX),
+            Operand::Immediate(i32::MAX),
+            Operand::JumpTarget(ProgramCounter(12)),
+        ],
+        instruction.operands()
+    );
+}
+
+#[test_log::test]
+fn invalid_switch_target_reports_the_address_of_the_switch() {
+    // nop, then lookupswitch at address 1 with default offset -2 and no pairs
+    let raw_code = [0x00, 0xab, 0, 0, 0xff, 0xff, 0xff, 0xfe, 0, 0, 0, 0];
+    let error = Instruction::parse(&raw_code, 1).unwrap_err();
+    assert_eq!(
+        "invalid class file: invalid jump offset at address 1",
+        error.to_string()
+    );
+}
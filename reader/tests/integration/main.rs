@@ -1,6 +1,11 @@
+mod annotations_test;
 mod assertions;
+mod bootstrap_methods_test;
 mod constants_class_test;
+mod control_flow_graph_test;
 mod deprecated_class_test;
 mod exceptions;
+mod instructions_test;
+mod method_parameters_test;
 mod pojo_class_test;
 mod utils;
@@ -0,0 +1,48 @@
+extern crate rjvm_reader;
+
+use rjvm_reader::method_parameter::{MethodParameter, MethodParameterFlags};
+
+use crate::utils;
+use utils::read_class_from_bytes;
+
+#[test_log::test]
+fn can_read_method_parameters_attribute() {
+    let class = read_class_from_bytes(include_bytes!("../resources/rjvm/MethodParameters.class"));
+    let sum = class
+        .methods
+        .iter()
+        .find(|method| method.name == "sum")
+        .expect("should find method sum");
+    assert_eq!(
+        vec![
+            MethodParameter {
+                name: Some("first".to_string()),
+                flags: MethodParameterFlags::FINAL,
+            },
+            MethodParameter {
+                name: Some("second".to_string()),
+                flags: MethodParameterFlags::empty(),
+            },
+        ],
+        sum.parameters
+    );
+}
+
+#[test_log::test]
+fn can_read_mandated_parameters() {
+    let class = read_class_from_bytes(include_bytes!(
+        "../resources/rjvm/MethodParameters$Inner.class"
+    ));
+    let constructor = class
+        .methods
+        .iter()
+        .find(|method| method.name == "<init>")
+        .expect("should find constructor");
+    assert_eq!(
+        vec![MethodParameter {
+            name: Some("this$0".to_string()),
+            flags: MethodParameterFlags::FINAL | MethodParameterFlags::MANDATED,
+        }],
+        constructor.parameters
+    );
+}
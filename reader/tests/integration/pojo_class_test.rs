@@ -48,6 +48,7 @@ fn check_fields(class: &ClassFile) {
                 type_descriptor: FieldType::Base(BaseType::Double),
                 constant_value: None,
                 deprecated: false,
+                annotations: vec![],
             },
             ClassFileField {
                 flags: FieldFlags::PRIVATE | FieldFlags::FINAL,
@@ -55,6 +56,7 @@ fn check_fields(class: &ClassFile) {
                 type_descriptor: FieldType::Base(BaseType::Double),
                 constant_value: None,
                 deprecated: false,
+                annotations: vec![],
             }
         ),
         class.fields
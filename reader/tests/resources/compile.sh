@@ -1,2 +1,6 @@
 #!/usr/bin/env sh
-javac -source 6 -target 6 rjvm/*.java
+javac -source 6 -target 6 $(ls rjvm/*.java | grep -v -e BootstrapMethods -e MethodParameters)
+# Lambdas require java 8
+javac --release 8 rjvm/BootstrapMethods.java
+# The MethodParameters attribute is generated only with -parameters, since java 8
+javac --release 8 -parameters rjvm/MethodParameters.java
@@ -0,0 +1,41 @@
+package rjvm;
+
+import java.lang.annotation.ElementType;
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+
+@Annotations.Info(
+        name = "annotated",
+        numbers = {1, 2},
+        targets = {ElementType.TYPE, ElementType.METHOD},
+        type = String.class,
+        nested = @Annotations.Nested("single"),
+        all = {@Annotations.Nested("first"), @Annotations.Nested("second")})
+public class Annotations {
+    @Retention(RetentionPolicy.RUNTIME)
+    public @interface Info {
+        String name();
+
+        int[] numbers();
+
+        ElementType[] targets();
+
+        Class<?> type();
+
+        Nested nested();
+
+        Nested[] all();
+
+        char letter() default 'x';
+    }
+
+    // Retained only in the class file, i.e. not visible at runtime
+    public @interface Nested {
+        String value();
+    }
+
+    @Deprecated
+    @Nested("method")
+    public void annotatedMethod() {
+    }
+}
@@ -0,0 +1,14 @@
+package rjvm;
+
+import java.util.function.IntSupplier;
+import java.util.function.Supplier;
+
+public class BootstrapMethods {
+    public static Supplier<String> greeting() {
+        return () -> "hello";
+    }
+
+    public static IntSupplier constant(int value) {
+        return () -> value;
+    }
+}
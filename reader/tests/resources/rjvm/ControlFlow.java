@@ -0,0 +1,43 @@
+package rjvm;
+
+public class ControlFlow {
+    public static int sum(int n) {
+        int total = 0;
+        for (int i = 0; i < n; i++) {
+            total += i;
+        }
+        return total;
+    }
+
+    public static int parse(String s) {
+        try {
+            return Integer.parseInt(s);
+        } catch (NumberFormatException e) {
+            return -1;
+        }
+    }
+
+    public static int dense(int n) {
+        switch (n) {
+            case 1:
+                return 10;
+            case 2:
+                return 20;
+            case 3:
+                return 30;
+            default:
+                return 0;
+        }
+    }
+
+    public static int sparse(int n) {
+        switch (n) {
+            case -100:
+                return 1;
+            case 1000:
+                return 2;
+            default:
+                return 3;
+        }
+    }
+}
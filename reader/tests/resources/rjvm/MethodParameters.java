@@ -0,0 +1,10 @@
+package rjvm;
+
+public class MethodParameters {
+    public static int sum(final int first, int second) {
+        return first + second;
+    }
+
+    public class Inner {
+    }
+}
@@ -88,7 +88,7 @@ pub(crate) struct AllocHeader {
     pub(crate) state: GcState,
 
     #[bits(30)]
-    identity_hash_code: i32,
+    pub(crate) identity_hash_code: i32,
 
     #[bits(32)]
     pub(crate) size: usize,
@@ -113,6 +113,9 @@ const fn align_to_8_bytes(required_size: usize) -> usize {
     }
 }
 
+/// The identity hash codes are stored in 30 bits of the [AllocHeader]
+pub(crate) const MAX_IDENTITY_HASH_CODE: i32 = (1 << 30) - 1;
+
 pub(crate) const ALLOC_HEADER_SIZE: usize = align_to_8_bytes(size_of::<AllocHeader>());
 pub(crate) const OBJECT_HEADER_SIZE: usize = align_to_8_bytes(size_of::<ObjectHeader>());
 pub(crate) const ARRAY_HEADER_SIZE: usize = align_to_8_bytes(size_of::<ArrayHeader>());
@@ -187,7 +190,7 @@ impl<'a> AbstractObject<'a> {
             AllocHeader::new()
                 .with_kind(kind)
                 .with_state(GcState::Unmarked)
-                .with_identity_hash_code(identity_hash_code(alloc_entry.ptr))
+                .with_identity_hash_code(alloc_entry.identity_hash_code)
                 .with_size(alloc_entry.alloc_size),
         );
         next_ptr.add(1) as *mut u8
@@ -243,20 +246,6 @@ impl<'a> Debug for AbstractObject<'a> {
     }
 }
 
-fn hash(data: u64) -> u64 {
-    (data >> 32) ^ (data)
-}
-
-fn identity_hash_code(ptr: *mut u8) -> i32 {
-    let hash = hash(ptr as u64);
-
-    // Note: we'll take some of the least significant bits here,
-    // since we'll store this in AllocHeader!
-    let hash = (hash & ((1 << 30) - 1)) as u32;
-
-    unsafe { std::mem::transmute(hash) }
-}
-
 unsafe fn write_value(ptr: *mut u8, value: Value) {
     match value {
         Value::Int(int) => std::ptr::write(ptr as *mut i32, int),
@@ -296,7 +285,7 @@ unsafe fn read_value2<'a>(ptr: *const u8, field_type: &ArrayEntryType) -> Value<
         ArrayEntryType::Base(BaseType::Long) => Value::Long(std::ptr::read(ptr as *const i64)),
         ArrayEntryType::Base(BaseType::Float) => Value::Float(std::ptr::read(ptr as *const f32)),
         ArrayEntryType::Base(BaseType::Double) => Value::Double(std::ptr::read(ptr as *const f64)),
-        ArrayEntryType::Object(_) | ArrayEntryType::Array => {
+        ArrayEntryType::Object(_) | ArrayEntryType::Array(..) => {
             match std::ptr::read(ptr as *const i64) {
                 0 => Value::Null,
                 _ => Value::Object(std::ptr::read(ptr as *const AbstractObject)),
@@ -2,4 +2,5 @@
 pub struct AllocEntry {
     pub(crate) ptr: *mut u8,
     pub(crate) alloc_size: usize,
+    pub(crate) identity_hash_code: i32,
 }
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use crate::{abstract_object::AbstractObject, class_and_method::ClassAndMethod};
+
+/// Where an object has been allocated, see [crate::vm::Vm::set_leak_diagnosis].
+/// Objects allocated by native methods are attributed to the instruction invoking them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AllocationSite {
+    /// The description of the method, such as `rjvm.Foo.bar(I)V`
+    pub method: String,
+    /// The program counter of the allocating instruction
+    pub pc: u16,
+}
+
+/// The live objects allocated by one [AllocationSite]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LiveObjects {
+    pub objects: usize,
+    pub bytes: usize,
+}
+
+/// The method and program counter of an allocating instruction
+type Site<'a> = (ClassAndMethod<'a>, u16);
+
+/// Tracks the allocation site of the objects. They are referenced weakly: the garbage
+/// collector updates the references when it moves the objects, and clears them when it
+/// collects the objects.
+#[derive(Debug, Default)]
+pub(crate) struct AllocationSites<'a> {
+    /// The instruction being executed by the innermost bytecode method
+    current: Option<Site<'a>>,
+    objects: Vec<(Option<AbstractObject<'a>>, Site<'a>)>,
+}
+
+impl<'a> AllocationSites<'a> {
+    pub(crate) fn set_current(&mut self, class_and_method: &ClassAndMethod<'a>, pc: u16) {
+        self.current = Some((class_and_method.clone(), pc));
+    }
+
+    /// Returns the current site, to restore it with [AllocationSites::restore_current]
+    pub(crate) fn current(&self) -> Option<Site<'a>> {
+        self.current.clone()
+    }
+
+    pub(crate) fn restore_current(&mut self, current: Option<Site<'a>>) {
+        self.current = current;
+    }
+
+    pub(crate) fn record(&mut self, object: &AbstractObject<'a>) {
+        if let Some(current) = &self.current {
+            self.objects.push((Some(object.clone()), current.clone()));
+        }
+    }
+
+    /// The weak references to the objects, to pass to the garbage collector
+    pub(crate) fn weak_roots(&mut self) -> Vec<*mut Option<AbstractObject<'a>>> {
+        self.objects
+            .iter_mut()
+            .map(|(object, _)| object as *mut Option<AbstractObject<'a>>)
+            .collect()
+    }
+
+    /// Forgets the objects cleared by the garbage collector
+    pub(crate) fn remove_collected(&mut self) {
+        self.objects.retain(|(object, _)| object.is_some());
+    }
+
+    /// Groups the objects that have not been collected by the site that allocated them
+    pub(crate) fn live_objects(&self) -> HashMap<AllocationSite, LiveObjects> {
+        let mut live_objects: HashMap<AllocationSite, LiveObjects> = HashMap::new();
+        for (object, (class_and_method, pc)) in self.objects.iter() {
+            let Some(object) = object else {
+                continue;
+            };
+            let site = AllocationSite {
+                method: class_and_method.description(),
+                pc: *pc,
+            };
+            let entry = live_objects.entry(site).or_default();
+            entry.objects += 1;
+            entry.bytes += object.alloc_size();
+        }
+        live_objects
+    }
+}
@@ -12,12 +12,53 @@ use crate::{class::ClassId, class_resolver_by_id::ClassByIdResolver};
 pub enum ArrayEntryType {
     Base(BaseType),
     Object(ClassId),
-    // Note: here we would have to keep the sub-element type. Not doing this means that we do not
-    // correctly support arrays of arrays!
-    Array,
+    /// The entries are arrays themselves, with the given number of dimensions and type of
+    /// their innermost elements. For example, the entries of a `String[][][]` are modelled
+    /// as `Array(2, ArrayLeafType::Object(<id of String>))`.
+    Array(u8, ArrayLeafType),
+}
+
+/// The type of the innermost elements of a multidimensional array
+#[derive(PartialEq, Clone, Debug)]
+#[repr(u8)]
+pub enum ArrayLeafType {
+    Base(BaseType),
+    Object(ClassId),
 }
 
 impl ArrayEntryType {
+    /// Returns the type of the entries of an array whose entries have this type, or `None`
+    /// if it would exceed the 255 dimensions allowed by the JVM
+    pub fn array_of(self) -> Option<Self> {
+        Some(match self {
+            ArrayEntryType::Base(base_type) => {
+                ArrayEntryType::Array(1, ArrayLeafType::Base(base_type))
+            }
+            ArrayEntryType::Object(class_id) => {
+                ArrayEntryType::Array(1, ArrayLeafType::Object(class_id))
+            }
+            ArrayEntryType::Array(dimensions, leaf_type) => {
+                ArrayEntryType::Array(dimensions.checked_add(1)?, leaf_type)
+            }
+        })
+    }
+
+    /// For entries that are arrays, returns the type of their own entries
+    pub fn component_type(&self) -> Option<Self> {
+        match self {
+            ArrayEntryType::Array(1, ArrayLeafType::Base(base_type)) => {
+                Some(ArrayEntryType::Base(base_type.clone()))
+            }
+            ArrayEntryType::Array(1, ArrayLeafType::Object(class_id)) => {
+                Some(ArrayEntryType::Object(*class_id))
+            }
+            ArrayEntryType::Array(dimensions, leaf_type) => {
+                Some(ArrayEntryType::Array(dimensions - 1, leaf_type.clone()))
+            }
+            _ => None,
+        }
+    }
+
     pub fn into_field_type<'a>(
         self,
         class_resolver: &impl ClassByIdResolver<'a>,
@@ -27,9 +68,38 @@ impl ArrayEntryType {
             ArrayEntryType::Object(class_id) => class_resolver
                 .find_class_by_id(class_id)
                 .map(|class| FieldType::Object(class.name.clone())),
-            ArrayEntryType::Array => {
-                todo!("Arrays of arrays are not supported at the moment")
+            ArrayEntryType::Array(..) => self
+                .component_type()?
+                .into_field_type(class_resolver)
+                .map(|component_type| FieldType::Array(Box::new(component_type))),
+        }
+    }
+
+    /// Returns whether an entry of this type can be assigned to a variable of the given type.
+    /// Arrays of references are covariant, i.e. a `String[]` is also an `Object[]`, and every
+    /// array extends Object and implements Cloneable and Serializable.
+    pub fn is_assignable_to<'a>(
+        &self,
+        class_resolver: &impl ClassByIdResolver<'a>,
+        field_type: &FieldType,
+    ) -> bool {
+        match (self, field_type) {
+            (ArrayEntryType::Base(base_type), FieldType::Base(expected)) => base_type == expected,
+            (ArrayEntryType::Object(class_id), FieldType::Object(expected_class_name)) => {
+                class_resolver
+                    .find_class_by_id(*class_id)
+                    .is_some_and(|class| class.is_subclass_of_class_named(expected_class_name))
+            }
+            (ArrayEntryType::Array(..), FieldType::Object(expected_class_name)) => matches!(
+                expected_class_name.as_str(),
+                "java/lang/Object" | "java/lang/Cloneable" | "java/io/Serializable"
+            ),
+            (ArrayEntryType::Array(..), FieldType::Array(expected_component_type)) => {
+                self.component_type().is_some_and(|component_type| {
+                    component_type.is_assignable_to(class_resolver, expected_component_type)
+                })
             }
+            _ => false,
         }
     }
 }
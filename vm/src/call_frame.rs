@@ -1,12 +1,16 @@
+use std::cmp::Ordering;
+
 use log::{debug, warn};
 
 use rjvm_reader::{
+    class_access_flags::ClassAccessFlags,
     class_file_field::ClassFileField,
     class_file_method::ClassFileMethod,
     constant_pool::ConstantPoolEntry,
     field_type::{BaseType, FieldType, FieldType::Base},
-    instruction::{Instruction, NewArrayType},
+    instruction::{Instruction, NewArrayType, WideInstruction},
     line_number::LineNumber,
+    method_flags::MethodFlags,
     program_counter::ProgramCounter,
     type_conversion::ToUsizeSafe,
 };
@@ -17,11 +21,11 @@ use crate::{
     array_entry_type::ArrayEntryType,
     call_frame::InstructionCompleted::{ContinueMethodExecution, ReturnFromMethod},
     call_stack::CallStack,
-    class::Class,
+    class::{Class, ClassRef},
     class_and_method::ClassAndMethod,
-    class_resolver_by_id::ClassByIdResolver,
+    class_resolver_by_id::{binary_class_name, ClassByIdResolver},
+    decoded_code::DecodedCode,
     exceptions::{JavaException, MethodCallFailed},
-    java_objects_creation::{new_java_lang_class_object, new_java_lang_string_object},
     object::Object,
     stack_trace_element::StackTraceElement,
     value::{
@@ -49,7 +53,6 @@ struct MethodReference<'a> {
 struct FieldReference<'a> {
     class_name: &'a str,
     field_name: &'a str,
-    #[allow(dead_code)]
     type_descriptor: &'a str,
 }
 
@@ -70,6 +73,9 @@ pub struct CallFrame<'a> {
 
     /// The bytecode to execute
     code: &'a Vec<u8>,
+
+    /// The instructions of the bytecode, decoded as they are executed
+    decoded_code: &'a DecodedCode,
 }
 
 /// One of the possible invocation kind of methods in the JVM.
@@ -143,12 +149,32 @@ macro_rules! generate_execute_math {
     };
 }
 
+/// Integer division or remainder: as for the other arithmetic instructions, but it
+/// throws a `java.lang.ArithmeticException` if the divisor is zero
+macro_rules! generate_execute_division {
+    ($name:ident, $pop_fn:ident, $variant:ident, $type:ty) => {
+        fn $name(
+            &mut self,
+            vm: &mut Vm<'a>,
+            call_stack: &mut CallStack<'a>,
+            evaluator: fn($type, $type) -> $type,
+        ) -> Result<(), MethodCallFailed<'a>> {
+            let val2 = self.$pop_fn()?;
+            let val1 = self.$pop_fn()?;
+            if val2 == 0 {
+                return Err(vm.throw_arithmetic(call_stack, "/ by zero"));
+            }
+            self.push($variant(evaluator(val1, val2)))
+        }
+    };
+}
+
 /// Pops a number, negates it, and pushes the negated value
 macro_rules! generate_execute_neg {
-    ($name:ident, $pop_fn:ident, $variant:ident) => {
+    ($name:ident, $pop_fn:ident, $variant:ident, $negate:expr) => {
         fn $name(&mut self) -> Result<(), MethodCallFailed<'a>> {
             let value = self.$pop_fn()?;
-            self.push($variant(-value))
+            self.push($variant($negate(value)))
         }
     };
 }
@@ -167,19 +193,21 @@ macro_rules! generate_execute_coerce {
     };
 }
 
-/// Pops two values, compares them, and pushes the result (+1, -1, or zero)
+/// Pops two values, compares them, and pushes the result (+1, -1, or zero).
+/// Values are compared directly rather than subtracted, since that could overflow.
+/// If they are not ordered, i.e. one of them is NaN, pushes `unordered_result`.
 macro_rules! generate_compare {
     ($name:ident, $pop_fn:ident) => {
-        fn $name(&mut self, sign_for_greater: i32) -> Result<(), MethodCallFailed<'a>> {
+        fn $name(&mut self, unordered_result: i32) -> Result<(), MethodCallFailed<'a>> {
             let val2 = self.$pop_fn()?;
             let val1 = self.$pop_fn()?;
-            if val1 > val2 {
-                self.push(Int(sign_for_greater))
-            } else if val1 < val2 {
-                self.push(Int(-sign_for_greater))
-            } else {
-                self.push(Int(0))
-            }
+            let result = match val1.partial_cmp(&val2) {
+                Some(Ordering::Greater) => 1,
+                Some(Ordering::Less) => -1,
+                Some(Ordering::Equal) => 0,
+                None => unordered_result,
+            };
+            self.push(Int(result))
         }
     };
 }
@@ -205,10 +233,7 @@ macro_rules! generate_execute_store {
         fn $name(&mut self, index: usize) -> Result<(), MethodCallFailed<'a>> {
             let value = self.pop()?;
             match value {
-                $variant(..) => {
-                    self.locals[index] = value;
-                    Ok(())
-                }
+                $variant(..) => Ok(self.set_local(index, value)?),
                 _ => Err(MethodCallFailed::InternalError(
                     VmError::ValidationException,
                 )),
@@ -220,9 +245,12 @@ macro_rules! generate_execute_store {
 /// Pops the index and the array and pushes the element at the index
 macro_rules! generate_execute_array_load {
     ($name:ident, $($variant:pat),+) => {
-        fn $name(&mut self) -> Result<(), MethodCallFailed<'a>> {
-            let index = self.pop_int()?.into_usize_safe();
-            let array = self.pop_array()?;
+        fn $name(
+            &mut self,
+            vm: &mut Vm<'a>,
+            call_stack: &mut CallStack<'a>,
+        ) -> Result<(), MethodCallFailed<'a>> {
+            let (array, index) = self.pop_array_and_index(vm, call_stack)?;
             let value = match array.elements_type() {
                 $($variant => {
                     array.get_element(index)
@@ -237,10 +265,13 @@ macro_rules! generate_execute_array_load {
 /// Pops the value, the index, and the array, and sets the element at the index
 macro_rules! generate_execute_array_store {
     ($name:ident, $pop_fn:ident, $map_fn:ident, $($variant:pat),+) => {
-        fn $name(&mut self) -> Result<(), MethodCallFailed<'a>> {
+        fn $name(
+            &mut self,
+            vm: &mut Vm<'a>,
+            call_stack: &mut CallStack<'a>,
+        ) -> Result<(), MethodCallFailed<'a>> {
             let value = Self::$map_fn(self.$pop_fn()?);
-            let index = self.pop_int()?.into_usize_safe();
-            let array = self.pop_array()?;
+            let (array, index) = self.pop_array_and_index(vm, call_stack)?;
             match array.elements_type() {
                 $($variant => {
                      array.set_element(index, value)?
@@ -267,12 +298,17 @@ impl<'a> CallFrame<'a> {
             .as_ref()
             .expect("method is not native")
             .code;
+        let decoded_code = class_and_method
+            .class
+            .decoded_code(class_and_method.method)
+            .expect("method should be one of its class");
         CallFrame {
             class_and_method,
             pc: ProgramCounter(0),
             locals,
             stack: ValueStack::with_max_size(max_stack_size),
             code,
+            decoded_code,
         }
     }
 
@@ -302,32 +338,58 @@ impl<'a> CallFrame<'a> {
     ) -> MethodCallResult<'a> {
         self.debug_start_execution();
 
+        // Counted locally and recorded once, so that the profile needs no lookup per instruction
+        let mut executed_instructions = 0;
+        let result = self.execute_instructions(vm, call_stack, &mut executed_instructions);
+        vm.record_executed_instructions(&self.class_and_method, executed_instructions);
+        result
+    }
+
+    fn execute_instructions(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        executed_instructions: &mut u64,
+    ) -> MethodCallResult<'a> {
         loop {
             let executed_instruction_pc = self.pc;
-            let (instruction, new_address) =
-                Instruction::parse(self.code, executed_instruction_pc.0.into_usize_safe())
-                    .map_err(|_| MethodCallFailed::InternalError(VmError::ValidationException))?;
-            self.debug_print_status(&instruction);
+            let (instruction, new_address) = self
+                .decoded_code
+                .instruction_at(self.code, executed_instruction_pc.0.into_usize_safe())
+                .ok_or(MethodCallFailed::InternalError(
+                    VmError::ValidationException,
+                ))?;
+            self.debug_print_status(instruction);
+            vm.execution_counters.instructions_executed += 1;
+            *executed_instructions += 1;
+            vm.set_current_allocation_site(&self.class_and_method, executed_instruction_pc.0);
 
             // Move pc to the next instruction, _before_ executing it, since we want a "goto" to override this
-            self.pc = ProgramCounter(new_address as u16);
-
-            let instruction_result = self.execute_instruction(vm, call_stack, instruction);
+            self.pc = ProgramCounter(*new_address as u16);
+
+            let instruction_result = match Self::invoke_kind(instruction) {
+                Some((constant_index, kind)) => self
+                    .invoke_method(vm, call_stack, constant_index, kind)
+                    .map(|_| ContinueMethodExecution),
+                None => self.execute_instruction(vm, call_stack, instruction),
+            };
             match instruction_result {
                 Ok(ReturnFromMethod(return_value)) => return Ok(return_value),
                 Ok(ContinueMethodExecution) => { /* continue the loop */ }
 
+                Err(MethodCallFailed::InternalError(VmError::ValueStackError(err))) => {
+                    return Err(MethodCallFailed::InternalError(VmError::VerifyError(
+                        err,
+                        executed_instruction_pc,
+                    )))
+                }
                 Err(MethodCallFailed::InternalError(err)) => {
                     return Err(MethodCallFailed::InternalError(err))
                 }
 
                 Err(MethodCallFailed::ExceptionThrown(exception)) => {
-                    let exception_handler = self.find_exception_handler(
-                        vm,
-                        call_stack,
-                        executed_instruction_pc,
-                        &exception,
-                    );
+                    let exception_handler =
+                        self.find_exception_handler(vm, executed_instruction_pc, &exception);
                     match exception_handler {
                         Err(err) => return Err(err),
                         Ok(None) => {
@@ -335,7 +397,10 @@ impl<'a> CallFrame<'a> {
                             return Err(MethodCallFailed::ExceptionThrown(exception));
                         }
                         Ok(Some(catch_handler_pc)) => {
-                            // Re-push exception on the stack and continue execution of this method from the catch handler
+                            // The operand stack is discarded, so that values such as an object
+                            // whose constructor has thrown do not remain reachable. Then we
+                            // push the exception and continue execution from the catch handler
+                            self.stack.truncate(0)?;
                             self.stack.push(Value::Object(exception.0))?;
                             self.pc = catch_handler_pc
                         }
@@ -345,14 +410,32 @@ impl<'a> CallFrame<'a> {
         }
     }
 
+    // Invocations are not executed by execute_instruction: its frame is huge in debug builds,
+    // and keeping it alive while the callee runs would exhaust the native stack quickly
+    fn invoke_kind(instruction: &Instruction) -> Option<(u16, InvokeKind)> {
+        match *instruction {
+            Instruction::Invokespecial(constant_index) => {
+                Some((constant_index, InvokeKind::Special))
+            }
+            Instruction::Invokestatic(constant_index) => Some((constant_index, InvokeKind::Static)),
+            Instruction::Invokevirtual(constant_index) => {
+                Some((constant_index, InvokeKind::Virtual))
+            }
+            Instruction::Invokeinterface(constant_index, _) => {
+                Some((constant_index, InvokeKind::Interface))
+            }
+            _ => None,
+        }
+    }
+
     // Reference: https://docs.oracle.com/javase/specs/jvms/se7/html/jvms-6.html
     fn execute_instruction(
         &mut self,
         vm: &mut Vm<'a>,
         call_stack: &mut CallStack<'a>,
-        instruction: Instruction,
+        instruction: &Instruction,
     ) -> Result<InstructionCompleted<'a>, MethodCallFailed<'a>> {
-        match instruction {
+        match *instruction {
             Instruction::Aconst_null => self.push(Null)?,
             Instruction::Aload(index) => self.execute_aload(index.into_usize_safe())?,
             Instruction::Aload_0 => self.execute_aload(0)?,
@@ -455,11 +538,7 @@ impl<'a> CallFrame<'a> {
             Instruction::D2l => self.coerce_double(Self::d2l)?,
             Instruction::D2f => self.coerce_double(Self::d2f)?,
 
-            Instruction::New(constant_index) => {
-                let new_object_class_name = self.get_constant_class_reference(constant_index)?;
-                let new_object = vm.new_object(call_stack, new_object_class_name)?;
-                self.push(Value::Object(new_object))?;
-            }
+            Instruction::New(constant_index) => self.execute_new(vm, call_stack, constant_index)?,
 
             Instruction::Dup => self.stack.dup()?,
             Instruction::Dup_x1 => self.stack.dup_x1()?,
@@ -471,22 +550,9 @@ impl<'a> CallFrame<'a> {
             Instruction::Pop2 => self.stack.pop2().map(|_| ())?,
             Instruction::Swap => self.stack.swap()?,
 
-            Instruction::Bipush(byte_value) => self.push(Int(byte_value as i32))?,
+            Instruction::Bipush(byte_value) => self.push(Int(byte_value as i8 as i32))?,
             Instruction::Sipush(short_value) => self.push(Int(short_value as i32))?,
 
-            Instruction::Invokespecial(constant_index) => {
-                self.invoke_method(vm, call_stack, constant_index, InvokeKind::Special)?
-            }
-            Instruction::Invokestatic(constant_index) => {
-                self.invoke_method(vm, call_stack, constant_index, InvokeKind::Static)?
-            }
-            Instruction::Invokevirtual(constant_index) => {
-                self.invoke_method(vm, call_stack, constant_index, InvokeKind::Virtual)?
-            }
-            Instruction::Invokeinterface(constant_index, _) => {
-                self.invoke_method(vm, call_stack, constant_index, InvokeKind::Interface)?
-            }
-
             Instruction::Return => {
                 if !self.class_and_method.is_void() {
                     return Err(MethodCallFailed::InternalError(
@@ -496,24 +562,28 @@ impl<'a> CallFrame<'a> {
                 self.debug_done_execution(None);
                 return Ok(ReturnFromMethod(None));
             }
-            Instruction::Areturn => return Ok(ReturnFromMethod(self.execute_areturn()?)),
+            Instruction::Areturn => return Ok(ReturnFromMethod(self.execute_areturn(vm)?)),
             Instruction::Ireturn => return Ok(ReturnFromMethod(self.execute_ireturn()?)),
             Instruction::Lreturn => return Ok(ReturnFromMethod(self.execute_lreturn()?)),
             Instruction::Freturn => return Ok(ReturnFromMethod(self.execute_freturn()?)),
             Instruction::Dreturn => return Ok(ReturnFromMethod(self.execute_dreturn()?)),
 
             Instruction::Instanceof(constant_index) => {
-                self.execute_instanceof(vm, call_stack, constant_index)?
+                self.execute_instanceof(vm, constant_index)?
             }
             Instruction::Checkcast(constant_index) => {
                 self.execute_checkcast(vm, call_stack, constant_index)?
             }
 
-            Instruction::Putfield(field_index) => self.execute_putfield(vm, field_index)?,
+            Instruction::Putfield(field_index) => {
+                self.execute_putfield(vm, call_stack, field_index)?
+            }
             Instruction::Putstatic(field_index) => {
                 self.execute_putstatic(vm, call_stack, field_index)?
             }
-            Instruction::Getfield(field_index) => self.execute_getfield(vm, field_index)?,
+            Instruction::Getfield(field_index) => {
+                self.execute_getfield(vm, call_stack, field_index)?
+            }
             Instruction::Getstatic(field_index) => {
                 self.execute_getstatic(vm, call_stack, field_index)?
             }
@@ -521,14 +591,8 @@ impl<'a> CallFrame<'a> {
             Instruction::Iadd => self.execute_int_math(|a, b| Ok(a.wrapping_add(b)))?,
             Instruction::Isub => self.execute_int_math(|a, b| Ok(a.wrapping_sub(b)))?,
             Instruction::Imul => self.execute_int_math(|a, b| Ok(a.wrapping_mul(b)))?,
-            Instruction::Idiv => self.execute_int_math(|a, b| match b {
-                0 => Err(VmError::ArithmeticException),
-                _ => Ok(a.wrapping_div(b)),
-            })?,
-            Instruction::Irem => self.execute_int_math(|a, b| match b {
-                0 => Err(VmError::ArithmeticException),
-                _ => Ok(a.wrapping_rem(b)),
-            })?,
+            Instruction::Idiv => self.execute_int_division(vm, call_stack, i32::wrapping_div)?,
+            Instruction::Irem => self.execute_int_division(vm, call_stack, i32::wrapping_rem)?,
             Instruction::Iand => self.execute_int_math(|a, b| Ok(a & b))?,
             Instruction::Ior => self.execute_int_math(|a, b| Ok(a | b))?,
             Instruction::Ixor => self.execute_int_math(|a, b| Ok(a ^ b))?,
@@ -545,33 +609,26 @@ impl<'a> CallFrame<'a> {
             })?,
 
             Instruction::Iinc(index, constant) => {
-                let index = index.into_usize_safe();
-                let local = self.get_local_int_as_int(vm, index)?;
-                self.locals[index] = Int(local + constant as i32);
+                self.execute_iinc(vm, index.into_usize_safe(), constant as i32)?
             }
 
             Instruction::Ladd => self.execute_long_math(|a, b| Ok(a + b))?,
             Instruction::Lsub => self.execute_long_math(|a, b| Ok(a - b))?,
             Instruction::Lmul => self.execute_long_math(|a, b| Ok(a * b))?,
-            Instruction::Ldiv => self.execute_long_math(|a, b| match b {
-                0 => Err(VmError::ArithmeticException),
-                _ => Ok(a / b),
-            })?,
-            Instruction::Lrem => self.execute_long_math(|a, b| match b {
-                0 => Err(VmError::ArithmeticException),
-                _ => Ok(a % b),
-            })?,
+            Instruction::Ldiv => self.execute_long_division(vm, call_stack, i64::wrapping_div)?,
+            Instruction::Lrem => self.execute_long_division(vm, call_stack, i64::wrapping_rem)?,
             Instruction::Land => self.execute_long_math(|a, b| Ok(a & b))?,
             Instruction::Lor => self.execute_long_math(|a, b| Ok(a | b))?,
             Instruction::Lxor => self.execute_long_math(|a, b| Ok(a ^ b))?,
-            Instruction::Lshr => self.execute_long_shift(|a, b| Ok(a >> (b & 0x1f)))?,
-            Instruction::Lshl => self.execute_long_shift(|a, b| Ok(a << (b & 0x1f)))?,
+            // Only the six lowest bits of the shift distance are used for longs
+            Instruction::Lshr => self.execute_long_shift(|a, b| Ok(a >> (b & 0x3f)))?,
+            Instruction::Lshl => self.execute_long_shift(|a, b| Ok(a << (b & 0x3f)))?,
             Instruction::Lushr => self.execute_long_shift(|a, b| {
                 Ok({
                     if a > 0 {
-                        a >> (b & 0x1f)
+                        a >> (b & 0x3f)
                     } else {
-                        ((a as u64) >> (b & 0x1f)) as i64
+                        ((a as u64) >> (b & 0x3f)) as i64
                     }
                 })
             })?,
@@ -588,15 +645,9 @@ impl<'a> CallFrame<'a> {
                     },
                 )
             })?,
-            Instruction::Frem => self.execute_float_math(|a, b| {
-                Ok(
-                    if Self::is_double_division_returning_nan(a as f64, b as f64) {
-                        f32::NAN
-                    } else {
-                        a % b
-                    },
-                )
-            })?,
+            // Rust's % on floats truncates the quotient, like C's fmod, so the result has the
+            // sign of the dividend and the special cases (NaN, infinities, zero) match the JVM spec
+            Instruction::Frem => self.execute_float_math(|a, b| Ok(a % b))?,
 
             Instruction::Dadd => self.execute_double_math(|a, b| Ok(a + b))?,
             Instruction::Dsub => self.execute_double_math(|a, b| Ok(a - b))?,
@@ -608,13 +659,7 @@ impl<'a> CallFrame<'a> {
                     a / b
                 })
             })?,
-            Instruction::Drem => self.execute_double_math(|a, b| {
-                Ok(if Self::is_double_division_returning_nan(a, b) {
-                    f64::NAN
-                } else {
-                    a % b
-                })
-            })?,
+            Instruction::Drem => self.execute_double_math(|a, b| Ok(a % b))?,
 
             Instruction::Ineg => self.execute_ineg()?,
             Instruction::Lneg => self.execute_lneg()?,
@@ -622,6 +667,14 @@ impl<'a> CallFrame<'a> {
             Instruction::Dneg => self.execute_dneg()?,
 
             Instruction::Goto(jump_address) => self.goto(jump_address),
+            Instruction::Tableswitch(ref table) => {
+                let key = self.pop_int()?;
+                self.goto(table.target(key))
+            }
+            Instruction::Lookupswitch(ref lookup) => {
+                let key = self.pop_int()?;
+                self.goto(lookup.target(key))
+            }
 
             Instruction::Ifeq(jump_address) => self.execute_if(jump_address, |v| v == 0)?,
             Instruction::Ifne(jump_address) => self.execute_if(jump_address, |v| v != 0)?,
@@ -653,54 +706,56 @@ impl<'a> CallFrame<'a> {
                 self.execute_if_icmp(jump_address, |a, b| a >= b)?
             }
 
-            Instruction::Lcmp => self.execute_long_compare(1)?,
-            Instruction::Fcmpg => self.execute_float_compare(-1)?,
-            Instruction::Fcmpl => self.execute_float_compare(1)?,
-            Instruction::Dcmpg => self.execute_double_compare(-1)?,
-            Instruction::Dcmpl => self.execute_double_compare(1)?,
+            // Longs are always ordered
+            Instruction::Lcmp => self.execute_long_compare(0)?,
+            Instruction::Fcmpg => self.execute_float_compare(1)?,
+            Instruction::Fcmpl => self.execute_float_compare(-1)?,
+            Instruction::Dcmpg => self.execute_double_compare(1)?,
+            Instruction::Dcmpl => self.execute_double_compare(-1)?,
 
             Instruction::Newarray(array_type) => {
-                self.execute_newarray(vm, array_type)?;
+                self.execute_newarray(vm, call_stack, array_type)?;
             }
             Instruction::Anewarray(constant_index) => {
                 self.execute_anewarray(vm, call_stack, constant_index)?;
             }
 
-            Instruction::Arraylength => self.execute_array_length()?,
-
-            Instruction::Baload => self.execute_baload()?,
-            Instruction::Caload => self.execute_caload()?,
-            Instruction::Saload => self.execute_saload()?,
-            Instruction::Iaload => self.execute_iaload()?,
-            Instruction::Laload => self.execute_laload()?,
-            Instruction::Faload => self.execute_faload()?,
-            Instruction::Daload => self.execute_daload()?,
-            Instruction::Aaload => self.execute_aaload()?,
-
-            Instruction::Bastore => self.execute_bastore()?,
-            Instruction::Castore => self.execute_castore()?,
-            Instruction::Sastore => self.execute_sastore()?,
-            Instruction::Iastore => self.execute_iastore()?,
-            Instruction::Lastore => self.execute_lastore()?,
-            Instruction::Fastore => self.execute_fastore()?,
-            Instruction::Dastore => self.execute_dastore()?,
-            Instruction::Aastore => self.execute_aastore(vm)?,
-
-            Instruction::Monitorenter => self.execute_monitorenter()?,
-            Instruction::Monitorexit => self.execute_monitorexit()?,
+            Instruction::Arraylength => self.execute_array_length(vm, call_stack)?,
+
+            Instruction::Baload => self.execute_baload(vm, call_stack)?,
+            Instruction::Caload => self.execute_caload(vm, call_stack)?,
+            Instruction::Saload => self.execute_saload(vm, call_stack)?,
+            Instruction::Iaload => self.execute_iaload(vm, call_stack)?,
+            Instruction::Laload => self.execute_laload(vm, call_stack)?,
+            Instruction::Faload => self.execute_faload(vm, call_stack)?,
+            Instruction::Daload => self.execute_daload(vm, call_stack)?,
+            Instruction::Aaload => self.execute_aaload(vm, call_stack)?,
+
+            Instruction::Bastore => self.execute_bastore(vm, call_stack)?,
+            Instruction::Castore => self.execute_castore(vm, call_stack)?,
+            Instruction::Sastore => self.execute_sastore(vm, call_stack)?,
+            Instruction::Iastore => self.execute_iastore(vm, call_stack)?,
+            Instruction::Lastore => self.execute_lastore(vm, call_stack)?,
+            Instruction::Fastore => self.execute_fastore(vm, call_stack)?,
+            Instruction::Dastore => self.execute_dastore(vm, call_stack)?,
+            Instruction::Aastore => self.execute_aastore(vm, call_stack)?,
+
+            Instruction::Monitorenter => self.execute_monitorenter(vm, call_stack)?,
+            Instruction::Monitorexit => self.execute_monitorexit(vm, call_stack)?,
+
+            Instruction::Athrow => self.execute_athrow(vm, call_stack)?,
+            Instruction::Multianewarray(constant_index, dimensions) => {
+                self.execute_multianewarray(vm, call_stack, constant_index, dimensions)?
+            }
 
-            Instruction::Athrow => self.execute_athrow()?,
+            Instruction::Wide(wide_instruction) => self.execute_wide(vm, wide_instruction)?,
 
             /* Unimplemented instructions:
             Instruction::Goto_w => {}
             Instruction::Invokedynamic(_) => {}
             Instruction::Jsr(_) => {}
             Instruction::Jsr_w => {}
-            Instruction::Lookupswitch => {}
-            Instruction::Multianewarray(_, _) => {}
             Instruction::Ret(_) => {}
-            Instruction::Tableswitch => {}
-            Instruction::Wide => {}
             */
             Instruction::Nop => {}
 
@@ -755,6 +810,8 @@ impl<'a> CallFrame<'a> {
         Double(value as f64)
     }
 
+    // Rust's float to integer casts saturate and convert NaN to zero,
+    // which is exactly what the JVM spec requires
     fn f2i(value: f32) -> Value<'a> {
         Int(value as i32)
     }
@@ -788,21 +845,40 @@ impl<'a> CallFrame<'a> {
         constant_index: u16,
         kind: InvokeKind,
     ) -> Result<(), MethodCallFailed<'a>> {
-        let method_reference = self.get_constant_method_reference(constant_index)?;
-        if method_reference.class_name.starts_with('[') && method_reference.method_name == "clone" {
-            // TODO:
-            //  Since we have NOT modelled arrays properly (i.e. we do not have a real class
-            //  to model them), we cannot lookup methods naturally. Thus we have a special case for
-            //  invoking "clone" on an array.
-            let array = self.pop()?;
-            let clone = vm.clone_array(array)?;
-            return self.push(clone);
-        }
-
-        let static_method_reference =
-            self.get_method_to_invoke_statically(vm, call_stack, method_reference, kind)?;
+        let static_method_reference = match vm
+            .get_resolved_method_reference(self.class_and_method.class, constant_index)
+        {
+            Some(method) => method,
+            None => {
+                let method_reference = self.get_constant_method_reference(constant_index)?;
+                if method_reference.class_name.starts_with('[')
+                    && method_reference.method_name == "clone"
+                {
+                    // TODO:
+                    //  Since we have NOT modelled arrays properly (i.e. we do not have a real class
+                    //  to model them), we cannot lookup methods naturally. Thus we have a special case for
+                    //  invoking "clone" on an array.
+                    let array = self.pop()?;
+                    let clone = vm.clone_array(array)?;
+                    return self.push(clone);
+                }
+
+                let method =
+                    self.get_method_to_invoke_statically(vm, call_stack, method_reference, kind)?;
+                Self::check_invocation_kind(vm, call_stack, &method, kind)?;
+                vm.cache_resolved_method_reference(
+                    self.class_and_method.class,
+                    constant_index,
+                    method.clone(),
+                );
+                method
+            }
+        };
         let (receiver, params, new_stack_len) =
-            self.get_method_receiver_and_params(&static_method_reference)?;
+            match self.get_method_receiver_and_params(&static_method_reference) {
+                Err(VmError::NullPointerException) => return Err(vm.throw_npe(call_stack)),
+                result => result?,
+            };
         let class_and_method = match kind {
             InvokeKind::Virtual | InvokeKind::Interface => {
                 Self::resolve_virtual_method(vm, receiver.clone(), static_method_reference)?
@@ -833,22 +909,78 @@ impl<'a> CallFrame<'a> {
             ))
     }
 
+    /// Resolves a static field and initializes the class that declares it, which
+    /// might be a superclass or an interface of the one named by the reference.
+    /// The latter is only loaded: accessing an inherited field does not initialize it.
+    /// The result does not depend on any object, so it is cached by the vm.
+    fn get_static_field(
+        &self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        constant_index: u16,
+    ) -> Result<(ClassRef<'a>, usize, &'a ClassFileField), MethodCallFailed<'a>> {
+        if let Some(field) =
+            vm.get_resolved_static_field_reference(self.class_and_method.class, constant_index)
+        {
+            return Ok(field);
+        }
+        let field_reference = self.get_constant_field_reference(constant_index)?;
+        let field_type = FieldType::parse(field_reference.type_descriptor)
+            .map_err(|_| VmError::ValidationException)?;
+        let class = vm.load_class_referenced_by_code(call_stack, field_reference.class_name)?;
+        let (declaring_class, index, field) = class
+            .find_static_field(field_reference.field_name, &field_type)
+            .ok_or(VmError::FieldNotFoundException(
+                field_reference.class_name.to_string(),
+                field_reference.field_name.to_string(),
+            ))?;
+        vm.get_or_resolve_class(call_stack, &declaring_class.name)?;
+        vm.cache_resolved_static_field_reference(
+            self.class_and_method.class,
+            constant_index,
+            (declaring_class, index, field),
+        );
+        Ok((declaring_class, index, field))
+    }
+
     generate_pop!(pop_int, Int, i32);
     generate_pop!(pop_long, Long, i64);
     generate_pop!(pop_float, Float, f32);
     generate_pop!(pop_double, Double, f64);
-    generate_pop!(pop_object, Object, AbstractObject<'a>);
 
-    fn pop_array(&mut self) -> Result<impl Array<'a>, MethodCallFailed<'a>> {
+    fn pop_array(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+    ) -> Result<impl Array<'a>, MethodCallFailed<'a>> {
         let receiver = self.pop()?;
         match receiver {
             Value::Object(object) if object.kind() == ObjectKind::Array => Ok(object),
+            Null => Err(vm.throw_npe(call_stack)),
             _ => Err(MethodCallFailed::InternalError(
                 VmError::ValidationException,
             )),
         }
     }
 
+    /// Pops the index and the array accessed by the array load and store instructions,
+    /// throwing `java.lang.NullPointerException` if the array is null, or
+    /// `java.lang.ArrayIndexOutOfBoundsException` if the index is not valid
+    fn pop_array_and_index(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+    ) -> Result<(impl Array<'a>, usize), MethodCallFailed<'a>> {
+        let index = self.pop_int()?;
+        let array = self.pop_array(vm, call_stack)?;
+        match usize::try_from(index) {
+            Ok(valid_index) if valid_index < array.len().into_usize_safe() => {
+                Ok((array, valid_index))
+            }
+            _ => Err(vm.throw_array_index_out_of_bounds(call_stack, index, array.len())),
+        }
+    }
+
     fn get_constant(&self, constant_index: u16) -> Result<&ConstantPoolEntry, VmError> {
         self.class_and_method
             .class
@@ -869,7 +1001,7 @@ impl<'a> CallFrame<'a> {
     fn get_constant_method_reference(
         &self,
         constant_index: u16,
-    ) -> Result<MethodReference, VmError> {
+    ) -> Result<MethodReference<'_>, VmError> {
         let constant = self.get_constant(constant_index)?;
 
         let (class_name_index, name_and_type_descriptor_index) = match *constant {
@@ -895,7 +1027,10 @@ impl<'a> CallFrame<'a> {
         }
     }
 
-    fn get_constant_field_reference(&self, constant_index: u16) -> Result<FieldReference, VmError> {
+    fn get_constant_field_reference(
+        &self,
+        constant_index: u16,
+    ) -> Result<FieldReference<'_>, VmError> {
         let constant = self.get_constant(constant_index)?;
         if let &ConstantPoolEntry::FieldReference(
             class_name_index,
@@ -935,7 +1070,12 @@ impl<'a> CallFrame<'a> {
         method_reference: MethodReference,
         kind: InvokeKind,
     ) -> Result<ClassAndMethod<'a>, MethodCallFailed<'a>> {
-        let class = vm.get_or_resolve_class(call_stack, method_reference.class_name)?;
+        // Only invokestatic initializes the class: the other kinds of invocation require an
+        // instance, whose creation has already initialized it
+        let class = vm.load_class_referenced_by_code(call_stack, method_reference.class_name)?;
+        if matches!(kind, InvokeKind::Static) {
+            vm.get_or_resolve_class(call_stack, &class.name)?;
+        }
         match kind {
             InvokeKind::Special | InvokeKind::Static => {
                 Self::get_method_of_class(class, method_reference)
@@ -947,6 +1087,33 @@ impl<'a> CallFrame<'a> {
         }
     }
 
+    /// Static methods can only be invoked by invokestatic, and instance methods by
+    /// the other instructions. A mismatch can happen with classes compiled separately.
+    fn check_invocation_kind(
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        class_and_method: &ClassAndMethod<'a>,
+        kind: InvokeKind,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        let is_static_invocation = matches!(kind, InvokeKind::Static);
+        if class_and_method.is_static() == is_static_invocation {
+            return Ok(());
+        }
+        let message = if is_static_invocation {
+            format!("Expected static method {}", class_and_method.description())
+        } else {
+            format!(
+                "Expecting non-static method {}",
+                class_and_method.description()
+            )
+        };
+        Err(vm.throw_exception(
+            call_stack,
+            "java/lang/IncompatibleClassChangeError",
+            Some(&message),
+        ))
+    }
+
     fn get_method_of_class<'b>(
         class: &'b Class<'a>,
         method_reference: MethodReference,
@@ -969,50 +1136,99 @@ impl<'a> CallFrame<'a> {
         class: &'b Class<'a>,
         method_reference: MethodReference,
     ) -> Result<ClassAndMethod<'b>, MethodCallFailed<'a>> {
-        let mut curr_class = class;
-        loop {
-            if let Some(method) = curr_class.find_method(
+        let mut curr_class = Some(class);
+        while let Some(searched_class) = curr_class {
+            if let Some(method) = searched_class.find_method(
                 method_reference.method_name,
                 method_reference.type_descriptor,
             ) {
                 return Ok(ClassAndMethod {
-                    class: curr_class,
+                    class: searched_class,
                     method,
                 });
             }
+            curr_class = searched_class.superclass;
+        }
 
-            if let Some(superclass) = curr_class.superclass {
-                curr_class = superclass;
-            } else {
-                return Err(MethodCallFailed::InternalError(
-                    VmError::MethodNotFoundException(
-                        class.name.to_string(),
-                        method_reference.method_name.to_string(),
-                        method_reference.type_descriptor.to_string(),
-                    ),
-                ));
+        // Default methods are inherited from the superinterfaces
+        Self::get_maximally_specific_interface_method(class, &method_reference).ok_or(
+            MethodCallFailed::InternalError(VmError::MethodNotFoundException(
+                class.name.to_string(),
+                method_reference.method_name.to_string(),
+                method_reference.type_descriptor.to_string(),
+            )),
+        )
+    }
+
+    /// Searches the method among all the superinterfaces of the class, including the
+    /// indirect ones, and returns the maximally specific declaration, i.e. the one of an
+    /// interface that is not extended by any other interface declaring the method.
+    /// A default method is preferred to an abstract declaration. Conflicting default
+    /// methods are not detected: the first one found is used.
+    fn get_maximally_specific_interface_method<'b>(
+        class: &'b Class<'a>,
+        method_reference: &MethodReference,
+    ) -> Option<ClassAndMethod<'b>> {
+        let mut superinterfaces = Vec::new();
+        Self::collect_superinterfaces(class, &mut superinterfaces);
+
+        let candidates: Vec<ClassAndMethod<'b>> = superinterfaces
+            .into_iter()
+            .filter_map(|interface| {
+                interface
+                    .find_method(
+                        method_reference.method_name,
+                        method_reference.type_descriptor,
+                    )
+                    .filter(|method| {
+                        !method.is_static() && !method.flags.contains(MethodFlags::PRIVATE)
+                    })
+                    .map(|method| ClassAndMethod {
+                        class: interface,
+                        method,
+                    })
+            })
+            .collect();
+        let maximally_specific: Vec<&ClassAndMethod<'b>> = candidates
+            .iter()
+            .filter(|candidate| {
+                !candidates.iter().any(|other| {
+                    other.class.id != candidate.class.id
+                        && other.class.is_subclass_of(candidate.class)
+                })
+            })
+            .collect();
+        maximally_specific
+            .iter()
+            .find(|candidate| !candidate.is_abstract())
+            .or(maximally_specific.first())
+            .map(|candidate| (*candidate).clone())
+    }
+
+    fn collect_superinterfaces<'b>(class: &'b Class<'a>, result: &mut Vec<&'b Class<'a>>) {
+        for interface in class.interfaces.iter() {
+            if !result.iter().any(|found| found.id == interface.id) {
+                result.push(interface);
+                Self::collect_superinterfaces(interface, result);
             }
         }
+        if let Some(superclass) = class.superclass {
+            Self::collect_superinterfaces(superclass, result);
+        }
     }
 
     fn resolve_virtual_method(
         vm: &Vm<'a>,
         receiver: Option<AbstractObject>,
-        class_and_method: ClassAndMethod,
+        class_and_method: ClassAndMethod<'a>,
     ) -> Result<ClassAndMethod<'a>, MethodCallFailed<'a>> {
         match receiver {
             Some(receiver) if receiver.kind() == ObjectKind::Object => {
                 let receiver_class = vm.find_class_by_id(receiver.class_id()).ok_or(
                     VmError::ClassNotFoundException(receiver.class_id().to_string()),
                 )?;
-                let resolved_method = Self::get_method_checking_superclasses(
-                    receiver_class,
-                    MethodReference {
-                        class_name: &class_and_method.class.name,
-                        method_name: &class_and_method.method.name,
-                        type_descriptor: &class_and_method.method.type_descriptor,
-                    },
-                )?;
+                let resolved_method =
+                    Self::select_virtual_method(receiver_class, &class_and_method)?;
                 debug!(
                     "resolved virtual method {}.{}:{} on object of class {}: using version of class {}",
                     class_and_method.class.name,
@@ -1023,12 +1239,119 @@ impl<'a> CallFrame<'a> {
                 );
                 Ok(resolved_method)
             }
+            Some(receiver) if receiver.kind() == ObjectKind::Array => {
+                // We do not have a real class to model arrays, but their superclass
+                // is java.lang.Object and they do not override any of its methods
+                let object_class = vm.find_class_by_name("java/lang/Object").ok_or(
+                    VmError::ClassNotFoundException("java/lang/Object".to_string()),
+                )?;
+                Self::get_method_checking_superclasses(
+                    object_class,
+                    MethodReference {
+                        class_name: &class_and_method.class.name,
+                        method_name: &class_and_method.method.name,
+                        type_descriptor: &class_and_method.method.type_descriptor,
+                    },
+                )
+            }
             _ => Err(MethodCallFailed::InternalError(
                 VmError::ValidationException,
             )),
         }
     }
 
+    /// Selects the method to execute for a virtual invocation, starting from the class of
+    /// the receiver. A method declared by a class overrides the resolved one only if it
+    /// can access it: in particular, package-private methods can only be overridden by
+    /// classes of the same package.
+    fn select_virtual_method(
+        receiver_class: ClassRef<'a>,
+        resolved_method: &ClassAndMethod<'a>,
+    ) -> Result<ClassAndMethod<'a>, MethodCallFailed<'a>> {
+        let mut curr_class = Some(receiver_class);
+        while let Some(searched_class) = curr_class {
+            if searched_class.id == resolved_method.class.id {
+                return Ok(resolved_method.clone());
+            }
+            if let Some(method) = searched_class.find_method(
+                &resolved_method.method.name,
+                &resolved_method.method.type_descriptor,
+            ) {
+                if Self::overrides(searched_class, method, resolved_method) {
+                    return Ok(ClassAndMethod {
+                        class: searched_class,
+                        method,
+                    });
+                }
+            }
+            curr_class = searched_class.superclass;
+        }
+
+        // Default methods are inherited from the superinterfaces
+        let method_reference = MethodReference {
+            class_name: &resolved_method.class.name,
+            method_name: &resolved_method.method.name,
+            type_descriptor: &resolved_method.method.type_descriptor,
+        };
+        Self::get_maximally_specific_interface_method(receiver_class, &method_reference).ok_or(
+            MethodCallFailed::InternalError(VmError::MethodNotFoundException(
+                receiver_class.name.to_string(),
+                method_reference.method_name.to_string(),
+                method_reference.type_descriptor.to_string(),
+            )),
+        )
+    }
+
+    /// Overriding is transitive: a method also overrides the resolved one if it overrides
+    /// a method of an intermediate superclass which, in turn, overrides the resolved one.
+    /// For example a method can override a package-private one of another package through
+    /// a public method declared in between.
+    fn overrides(
+        class: ClassRef<'a>,
+        method: &ClassFileMethod,
+        overridden: &ClassAndMethod<'a>,
+    ) -> bool {
+        if method.is_static() || method.flags.contains(MethodFlags::PRIVATE) {
+            return false;
+        }
+        if Self::can_override(class, overridden) {
+            return true;
+        }
+        let mut curr_class = class.superclass;
+        while let Some(intermediate_class) = curr_class {
+            if intermediate_class.id == overridden.class.id {
+                break;
+            }
+            if let Some(intermediate_method) =
+                intermediate_class.find_method(&method.name, &method.type_descriptor)
+            {
+                let intermediate = ClassAndMethod {
+                    class: intermediate_class,
+                    method: intermediate_method,
+                };
+                if Self::overrides(class, method, &intermediate)
+                    && Self::overrides(intermediate_class, intermediate_method, overridden)
+                {
+                    return true;
+                }
+            }
+            curr_class = intermediate_class.superclass;
+        }
+        false
+    }
+
+    /// Whether a method of the given class can directly override the given one
+    fn can_override(class: ClassRef<'a>, overridden: &ClassAndMethod<'a>) -> bool {
+        let overridden_flags = overridden.method.flags;
+        if overridden_flags.intersects(MethodFlags::PUBLIC | MethodFlags::PROTECTED) {
+            true
+        } else if overridden_flags.contains(MethodFlags::PRIVATE) {
+            false
+        } else {
+            class.package_name() == overridden.class.package_name()
+        }
+    }
+
     fn get_method_receiver_and_params(
         &self,
         class_and_method: &ClassAndMethod<'a>,
@@ -1093,6 +1416,7 @@ impl<'a> CallFrame<'a> {
                 // TODO: here we should check "instanceof" the expected class of a subclass
                 Ok(object.clone())
             }
+            Null => Err(VmError::NullPointerException),
             _ => Err(VmError::ValidationException),
         }
     }
@@ -1124,13 +1448,39 @@ impl<'a> CallFrame<'a> {
         }
     }
 
-    fn execute_areturn(&mut self) -> MethodCallResult<'a> {
+    fn execute_areturn(&mut self, vm: &Vm) -> MethodCallResult<'a> {
         let result = self.pop()?;
+        // The returned value can be null, or an instance of the declared return type
+        // or of any of its subclasses
+        let return_type = self
+            .class_and_method
+            .return_type()
+            .ok_or(VmError::ValidationException)?;
+        Self::validate_type(vm, return_type, &result)?;
+        self.debug_done_execution(Some(&result));
+        Ok(Some(result))
+    }
+
+    // Like the JVM, we narrow the value returned by methods declared to return a type
+    // smaller than int, since the bytecode is allowed to return any int
+    fn execute_ireturn(&mut self) -> MethodCallResult<'a> {
+        let value = self.pop_int()?;
+        let result = match self.class_and_method.return_type() {
+            Some(Base(BaseType::Int)) => Int(value),
+            Some(Base(BaseType::Boolean)) => Int(value & 1),
+            Some(Base(BaseType::Byte)) => Self::i2b(value),
+            Some(Base(BaseType::Char)) => Self::i2c(value),
+            Some(Base(BaseType::Short)) => Self::i2s(value),
+            _ => {
+                return Err(MethodCallFailed::InternalError(
+                    VmError::ValidationException,
+                ))
+            }
+        };
         self.debug_done_execution(Some(&result));
         Ok(Some(result))
     }
 
-    generate_execute_return!(execute_ireturn, Int);
     generate_execute_return!(execute_lreturn, Long);
     generate_execute_return!(execute_freturn, Float);
     generate_execute_return!(execute_dreturn, Double);
@@ -1149,10 +1499,44 @@ impl<'a> CallFrame<'a> {
         }
     }
 
+    fn execute_iinc(&mut self, vm: &Vm, index: usize, constant: i32) -> Result<(), VmError> {
+        let local = self.get_local_int_as_int(vm, index)?;
+        self.locals[index] = Int(local.wrapping_add(constant));
+        Ok(())
+    }
+
+    fn execute_wide(
+        &mut self,
+        vm: &Vm,
+        wide_instruction: WideInstruction,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        match wide_instruction {
+            WideInstruction::Aload(index) => self.execute_aload(index.into_usize_safe()),
+            WideInstruction::Astore(index) => self.execute_astore(index.into_usize_safe()),
+            WideInstruction::Dload(index) => self.execute_dload(index.into_usize_safe()),
+            WideInstruction::Dstore(index) => self.execute_dstore(index.into_usize_safe()),
+            WideInstruction::Fload(index) => self.execute_fload(index.into_usize_safe()),
+            WideInstruction::Fstore(index) => self.execute_fstore(index.into_usize_safe()),
+            WideInstruction::Iinc(index, constant) => {
+                Ok(self.execute_iinc(vm, index.into_usize_safe(), constant as i32)?)
+            }
+            WideInstruction::Iload(index) => self.execute_iload(index.into_usize_safe()),
+            WideInstruction::Istore(index) => self.execute_istore(index.into_usize_safe()),
+            WideInstruction::Lload(index) => self.execute_lload(index.into_usize_safe()),
+            WideInstruction::Lstore(index) => self.execute_lstore(index.into_usize_safe()),
+            WideInstruction::Ret(_) => {
+                warn!("Unsupported instruction: {:?}", wide_instruction);
+                Err(MethodCallFailed::InternalError(VmError::NotImplemented))
+            }
+        }
+    }
+
     generate_execute_math!(execute_int_math, pop_int, Int, i32);
     generate_execute_math!(execute_long_math, pop_long, Long, i64);
     generate_execute_math!(execute_float_math, pop_float, Float, f32);
     generate_execute_math!(execute_double_math, pop_double, Double, f64);
+    generate_execute_division!(execute_int_division, pop_int, Int, i32);
+    generate_execute_division!(execute_long_division, pop_long, Long, i64);
 
     fn execute_long_shift<T>(&mut self, evaluator: T) -> Result<(), MethodCallFailed<'a>>
     where
@@ -1171,10 +1555,11 @@ impl<'a> CallFrame<'a> {
             || ((a == 0f64 || a == -0f64) && (b == 0f64 || b == -0f64))
     }
 
-    generate_execute_neg!(execute_ineg, pop_int, Int);
-    generate_execute_neg!(execute_lneg, pop_long, Long);
-    generate_execute_neg!(execute_fneg, pop_float, Float);
-    generate_execute_neg!(execute_dneg, pop_double, Double);
+    // Negating the minimum value overflows, and the JVM spec requires it to wrap around
+    generate_execute_neg!(execute_ineg, pop_int, Int, i32::wrapping_neg);
+    generate_execute_neg!(execute_lneg, pop_long, Long, i64::wrapping_neg);
+    generate_execute_neg!(execute_fneg, pop_float, Float, std::ops::Neg::neg);
+    generate_execute_neg!(execute_dneg, pop_double, Double, std::ops::Neg::neg);
 
     generate_execute_coerce!(coerce_int, pop_int, i32);
     generate_execute_coerce!(coerce_long, pop_long, i64);
@@ -1222,22 +1607,8 @@ impl<'a> CallFrame<'a> {
         jump_on_null: bool,
     ) -> Result<(), MethodCallFailed<'a>> {
         let value = self.pop()?;
-        match value {
-            Value::Object(_) => {
-                if !jump_on_null {
-                    self.goto(jump_address);
-                }
-            }
-            Null => {
-                if jump_on_null {
-                    self.goto(jump_address);
-                }
-            }
-            _ => {
-                return Err(MethodCallFailed::InternalError(
-                    VmError::ValidationException,
-                ))
-            }
+        if value.as_reference()?.is_none() == jump_on_null {
+            self.goto(jump_address);
         }
         Ok(())
     }
@@ -1249,32 +1620,11 @@ impl<'a> CallFrame<'a> {
     ) -> Result<(), MethodCallFailed<'a>> {
         let value2 = self.pop()?;
         let value1 = self.pop()?;
-        let equal = match value1 {
-            Value::Object(object1) => match value2 {
-                Value::Object(object2) => object1.is_same_as(&object2),
-                Null => false,
-                _ => {
-                    return Err(MethodCallFailed::InternalError(
-                        VmError::ValidationException,
-                    ))
-                }
-            },
-            Null => match value2 {
-                Null => true,
-                Value::Object(..) => false,
-                _ => {
-                    return Err(MethodCallFailed::InternalError(
-                        VmError::ValidationException,
-                    ))
-                }
-            },
-            _ => {
-                return Err(MethodCallFailed::InternalError(
-                    VmError::ValidationException,
-                ))
-            }
+        let equal = match (value1.as_reference()?, value2.as_reference()?) {
+            (Some(object1), Some(object2)) => object1.is_same_as(object2),
+            (reference1, reference2) => reference1.is_none() && reference2.is_none(),
         };
-        if (jump_on_equal && equal) || (!jump_on_equal && !equal) {
+        if equal == jump_on_equal {
             self.goto(jump_address);
         }
         Ok(())
@@ -1294,6 +1644,27 @@ impl<'a> CallFrame<'a> {
         }
     }
 
+    /// Stores a value in a local variable. Longs and doubles take two slots, the second of
+    /// which holds no value, so storing anything over either half of them invalidates them
+    fn set_local(&mut self, index: usize, value: Value<'a>) -> Result<(), VmError> {
+        let slots = if matches!(value, Long(_) | Double(_)) {
+            2
+        } else {
+            1
+        };
+        if index + slots > self.locals.len() {
+            return Err(VmError::ValidationException);
+        }
+        if index > 0 && matches!(self.locals[index - 1], Long(_) | Double(_)) {
+            self.locals[index - 1] = Value::Uninitialized;
+        }
+        self.locals[index] = value;
+        if slots == 2 {
+            self.locals[index + 1] = Value::Uninitialized;
+        }
+        Ok(())
+    }
+
     generate_execute_load!(execute_iload, Int);
     generate_execute_load!(execute_lload, Long);
     generate_execute_load!(execute_fload, Float);
@@ -1302,14 +1673,7 @@ impl<'a> CallFrame<'a> {
     fn execute_astore(&mut self, index: usize) -> Result<(), MethodCallFailed<'a>> {
         let value = self.pop()?;
         match value {
-            Value::Object(..) => {
-                self.locals[index] = value;
-                Ok(())
-            }
-            Null => {
-                self.locals[index] = value;
-                Ok(())
-            }
+            Value::Object(..) | Null => Ok(self.set_local(index, value)?),
             _ => Err(MethodCallFailed::InternalError(
                 VmError::ValidationException,
             )),
@@ -1335,7 +1699,7 @@ impl<'a> CallFrame<'a> {
                 let constant = self.get_constant(*string_index)?;
                 match constant {
                     ConstantPoolEntry::Utf8(string) => {
-                        let string_object = new_java_lang_string_object(vm, call_stack, string)?;
+                        let string_object = vm.intern_string(call_stack, string)?;
                         self.push(Value::Object(string_object))
                     }
                     _ => Err(MethodCallFailed::InternalError(
@@ -1347,7 +1711,9 @@ impl<'a> CallFrame<'a> {
                 let constant = self.get_constant(*class_index)?;
                 match constant {
                     ConstantPoolEntry::Utf8(class_name) => {
-                        let class_object = new_java_lang_class_object(vm, call_stack, class_name)?;
+                        // Class::getName uses the binary name, i.e. with dots as separator
+                        let class_object =
+                            vm.get_class_object(call_stack, &class_name.replace('/', "."))?;
                         self.push(Value::Object(class_object))
                     }
                     _ => Err(MethodCallFailed::InternalError(
@@ -1373,12 +1739,46 @@ impl<'a> CallFrame<'a> {
         }
     }
 
+    fn execute_new(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        constant_index: u16,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        let class_name = self.get_constant_class_reference(constant_index)?;
+        if class_name.starts_with('[') {
+            let message = format!("Illegal use of array type {class_name} in new");
+            return Err(vm.throw_exception(call_stack, "java/lang/VerifyError", Some(&message)));
+        }
+
+        // The check happens before the initialization, so that an invalid new does not
+        // run the static initializer
+        let class = vm.load_class_referenced_by_code(call_stack, class_name)?;
+        if class
+            .flags
+            .intersects(ClassAccessFlags::INTERFACE | ClassAccessFlags::ABSTRACT)
+        {
+            return Err(vm.throw_exception(
+                call_stack,
+                "java/lang/InstantiationError",
+                Some(&class_name.replace('/', ".")),
+            ));
+        }
+
+        vm.collect_if_over_threshold();
+        let new_object = vm.new_object(call_stack, class_name)?;
+        self.push(Value::Object(new_object))
+    }
+
     fn execute_newarray(
         &mut self,
         vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
         array_type: NewArrayType,
     ) -> Result<(), MethodCallFailed<'a>> {
-        let length = self.pop_int()?.into_usize_safe();
+        let length = self.pop_int()?;
+        let length = vm.check_array_length(call_stack, length)?;
+        vm.collect_if_over_threshold();
         let elements_type = match array_type {
             NewArrayType::Boolean => ArrayEntryType::Base(BaseType::Boolean),
             NewArrayType::Char => ArrayEntryType::Base(BaseType::Char),
@@ -1400,20 +1800,57 @@ impl<'a> CallFrame<'a> {
         call_stack: &mut CallStack<'a>,
         constant_index: u16,
     ) -> Result<(), MethodCallFailed<'a>> {
-        let length = self.pop_int()?.into_usize_safe();
+        let length = self.pop_int()?;
+        let length = vm.check_array_length(call_stack, length)?;
+        vm.collect_if_over_threshold();
         let class_name = self.get_constant_class_reference(constant_index)?;
-        let class = vm.get_or_resolve_class(call_stack, class_name)?;
-        let elements_type = ArrayEntryType::Object(class.id);
+        let component_type = if class_name.starts_with('[') {
+            FieldType::parse(class_name)
+                .map_err(|_| MethodCallFailed::InternalError(VmError::ValidationException))?
+        } else {
+            FieldType::Object(class_name.to_string())
+        };
+        let elements_type = vm.array_entry_type(call_stack, &component_type)?;
 
         let array = vm.new_array(elements_type, length);
         self.push(Value::Object(array))
     }
 
-    fn execute_array_length(&mut self) -> Result<(), MethodCallFailed<'a>> {
-        let array = self.pop_array()?;
-        let len = array.len() as i32;
-        self.push(Int(len))?;
-        Ok(())
+    fn execute_multianewarray(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+        constant_index: u16,
+        dimensions: u8,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        // The length of the first dimension is the deepest in the stack
+        let mut lengths = (0..dimensions)
+            .map(|_| self.pop_int())
+            .collect::<Result<Vec<i32>, _>>()?;
+        lengths.reverse();
+        vm.collect_if_over_threshold();
+
+        let class_name = self.get_constant_class_reference(constant_index)?;
+        let array_type = FieldType::parse(class_name)
+            .map_err(|_| MethodCallFailed::InternalError(VmError::ValidationException))?;
+        let array = vm.allocate_multi_array(call_stack, &array_type, &lengths)?;
+        self.push(Value::Object(array))
+    }
+
+    fn execute_array_length(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        match self.pop()? {
+            Value::Object(array) if array.kind() == ObjectKind::Array => {
+                self.push(Int(array.len() as i32))
+            }
+            Null => Err(vm.throw_npe(call_stack)),
+            _ => Err(MethodCallFailed::InternalError(
+                VmError::ValidationException,
+            )),
+        }
     }
 
     generate_execute_array_load!(
@@ -1427,7 +1864,11 @@ impl<'a> CallFrame<'a> {
     generate_execute_array_load!(execute_laload, ArrayEntryType::Base(BaseType::Long));
     generate_execute_array_load!(execute_faload, ArrayEntryType::Base(BaseType::Float));
     generate_execute_array_load!(execute_daload, ArrayEntryType::Base(BaseType::Double));
-    generate_execute_array_load!(execute_aaload, ArrayEntryType::Object(..));
+    generate_execute_array_load!(
+        execute_aaload,
+        ArrayEntryType::Object(..),
+        ArrayEntryType::Array(..)
+    );
 
     generate_execute_array_store!(
         execute_bastore,
@@ -1473,18 +1914,33 @@ impl<'a> CallFrame<'a> {
         ArrayEntryType::Base(BaseType::Double)
     );
 
-    fn execute_aastore(&mut self, vm: &Vm) -> Result<(), MethodCallFailed<'a>> {
-        let value = Value::Object(self.pop_object()?);
-        let index = self.pop_int()?.into_usize_safe();
-        let array = self.pop_array()?;
-        match array.elements_type() {
-            ArrayEntryType::Object(elements_class_id) => {
-                let elements_class_name = vm.get_class_by_id(elements_class_id)?;
-                Self::validate_type(
-                    vm,
-                    FieldType::Object(elements_class_name.name.clone()),
-                    &value,
-                )?;
+    fn execute_aastore(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        let value = self.pop()?;
+        let (array, index) = self.pop_array_and_index(vm, call_stack)?;
+        match (&value, array.elements_type()) {
+            // null is assignable to any reference type, so there is nothing to check
+            (Null, ArrayEntryType::Object(_) | ArrayEntryType::Array(..)) => {
+                array.set_element(index, value)?
+            }
+            (
+                Value::Object(element),
+                elements_type @ (ArrayEntryType::Object(_) | ArrayEntryType::Array(..)),
+            ) => {
+                let elements_type = elements_type
+                    .into_field_type(vm)
+                    .ok_or(VmError::ValidationException)?;
+                if Self::validate_type(vm, elements_type, &value).is_err() {
+                    let message = binary_class_name(vm, element)?;
+                    return Err(vm.throw_exception(
+                        call_stack,
+                        "java/lang/ArrayStoreException",
+                        Some(&message),
+                    ));
+                }
                 array.set_element(index, value)?
             }
             _ => {
@@ -1499,11 +1955,10 @@ impl<'a> CallFrame<'a> {
     fn execute_instanceof(
         &mut self,
         vm: &mut Vm<'a>,
-        call_stack: &mut CallStack<'a>,
         constant_index: u16,
     ) -> Result<(), MethodCallFailed<'a>> {
         let value = self.pop()?;
-        let is_instance_of = self.is_instanceof(vm, call_stack, constant_index, &value)?;
+        let is_instance_of = self.is_instanceof(vm, constant_index, &value)?;
         self.push(Int(is_instance_of as i32))
     }
 
@@ -1514,57 +1969,89 @@ impl<'a> CallFrame<'a> {
         constant_index: u16,
     ) -> Result<(), MethodCallFailed<'a>> {
         let value = self.pop()?;
-        let is_instance_of = self.is_instanceof(vm, call_stack, constant_index, &value)?;
+        if self.is_trivially_castable(vm, constant_index, &value)? {
+            return self.push(value);
+        }
+
+        let is_instance_of = self.is_instanceof(vm, constant_index, &value)?;
         if is_instance_of {
-            self.push(value)
-        } else {
-            Err(MethodCallFailed::InternalError(VmError::ClassCastException))
+            return self.push(value);
+        }
+        let Value::Object(object) = value else {
+            return Err(MethodCallFailed::InternalError(
+                VmError::ValidationException,
+            ));
+        };
+        let message = format!(
+            "{} cannot be cast to {}",
+            binary_class_name(vm, &object)?,
+            self.get_constant_class_reference(constant_index)?
+                .replace('/', ".")
+        );
+        Err(vm.throw_exception(call_stack, "java/lang/ClassCastException", Some(&message)))
+    }
+
+    // Casting null, or an object to its exact class, always succeeds: there is no need
+    // to resolve the target class or to walk the hierarchy
+    fn is_trivially_castable(
+        &self,
+        vm: &Vm<'a>,
+        constant_index: u16,
+        value: &Value<'a>,
+    ) -> Result<bool, MethodCallFailed<'a>> {
+        match value {
+            Null => Ok(true),
+            Value::Object(object) if object.kind() == ObjectKind::Object => {
+                let class_name = self.get_constant_class_reference(constant_index)?;
+                let object_class = vm.get_class_by_id(object.class_id())?;
+                Ok(object_class.name == class_name)
+            }
+            _ => Ok(false),
         }
     }
 
-    // Pops a value from the stack and returns whether the cast is valid or not, and the popped value
+    // Returns whether the value is an instance of the class referred by the constant. Null is
+    // never an instance of anything: checkcast accepts it before calling this function.
     fn is_instanceof(
         &mut self,
         vm: &mut Vm<'a>,
-        call_stack: &mut CallStack<'a>,
         constant_index: u16,
         value: &Value<'a>,
     ) -> Result<bool, MethodCallFailed<'a>> {
         let class_name = self.get_constant_class_reference(constant_index)?;
 
-        // TODO: we should model classes of arrays
-        // TODO: multidimensional arrays are not supported!
-        let (is_array, expected_class) = {
-            if class_name.starts_with("[L") && class_name.ends_with(';') {
-                (
-                    true,
-                    vm.get_or_resolve_class(call_stack, &class_name[2..class_name.len() - 1])?,
-                )
-            } else {
-                (false, vm.get_or_resolve_class(call_stack, class_name)?)
+        // Arrays have no class to resolve: we compare the type of their elements instead,
+        // after loading the class of the innermost ones
+        if class_name.starts_with('[') {
+            let expected_type = FieldType::parse(class_name)
+                .map_err(|_| MethodCallFailed::InternalError(VmError::ValidationException))?;
+            if let Some(class_name) = Self::innermost_class_name(&expected_type) {
+                vm.get_or_load_class(class_name)?;
             }
-        };
+            return match value {
+                Null => Ok(false),
+                Value::Object(_) => Ok(value.matches_type(expected_type, vm, |class_name| {
+                    vm.find_class_by_name(class_name)
+                })),
+                _ => Err(MethodCallFailed::InternalError(
+                    VmError::ValidationException,
+                )),
+            };
+        }
 
+        let expected_class = vm.get_or_load_class(class_name)?;
         let is_instance_of = match &value {
             Null => false,
 
             Value::Object(object) => match object.kind() {
                 ObjectKind::Object => {
-                    if is_array {
-                        false
-                    } else {
-                        let object_class = vm.get_class_by_id(object.class_id())?;
-                        object_class.is_subclass_of(expected_class)
-                    }
+                    vm.is_assignable_from(object.class_id(), &expected_class.name)?
                 }
-                ObjectKind::Array => match object.elements_type() {
-                    ArrayEntryType::Base(_) => false,
-                    ArrayEntryType::Object(elements_class_id) => {
-                        let components_class = vm.get_class_by_id(elements_class_id)?;
-                        components_class.is_subclass_of(expected_class)
-                    }
-                    ArrayEntryType::Array => false,
-                },
+                // Arrays only extend Object and implement Cloneable and Serializable
+                ObjectKind::Array => matches!(
+                    expected_class.name.as_str(),
+                    "java/lang/Object" | "java/lang/Cloneable" | "java/io/Serializable"
+                ),
             },
 
             _ => {
@@ -1576,12 +2063,24 @@ impl<'a> CallFrame<'a> {
         Ok(is_instance_of)
     }
 
+    fn innermost_class_name(field_type: &FieldType) -> Option<&str> {
+        match field_type {
+            FieldType::Base(_) => None,
+            FieldType::Object(class_name) => Some(class_name),
+            FieldType::Array(component_type) => Self::innermost_class_name(component_type),
+        }
+    }
+
     fn execute_getfield(
         &mut self,
         vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
         field_index: u16,
     ) -> Result<(), MethodCallFailed<'a>> {
         let object = self.pop()?;
+        if let Null = object {
+            return Err(vm.throw_npe(call_stack));
+        }
         if let Value::Object(object_ref) = object {
             if object_ref.kind() == ObjectKind::Object {
                 let field_reference = self.get_constant_field_reference(field_index)?;
@@ -1601,10 +2100,14 @@ impl<'a> CallFrame<'a> {
     fn execute_putfield(
         &mut self,
         vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
         field_index: u16,
     ) -> Result<(), MethodCallFailed<'a>> {
         let value = self.pop()?;
         let object = self.pop()?;
+        if let Null = object {
+            return Err(vm.throw_npe(call_stack));
+        }
         if let Value::Object(object_ref) = object {
             if object_ref.kind() == ObjectKind::Object {
                 let field_reference = self.get_constant_field_reference(field_index)?;
@@ -1626,10 +2129,8 @@ impl<'a> CallFrame<'a> {
         call_stack: &mut CallStack<'a>,
         field_index: u16,
     ) -> Result<(), MethodCallFailed<'a>> {
-        let field_reference = self.get_constant_field_reference(field_index)?;
-        let object_class = vm.get_or_resolve_class(call_stack, field_reference.class_name)?;
-        let (index, field) = Self::get_field(object_class, field_reference)?;
-        let object = vm.get_static_instance(self.class_and_method.class.id);
+        let (object_class, index, field) = self.get_static_field(vm, call_stack, field_index)?;
+        let object = vm.get_static_instance(object_class.id);
         if let Some(object_ref) = object {
             if object_ref.kind() == ObjectKind::Object {
                 let field_value = object_ref.get_field(object_class, index);
@@ -1649,12 +2150,10 @@ impl<'a> CallFrame<'a> {
         call_stack: &mut CallStack<'a>,
         field_index: u16,
     ) -> Result<(), MethodCallFailed<'a>> {
-        let field_reference = self.get_constant_field_reference(field_index)?;
-        let object_class = vm.get_or_resolve_class(call_stack, field_reference.class_name)?;
-        let (index, field) = Self::get_field(object_class, field_reference)?;
+        let (object_class, index, field) = self.get_static_field(vm, call_stack, field_index)?;
         let value = self.pop()?;
         Self::validate_type(vm, field.type_descriptor.clone(), &value)?;
-        let object = vm.get_static_instance(self.class_and_method.class.id);
+        let object = vm.get_static_instance(object_class.id);
         if let Some(object_ref) = object {
             if object_ref.kind() == ObjectKind::Object {
                 object_ref.set_field(index, value);
@@ -1666,7 +2165,11 @@ impl<'a> CallFrame<'a> {
         ))
     }
 
-    fn execute_monitorenter(&mut self) -> Result<(), MethodCallFailed<'a>> {
+    fn execute_monitorenter(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+    ) -> Result<(), MethodCallFailed<'a>> {
         let obj = self.pop()?;
         match obj {
             Value::Object(_) => {
@@ -1674,13 +2177,18 @@ impl<'a> CallFrame<'a> {
                 // so any monitor access will succeed!
                 Ok(())
             }
+            Null => Err(vm.throw_npe(call_stack)),
             _ => Err(MethodCallFailed::InternalError(
                 VmError::ValidationException,
             )),
         }
     }
 
-    fn execute_monitorexit(&mut self) -> Result<(), MethodCallFailed<'a>> {
+    fn execute_monitorexit(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+    ) -> Result<(), MethodCallFailed<'a>> {
         let obj = self.pop()?;
         match obj {
             Value::Object(_) => {
@@ -1688,28 +2196,50 @@ impl<'a> CallFrame<'a> {
                 // so any monitor access will succeed!
                 Ok(())
             }
+            Null => Err(vm.throw_npe(call_stack)),
             _ => Err(MethodCallFailed::InternalError(
                 VmError::ValidationException,
             )),
         }
     }
 
-    fn execute_athrow(&mut self) -> Result<(), MethodCallFailed<'a>> {
+    fn execute_athrow(
+        &mut self,
+        vm: &mut Vm<'a>,
+        call_stack: &mut CallStack<'a>,
+    ) -> Result<(), MethodCallFailed<'a>> {
         let obj = self.pop()?;
         match obj {
+            // The verifier would reject such bytecode, but we do not have one
+            Value::Object(object) if !Self::is_throwable(vm, &object)? => {
+                let message = format!(
+                    "Can only throw instances of java.lang.Throwable, got {}",
+                    binary_class_name(vm, &object)?
+                );
+                Err(vm.throw_exception(call_stack, "java/lang/VerifyError", Some(&message)))
+            }
             Value::Object(exception) => {
                 Err(MethodCallFailed::ExceptionThrown(JavaException(exception)))
             }
+            Null => Err(vm.throw_npe(call_stack)),
             _ => Err(MethodCallFailed::InternalError(
                 VmError::ValidationException,
             )),
         }
     }
 
+    fn is_throwable(vm: &mut Vm<'a>, object: &AbstractObject<'a>) -> Result<bool, VmError> {
+        if object.kind() == ObjectKind::Array {
+            return Ok(false);
+        }
+        let throwable_class = vm.get_or_load_class("java/lang/Throwable")?;
+        let class = vm.get_class_by_id(object.class_id())?;
+        Ok(class.is_subclass_of(throwable_class))
+    }
+
     fn find_exception_handler(
         &self,
         vm: &mut Vm<'a>,
-        call_stack: &mut CallStack<'a>,
         executed_instruction_pc: ProgramCounter,
         exception: &JavaException<'a>,
     ) -> Result<Option<ProgramCounter>, MethodCallFailed<'a>> {
@@ -1730,7 +2260,7 @@ impl<'a> CallFrame<'a> {
             match &catch_handler.catch_class {
                 None => return Ok(Some(catch_handler.handler_pc)),
                 Some(class_name) => {
-                    let catch_class = vm.get_or_resolve_class(call_stack, class_name)?;
+                    let catch_class = vm.get_or_load_class(class_name)?;
                     let exception_class = vm.get_class_by_id(exception.0.class_id())?;
                     if exception_class.is_subclass_of(catch_class) {
                         return Ok(Some(catch_handler.handler_pc));
@@ -8,19 +8,34 @@ use rjvm_reader::{
 
 use crate::{
     abstract_object::AbstractObject, call_frame::CallFrame, class_and_method::ClassAndMethod,
-    stack_trace_element::StackTraceElement, value::Value, vm_error::VmError,
+    exceptions::MethodCallFailed, stack_trace_element::StackTraceElement, value::Value, vm::Vm,
+    vm_error::VmError,
 };
 
+/// The default maximum number of frames of a call stack. Each java frame needs a few KBs
+/// of native stack in debug builds, so this fits comfortably in the usual 8MB.
+pub const DEFAULT_MAX_DEPTH: usize = 512;
+
+/// How many frames beyond the maximum depth can be used to create the
+/// `java.lang.StackOverflowError`, since that requires invoking its constructors
+const STACK_OVERFLOW_RESERVE: usize = 16;
+
 /// A call stack, which will include multiple frames, one for each method call.
 // The allocator will allocate and ensure that our call frames are alive while the call stack is.
 // Thus, we can do some unsafe magic to avoid Rc<RefCell<>>, which would mess up our code when
 // we try to get a stack trace _while_ executing a method, which we need for exceptions.
 // This also means that we _never_ deallocate the call frames, even after we have finished
 // executing them!
-#[derive(Default)]
 pub struct CallStack<'a> {
     frames: Vec<CallFrameReference<'a>>,
     allocator: Arena<CallFrame<'a>>,
+    max_depth: usize,
+}
+
+impl<'a> Default for CallStack<'a> {
+    fn default() -> Self {
+        Self::with_max_depth(DEFAULT_MAX_DEPTH)
+    }
 }
 
 // SAFETY: The pointer will be valid until the generating call stack is,
@@ -46,15 +61,41 @@ impl<'a> CallStack<'a> {
         Default::default()
     }
 
+    /// Creates a call stack that can contain at most the given number of frames. Adding more
+    /// will throw a `java.lang.StackOverflowError`, rather than overflowing the real stack.
+    pub fn with_max_depth(max_depth: usize) -> Self {
+        Self {
+            frames: Default::default(),
+            allocator: Default::default(),
+            max_depth,
+        }
+    }
+
+    /// Returns the current number of frames
+    pub fn depth(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
     /// Adds a new frame to the call stack.
-    /// Only supports bytecode methods (i.e. non native).
+    /// Only supports bytecode methods (i.e. non native and non abstract).
     pub fn add_frame(
         &mut self,
+        vm: &mut Vm<'a>,
         class_and_method: ClassAndMethod<'a>,
         receiver: Option<AbstractObject<'a>>,
         args: Vec<Value<'a>>,
-    ) -> Result<CallFrameReference<'a>, VmError> {
-        Self::check_receiver(&class_and_method, receiver.clone())?;
+    ) -> Result<CallFrameReference<'a>, MethodCallFailed<'a>> {
+        if self.frames.len() >= self.max_depth {
+            return Err(self.throw_stack_overflow(vm));
+        }
+        match Self::check_receiver(&class_and_method, receiver.clone()) {
+            Err(VmError::NullPointerException) => return Err(vm.throw_npe(self)),
+            result => result?,
+        }
         let code = Self::get_code(&class_and_method)?;
         let locals = Self::prepare_locals(code, receiver, args);
         let new_frame = self
@@ -66,6 +107,14 @@ impl<'a> CallStack<'a> {
         Ok(reference)
     }
 
+    fn throw_stack_overflow(&mut self, vm: &mut Vm<'a>) -> MethodCallFailed<'a> {
+        let max_depth = self.max_depth;
+        self.max_depth += STACK_OVERFLOW_RESERVE;
+        let error = vm.throw_exception(self, "java/lang/StackOverflowError", None);
+        self.max_depth = max_depth;
+        error
+    }
+
     fn check_receiver(
         class_and_method: &ClassAndMethod,
         receiver: Option<AbstractObject>,
@@ -87,11 +136,16 @@ impl<'a> CallStack<'a> {
             return Err(VmError::NotImplemented);
         };
 
-        let code = &class_and_method.method.code.as_ref().unwrap();
-        Ok(code)
+        // Abstract methods have no code either
+        class_and_method
+            .method
+            .code
+            .as_ref()
+            .ok_or(VmError::ValidationException)
     }
 
-    /// Returns a Vec filled with one `Unitialized` per variable
+    /// Returns the locals of a new frame: the receiver, if any, goes in slot 0 and the
+    /// arguments follow it. The remaining variables are filled with `Uninitialized`.
     fn prepare_locals(
         code: &ClassFileMethodCode,
         receiver: Option<AbstractObject<'a>>,
@@ -100,7 +154,7 @@ impl<'a> CallStack<'a> {
         let mut locals: Vec<Value<'a>> = receiver
             .map(Value::Object)
             .into_iter()
-            .chain(args.into_iter())
+            .chain(args)
             .collect();
         while locals.len() < code.max_locals.into_usize_safe() {
             locals.push(Value::Uninitialized);
@@ -136,6 +190,10 @@ impl<'a> CallStack<'a> {
 
 impl<'a> fmt::Debug for CallStack<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "CallStack{{frames={:?}}}", self.frames)
+        write!(
+            f,
+            "CallStack{{frames={:?}, max_depth={}}}",
+            self.frames, self.max_depth
+        )
     }
 }
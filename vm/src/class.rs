@@ -1,10 +1,17 @@
-use std::{fmt, fmt::Formatter};
+use std::{
+    cell::{Cell, OnceCell},
+    fmt,
+    fmt::Formatter,
+};
 
 use rjvm_reader::{
-    class_access_flags::ClassAccessFlags, class_file_field::ClassFileField,
-    class_file_method::ClassFileMethod, constant_pool::ConstantPool,
+    class_access_flags::ClassAccessFlags, class_file::ClassFile, class_file_field::ClassFileField,
+    class_file_method::ClassFileMethod, constant_pool::ConstantPool, field_flags::FieldFlags,
+    field_type::FieldType,
 };
 
+use crate::{constant_pool_view::ConstantPoolView, decoded_code::DecodedCode};
+
 /// In various data structures, we store the class id of the object, i..e. a progressive
 /// number assigned when we load the class. Note that, while we do not support it yet,
 /// multiple class loaders could load the same class more than once, but they would be
@@ -29,6 +36,20 @@ impl ClassId {
     }
 }
 
+/// The state of the initialization of a class, i.e. of the execution of its `<clinit>`,
+/// which happens at the first active use of the class
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ClassInitState {
+    /// The class has been loaded and linked, but its static initializer has not run yet
+    #[default]
+    Unprepared,
+    /// The static initializer is currently executing
+    InProgress,
+    Initialized,
+    /// The static initializer has failed, thus the class cannot be used
+    Erroneous,
+}
+
 /// A loaded java class
 #[derive(Debug)]
 pub struct Class<'a> {
@@ -49,21 +70,112 @@ pub struct Class<'a> {
     pub first_field_index: usize,
     // The total number of fields in this class, including those in the base class.
     pub num_total_fields: usize,
+    /// Classes are shared, so the state is modified by the vm via interior mutability
+    pub(crate) init_state: Cell<ClassInitState>,
+    /// The decoded instructions of each method, in the same order as [Class::methods],
+    /// created the first time the method is executed
+    pub(crate) decoded_code: Vec<OnceCell<DecodedCode>>,
 }
 
 pub type ClassRef<'a> = &'a Class<'a>;
 
 impl<'a> Class<'a> {
+    /// Creates a class, not yet initialized, from its class file and its already
+    /// resolved superclass and interfaces
+    pub fn new(
+        id: ClassId,
+        class_file: ClassFile,
+        superclass: Option<ClassRef<'a>>,
+        interfaces: Vec<ClassRef<'a>>,
+    ) -> Self {
+        let num_superclass_fields = match superclass {
+            Some(superclass) => superclass.num_total_fields,
+            None => 0,
+        };
+        let num_this_class_fields = class_file.fields.len();
+        let decoded_code = Self::empty_decoded_code(&class_file.methods);
+
+        Self {
+            id,
+            name: class_file.name,
+            source_file: class_file.source_file,
+            constants: class_file.constants,
+            flags: class_file.flags,
+            superclass,
+            interfaces,
+            fields: class_file.fields,
+            methods: class_file.methods,
+            num_total_fields: num_superclass_fields + num_this_class_fields,
+            first_field_index: num_superclass_fields,
+            init_state: Default::default(),
+            decoded_code,
+        }
+    }
+
+    /// Returns the cache of the decoded instructions for the given methods, empty at first
+    pub(crate) fn empty_decoded_code(methods: &[ClassFileMethod]) -> Vec<OnceCell<DecodedCode>> {
+        methods.iter().map(|_| OnceCell::new()).collect()
+    }
+
+    /// Returns the decoded instructions of the given method of this class, if it has code
+    pub(crate) fn decoded_code(&self, method: &ClassFileMethod) -> Option<&DecodedCode> {
+        let index = (method as *const ClassFileMethod as usize)
+            .checked_sub(self.methods.as_ptr() as usize)?
+            / size_of::<ClassFileMethod>();
+        if !std::ptr::eq(self.methods.get(index)?, method) {
+            return None;
+        }
+        let code_length = method.code.as_ref()?.code.len();
+        Some(self.decoded_code[index].get_or_init(|| DecodedCode::new(code_length)))
+    }
+
+    /// Returns a view of the constant pool that resolves the references between its entries
+    pub fn constant_pool(&self) -> ConstantPoolView<'_> {
+        ConstantPoolView::new(&self.constants)
+    }
+
+    pub fn init_state(&self) -> ClassInitState {
+        self.init_state.get()
+    }
+
     /// Returns whether self is a subclass of the given class, or implements
     /// the given interface
     pub fn is_subclass_of(&self, base: ClassRef) -> bool {
-        self.name == base.name
+        self.is_subclass_of_class_named(&base.name)
+    }
+
+    /// Same as [Class::is_subclass_of], but does not require the base class to be loaded
+    pub fn is_subclass_of_class_named(&self, base_name: &str) -> bool {
+        self.name == base_name
             || self
                 .superclass
-                .map_or(false, |superclass| superclass.is_subclass_of(base))
-            || self.interfaces.iter().any(|intf| intf.is_subclass_of(base))
+                .is_some_and(|superclass| superclass.is_subclass_of_class_named(base_name))
+            || self
+                .interfaces
+                .iter()
+                .any(|intf| intf.is_subclass_of_class_named(base_name))
     }
 
+    /// Returns the name of the package of the class, in internal form (i.e. `java/lang`),
+    /// which is empty for the default package
+    pub fn package_name(&self) -> &str {
+        self.name
+            .rfind('/')
+            .map_or("", |separator| &self.name[..separator])
+    }
+
+    /// Returns the methods declared by this class, excluding the inherited ones
+    pub fn methods(&self) -> &[ClassFileMethod] {
+        &self.methods
+    }
+
+    /// Returns the fields declared by this class, excluding the inherited ones
+    pub fn fields(&self) -> &[ClassFileField] {
+        &self.fields
+    }
+
+    /// Searches only the methods declared by this class: method resolution, which includes
+    /// the inherited ones, is done by the vm according to the kind of invocation
     pub fn find_method(
         &self,
         method_name: &str,
@@ -91,6 +203,34 @@ impl<'a> Class<'a> {
             })
     }
 
+    /// Resolves a static field as described by the JVM specs: it might be declared by
+    /// this class, by one of its superinterfaces or by one of its superclasses. Only the
+    /// static fields with the given name and type match. Returns the class declaring the
+    /// field, whose static instance holds its value, together with the field's index.
+    pub fn find_static_field(
+        &'a self,
+        field_name: &str,
+        field_type: &FieldType,
+    ) -> Option<(ClassRef<'a>, usize, &'a ClassFileField)> {
+        self.fields
+            .iter()
+            .position(|field| {
+                field.flags.contains(FieldFlags::STATIC)
+                    && field.name == field_name
+                    && field.type_descriptor == *field_type
+            })
+            .map(|index| (self, index + self.first_field_index, &self.fields[index]))
+            .or_else(|| {
+                self.interfaces
+                    .iter()
+                    .find_map(|interface| interface.find_static_field(field_name, field_type))
+            })
+            .or_else(|| {
+                self.superclass
+                    .and_then(|superclass| superclass.find_static_field(field_name, field_type))
+            })
+    }
+
     pub fn field_at_index(&self, index: usize) -> Option<&ClassFileField> {
         if index < self.first_field_index {
             self.superclass
@@ -100,6 +240,16 @@ impl<'a> Class<'a> {
         }
     }
 
+    /// Returns the static fields declared by this class (not its superclasses),
+    /// together with their index
+    pub fn static_fields(&self) -> impl Iterator<Item = (usize, &ClassFileField)> {
+        self.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| field.flags.contains(FieldFlags::STATIC))
+            .map(|(index, field)| (index + self.first_field_index, field))
+    }
+
     pub fn all_fields(&self) -> impl Iterator<Item = &ClassFileField> {
         let mut all_fields = Vec::from_iter(
             self.superclass
@@ -26,6 +26,10 @@ impl<'a> ClassAndMethod<'a> {
         self.method.is_native()
     }
 
+    pub fn is_abstract(&self) -> bool {
+        self.method.is_abstract()
+    }
+
     pub fn is_void(&self) -> bool {
         self.method.is_void()
     }
@@ -33,4 +37,15 @@ impl<'a> ClassAndMethod<'a> {
     pub fn returns(&self, expected_type: FieldType) -> bool {
         self.method.returns(expected_type)
     }
+
+    /// Returns a description such as `java.lang.Object.hashCode()I`, used in the
+    /// messages of the errors thrown by the vm
+    pub fn description(&self) -> String {
+        format!(
+            "{}.{}{}",
+            self.class.name.replace('/', "."),
+            self.method.name,
+            self.method.type_descriptor
+        )
+    }
 }
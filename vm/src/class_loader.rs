@@ -18,6 +18,10 @@ impl<'a> ClassLoader<'a> {
         self.classes_by_name.insert(class.name.clone(), class);
     }
 
+    pub fn forget_class(&mut self, name: &str) -> Option<ClassRef<'a>> {
+        self.classes_by_name.remove(name)
+    }
+
     pub fn find_class_by_name(&self, name: &str) -> Option<ClassRef<'a>> {
         self.classes_by_name.get(name).cloned()
     }
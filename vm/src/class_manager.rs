@@ -1,4 +1,4 @@
-use std::{collections::HashMap, fmt, fmt::Formatter};
+use std::{cell::Cell, collections::HashMap, fmt, fmt::Formatter};
 
 use indexmap::IndexMap;
 use log::debug;
@@ -91,6 +91,13 @@ impl<'a> ClassManager<'a> {
         self.classes_by_name.get(class_name).cloned()
     }
 
+    /// Returns the classes that can be found by name, in the order they were loaded
+    pub fn loaded_classes(&self) -> Vec<ClassRef<'a>> {
+        let mut classes: Vec<ClassRef<'a>> = self.classes_by_name.values().cloned().collect();
+        classes.sort_by_key(|class| class.id.as_u32());
+        classes
+    }
+
     pub fn get_or_resolve_class(&mut self, class_name: &str) -> Result<ResolvedClass<'a>, VmError> {
         if let Some(already_loaded_class) = self.find_class_by_name(class_name) {
             Ok(ResolvedClass::AlreadyLoaded(already_loaded_class))
@@ -156,17 +163,7 @@ impl<'a> ClassManager<'a> {
         let id = ClassId::new(next_id);
         debug!("loading class {} from file {}", id, class_file.name);
         let class = Self::new_class(class_file, id, &referenced_classes)?;
-        let class_ref = self.arena.alloc(class);
-
-        // SAFETY: our reference class_ref is alive only for 'b.
-        // However we actually know that the arena will keep the value alive for 'a,
-        // and I cannot find a way to convince the compiler of this fact. Thus
-        // I'm using this pointer "trick" to make the compiler happy.
-        // I expect this can be done with safe Rust, I just do not know how at the moment...
-        let class_ref = unsafe {
-            let class_ptr: *const Class<'a> = class_ref;
-            &*class_ptr
-        };
+        let class_ref = self.alloc_class(class);
 
         let mut classes_to_init: Vec<ClassRef<'a>> = Vec::new();
         for resolved_class in referenced_classes.values() {
@@ -193,6 +190,99 @@ impl<'a> ClassManager<'a> {
         })
     }
 
+    fn alloc_class(&mut self, class: Class<'a>) -> ClassRef<'a> {
+        let class_ref = self.arena.alloc(class);
+
+        // SAFETY: our reference class_ref is alive only for 'b.
+        // However we actually know that the arena will keep the value alive for 'a,
+        // and I cannot find a way to convince the compiler of this fact. Thus
+        // I'm using this pointer "trick" to make the compiler happy.
+        // I expect this can be done with safe Rust, I just do not know how at the moment...
+        unsafe {
+            let class_ptr: *const Class<'a> = class_ref;
+            &*class_ptr
+        }
+    }
+
+    /// Replaces an already loaded class with a new definition, which keeps the same id.
+    /// Only the methods' code can change: the superclass, the interfaces, the fields and
+    /// the methods' signatures must be the same of the old definition.
+    /// The old definition is kept alive, since it might still be executing.
+    pub fn redefine_class(
+        &mut self,
+        class_name: &str,
+        class_file_bytes: &[u8],
+    ) -> Result<ClassRef<'a>, VmError> {
+        let old_class = self
+            .find_class_by_name(class_name)
+            .ok_or(VmError::ClassNotFoundException(class_name.to_string()))?;
+        let class_file = class_reader::read_buffer(class_file_bytes)
+            .map_err(|err| VmError::ClassLoadingError(err.to_string()))?;
+        Self::validate_redefinition(old_class, &class_file)?;
+
+        debug!("redefining class {} with id {}", class_name, old_class.id);
+        let decoded_code = Class::empty_decoded_code(&class_file.methods);
+        let class = Class {
+            id: old_class.id,
+            name: class_file.name,
+            source_file: class_file.source_file,
+            constants: class_file.constants,
+            flags: class_file.flags,
+            superclass: old_class.superclass,
+            interfaces: old_class.interfaces.clone(),
+            fields: class_file.fields,
+            methods: class_file.methods,
+            first_field_index: old_class.first_field_index,
+            num_total_fields: old_class.num_total_fields,
+            init_state: Cell::new(old_class.init_state()),
+            decoded_code,
+        };
+        let class_ref = self.alloc_class(class);
+        self.register_loaded_class(class_ref);
+        Ok(class_ref)
+    }
+
+    fn validate_redefinition(
+        old_class: ClassRef<'a>,
+        class_file: &ClassFile,
+    ) -> Result<(), VmError> {
+        let unsupported = |what: &str| {
+            Err(VmError::ClassLoadingError(format!(
+                "unsupported redefinition of class {}: {what} changed",
+                old_class.name
+            )))
+        };
+
+        if class_file.name != old_class.name {
+            return unsupported("name");
+        }
+        if class_file.superclass.as_ref() != old_class.superclass.map(|class| &class.name) {
+            return unsupported("superclass");
+        }
+        if !class_file
+            .interfaces
+            .iter()
+            .eq(old_class.interfaces.iter().map(|class| &class.name))
+        {
+            return unsupported("interfaces");
+        }
+        if class_file.fields != old_class.fields {
+            return unsupported("fields");
+        }
+        let signatures_match = class_file.methods.len() == old_class.methods.len()
+            && class_file.methods.iter().zip(old_class.methods.iter()).all(
+                |(new_method, old_method)| {
+                    new_method.name == old_method.name
+                        && new_method.type_descriptor == old_method.type_descriptor
+                        && new_method.flags == old_method.flags
+                },
+            );
+        if !signatures_match {
+            return unsupported("methods");
+        }
+        Ok(())
+    }
+
     fn new_class(
         class_file: ClassFile,
         id: ClassId,
@@ -208,25 +298,15 @@ impl<'a> ClassManager<'a> {
             .map(|interface_name| resolved_classes.get(interface_name).unwrap().get_class())
             .collect();
 
-        let num_superclass_fields = match superclass {
-            Some(superclass) => superclass.num_total_fields,
-            None => 0,
-        };
-        let num_this_class_fields = class_file.fields.len();
+        Ok(Class::new(id, class_file, superclass, interfaces))
+    }
 
-        Ok(Class {
-            id,
-            name: class_file.name,
-            source_file: class_file.source_file,
-            constants: class_file.constants,
-            flags: class_file.flags,
-            superclass,
-            interfaces,
-            fields: class_file.fields,
-            methods: class_file.methods,
-            num_total_fields: num_superclass_fields + num_this_class_fields,
-            first_field_index: num_superclass_fields,
-        })
+    /// Removes the class with the given name, so that it will be loaded again when
+    /// requested. The class remains resolvable by id, since there might still be
+    /// instances of it, or subclasses, whose layout depends on it.
+    pub fn forget_class(&mut self, class_name: &str) -> Option<ClassRef<'a>> {
+        self.current_class_loader.forget_class(class_name);
+        self.classes_by_name.remove(class_name)
     }
 
     fn register_loaded_class(&mut self, class: ClassRef<'a>) {
@@ -1,6 +1,50 @@
-use crate::class::{ClassId, ClassRef};
+use crate::{
+    abstract_object::{AbstractObject, ObjectKind},
+    array::Array,
+    class::{ClassId, ClassRef},
+    object::Object,
+    vm_error::VmError,
+};
 
 /// Trait that models the fact that a class can be resolved by its given id
 pub trait ClassByIdResolver<'a> {
     fn find_class_by_id(&self, class_id: ClassId) -> Option<ClassRef<'a>>;
 }
+
+/// Returns the name of the class of the object, as returned by `Class::getName`
+pub(crate) fn binary_class_name<'a>(
+    class_resolver: &impl ClassByIdResolver<'a>,
+    object: &AbstractObject<'a>,
+) -> Result<String, VmError> {
+    match object.kind() {
+        ObjectKind::Array => Ok(format!(
+            "[{}",
+            array_component_descriptor(class_resolver, object)?
+        )),
+        ObjectKind::Object => Ok(find_class(class_resolver, object.class_id())?
+            .name
+            .replace('/', ".")),
+    }
+}
+
+/// Returns the descriptor of the array's component type, using dots as the package separator,
+/// which is the format used by `Class::getName()` for arrays
+fn array_component_descriptor<'a>(
+    class_resolver: &impl ClassByIdResolver<'a>,
+    array: &AbstractObject<'a>,
+) -> Result<String, VmError> {
+    array
+        .elements_type()
+        .into_field_type(class_resolver)
+        .map(|component_type| component_type.to_descriptor().replace('/', "."))
+        .ok_or(VmError::ValidationException)
+}
+
+fn find_class<'a>(
+    class_resolver: &impl ClassByIdResolver<'a>,
+    class_id: ClassId,
+) -> Result<ClassRef<'a>, VmError> {
+    class_resolver
+        .find_class_by_id(class_id)
+        .ok_or(VmError::ValidationException)
+}
@@ -0,0 +1,105 @@
+use rjvm_reader::constant_pool::{ConstantPool, ConstantPoolEntry};
+
+/// A constant used by the bytecode, with the references to the other entries of the pool,
+/// such as the name of a class or the name and type of a method, already followed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResolvedConstant<'c> {
+    Integer(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
+    String(&'c str),
+    Class(&'c str),
+    FieldReference(MemberReference<'c>),
+    MethodReference(MemberReference<'c>),
+    InterfaceMethodReference(MemberReference<'c>),
+}
+
+/// A reference to a field or to a method
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemberReference<'c> {
+    pub class_name: &'c str,
+    pub name: &'c str,
+    pub type_descriptor: &'c str,
+}
+
+/// A read-only view of the constant pool of a loaded class, meant for tools that
+/// need to inspect it. Only the entries that can be used by the bytecode are exposed:
+/// utf8 strings and names and types are returned as part of the entries that refer to them.
+#[derive(Debug, Clone, Copy)]
+pub struct ConstantPoolView<'c> {
+    constants: &'c ConstantPool,
+}
+
+impl<'c> ConstantPoolView<'c> {
+    pub(crate) fn new(constants: &'c ConstantPool) -> Self {
+        Self { constants }
+    }
+
+    /// Returns the constant at the given 1-based index, or `None` if the index is invalid
+    /// or the entry is not one of the exposed kinds
+    pub fn get(&self, index: u16) -> Option<ResolvedConstant<'c>> {
+        let constant = match self.constants.get(index).ok()? {
+            ConstantPoolEntry::Integer(value) => ResolvedConstant::Integer(*value),
+            ConstantPoolEntry::Float(value) => ResolvedConstant::Float(*value),
+            ConstantPoolEntry::Long(value) => ResolvedConstant::Long(*value),
+            ConstantPoolEntry::Double(value) => ResolvedConstant::Double(*value),
+            ConstantPoolEntry::StringReference(string_index) => {
+                ResolvedConstant::String(self.utf8(*string_index)?)
+            }
+            ConstantPoolEntry::ClassReference(name_index) => {
+                ResolvedConstant::Class(self.utf8(*name_index)?)
+            }
+            ConstantPoolEntry::FieldReference(class_index, name_and_type_index) => {
+                ResolvedConstant::FieldReference(self.member(*class_index, *name_and_type_index)?)
+            }
+            ConstantPoolEntry::MethodReference(class_index, name_and_type_index) => {
+                ResolvedConstant::MethodReference(self.member(*class_index, *name_and_type_index)?)
+            }
+            ConstantPoolEntry::InterfaceMethodReference(class_index, name_and_type_index) => {
+                ResolvedConstant::InterfaceMethodReference(
+                    self.member(*class_index, *name_and_type_index)?,
+                )
+            }
+            _ => return None,
+        };
+        Some(constant)
+    }
+
+    /// Returns all the exposed constants, together with their index
+    pub fn iter(&self) -> impl Iterator<Item = (u16, ResolvedConstant<'c>)> + '_ {
+        (1..=self.constants.len() as u16)
+            .filter_map(|index| self.get(index).map(|constant| (index, constant)))
+    }
+
+    /// Returns the string literals used by the class
+    pub fn strings(&self) -> impl Iterator<Item = &'c str> + '_ {
+        self.iter().filter_map(|(_, constant)| match constant {
+            ResolvedConstant::String(string) => Some(string),
+            _ => None,
+        })
+    }
+
+    fn utf8(&self, index: u16) -> Option<&'c str> {
+        match self.constants.get(index).ok()? {
+            ConstantPoolEntry::Utf8(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    fn member(&self, class_index: u16, name_and_type_index: u16) -> Option<MemberReference<'c>> {
+        let ResolvedConstant::Class(class_name) = self.get(class_index)? else {
+            return None;
+        };
+        let &ConstantPoolEntry::NameAndTypeDescriptor(name_index, type_descriptor_index) =
+            self.constants.get(name_and_type_index).ok()?
+        else {
+            return None;
+        };
+        Some(MemberReference {
+            class_name,
+            name: self.utf8(name_index)?,
+            type_descriptor: self.utf8(type_descriptor_index)?,
+        })
+    }
+}
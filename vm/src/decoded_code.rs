@@ -0,0 +1,34 @@
+use std::cell::OnceCell;
+
+use rjvm_reader::instruction::Instruction;
+
+/// The instructions of a method, each one decoded the first time it is executed. This
+/// spares the call frames from decoding an instruction, and in particular from allocating
+/// the jump table of a switch, every time they execute it.
+#[derive(Debug)]
+pub(crate) struct DecodedCode {
+    /// Indexed by address: the instruction and the address of the next one, or `None` if
+    /// the bytes at that address are not a valid instruction
+    instructions: Vec<OnceCell<Option<(Instruction, usize)>>>,
+}
+
+impl DecodedCode {
+    pub(crate) fn new(code_length: usize) -> Self {
+        Self {
+            instructions: (0..code_length).map(|_| OnceCell::new()).collect(),
+        }
+    }
+
+    /// Returns the instruction at the given address of the code, and the address of the
+    /// next one, decoding it if this is the first time
+    pub(crate) fn instruction_at(
+        &self,
+        code: &[u8],
+        address: usize,
+    ) -> Option<&(Instruction, usize)> {
+        self.instructions
+            .get(address)?
+            .get_or_init(|| Instruction::parse(code, address).ok())
+            .as_ref()
+    }
+}
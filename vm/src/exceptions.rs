@@ -13,10 +13,9 @@ impl<'a> From<VmError> for MethodCallFailed<'a> {
     }
 }
 
-// TODO: need to remove this eventually and manage it with real exceptions
 impl<'a> From<ValueStackError> for MethodCallFailed<'a> {
-    fn from(_: ValueStackError) -> Self {
-        Self::InternalError(VmError::ValidationException)
+    fn from(value: ValueStackError) -> Self {
+        Self::InternalError(VmError::ValueStackError(value))
     }
 }
 
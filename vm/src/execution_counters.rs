@@ -0,0 +1,21 @@
+/// Counters of the work done by the vm, useful to write performance regression tests,
+/// for example to check that a loop does not allocate any object.
+/// See [crate::vm::Vm::execution_counters] and [crate::vm::Vm::reset_execution_counters].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionCounters {
+    /// Number of bytecode instructions executed
+    pub instructions_executed: u64,
+    /// Number of objects allocated, including arrays
+    pub objects_allocated: u64,
+    /// Number of garbage collections run
+    pub garbage_collections: u64,
+    /// Number of invocations of native methods, including intrinsics
+    pub native_calls: u64,
+    /// Number of method references in the constant pools that have been resolved, i.e.
+    /// looked up by class name, method name and descriptor. Each one is resolved only
+    /// once, and then cached.
+    pub method_references_resolved: u64,
+    /// Number of references to static fields in the constant pools that have been
+    /// resolved, which are cached like the method references
+    pub static_field_references_resolved: u64,
+}
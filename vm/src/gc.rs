@@ -5,7 +5,9 @@ use log::{debug, info};
 use rjvm_reader::{field_type::FieldType, type_conversion::ToUsizeSafe};
 
 use crate::{
-    abstract_object::{AbstractObject, AllocHeader, GcState, ObjectKind, ALLOC_HEADER_SIZE},
+    abstract_object::{
+        AbstractObject, AllocHeader, GcState, ObjectKind, ALLOC_HEADER_SIZE, MAX_IDENTITY_HASH_CODE,
+    },
     alloc_entry::AllocEntry,
     array::Array,
     array_entry_type::ArrayEntryType,
@@ -50,7 +52,7 @@ impl MemoryChunk {
     }
 
     /// Allocates from the chunk, or returns None if there is not enough space
-    fn alloc(&mut self, required_size: usize) -> Option<AllocEntry> {
+    fn alloc(&mut self, required_size: usize, identity_hash_code: i32) -> Option<AllocEntry> {
         if self.used + required_size > self.capacity {
             return None;
         }
@@ -64,6 +66,7 @@ impl MemoryChunk {
         Some(AllocEntry {
             ptr,
             alloc_size: required_size,
+            identity_hash_code,
         })
     }
 
@@ -91,9 +94,20 @@ impl MemoryChunk {
 /// Obviously, this wastes half the memory, which is why nobody uses this algorithm
 /// in any real inmplementation. However, it is quite simple, and handles reference cycles,
 /// so it is the one I have chosen here.
+///
+/// Since objects are moved, any reference to them that is not a gc root becomes dangling
+/// after a collection: using it is a logic error.
 pub struct ObjectAllocator<'a> {
     current: MemoryChunk,
     other: MemoryChunk,
+    /// Number of objects and arrays in the current chunk, including the unreachable ones
+    allocated_objects: usize,
+    /// Bytes that were still in use at the end of the last garbage collection
+    used_after_last_gc: usize,
+    /// Identity hash code of the next allocated object. Since objects keep their hash code
+    /// when they are moved, we cannot derive it from their address: two live objects could
+    /// end up with the same one.
+    next_identity_hash_code: i32,
     marker: PhantomData<&'a AbstractObject<'a>>,
 }
 
@@ -103,6 +117,9 @@ impl<'a> ObjectAllocator<'a> {
         Self {
             current: MemoryChunk::new(semi_space_capacity),
             other: MemoryChunk::new(semi_space_capacity),
+            allocated_objects: 0,
+            used_after_last_gc: 0,
+            next_identity_hash_code: 1,
             marker: Default::default(),
         }
     }
@@ -110,9 +127,13 @@ impl<'a> ObjectAllocator<'a> {
     /// Allocates a new object, or returns None if the memory is full
     pub fn allocate_object(&mut self, class: &Class<'a>) -> Option<AbstractObject<'a>> {
         let size = AbstractObject::size_of_object(class);
-        self.current
-            .alloc(size)
-            .map(|alloc_entry| AbstractObject::new_object(class, alloc_entry))
+        let identity_hash_code = self.next_identity_hash_code();
+        let object = self
+            .current
+            .alloc(size, identity_hash_code)
+            .map(|alloc_entry| AbstractObject::new_object(class, alloc_entry))?;
+        self.allocated_objects += 1;
+        Some(object)
     }
 
     /// Allocates a new array, or returns None if the memory is full
@@ -122,15 +143,50 @@ impl<'a> ObjectAllocator<'a> {
         length: usize,
     ) -> Option<AbstractObject<'a>> {
         let size = AbstractObject::size_of_array(length);
-        self.current
-            .alloc(size)
-            .map(|alloc_entry| AbstractObject::new_array(elements_type, length, &alloc_entry))
+        let identity_hash_code = self.next_identity_hash_code();
+        let array = self
+            .current
+            .alloc(size, identity_hash_code)
+            .map(|alloc_entry| AbstractObject::new_array(elements_type, length, &alloc_entry))?;
+        self.allocated_objects += 1;
+        Some(array)
+    }
+
+    fn next_identity_hash_code(&mut self) -> i32 {
+        let identity_hash_code = self.next_identity_hash_code;
+        // The hash code is stored in 30 bits of the AllocHeader, so we wrap around
+        self.next_identity_hash_code = (identity_hash_code + 1) & MAX_IDENTITY_HASH_CODE;
+        identity_hash_code
+    }
+
+    /// Returns the number of bytes currently allocated
+    pub fn used_memory(&self) -> usize {
+        self.current.used
+    }
+
+    /// Returns the number of bytes that can still be allocated before a garbage collection
+    pub fn free_memory(&self) -> usize {
+        self.current.capacity - self.current.used
+    }
+
+    /// Returns the number of bytes allocated since the last garbage collection
+    pub fn allocated_since_last_gc(&self) -> usize {
+        self.current.used - self.used_after_last_gc
+    }
+
+    /// Returns the number of objects and arrays currently allocated. Right after a garbage
+    /// collection, these are exactly the reachable ones.
+    pub fn allocated_objects(&self) -> usize {
+        self.allocated_objects
     }
 
     /// Runs the garbage collection! Will update the roots with the new addresses of the objects.
+    /// The weak roots do not keep their objects alive: they are updated in the same way if
+    /// their object is reachable from the roots, and cleared otherwise.
     pub unsafe fn do_garbage_collection(
         &mut self,
         roots: Vec<*mut AbstractObject<'a>>,
+        weak_roots: Vec<*mut Option<AbstractObject<'a>>>,
         class_resolver: &impl ClassByIdResolver<'a>,
     ) -> Result<(), VmError> {
         info!(
@@ -143,10 +199,13 @@ impl<'a> ObjectAllocator<'a> {
         for root in roots.iter() {
             self.visit(*root, class_resolver)?;
         }
-        self.fix_references_in_new_region(class_resolver)?;
+        self.allocated_objects = self.fix_references_in_new_region(class_resolver)?;
         for root in roots {
             self.fix_gc_root(root);
         }
+        for weak_root in weak_roots {
+            self.fix_weak_root(weak_root);
+        }
 
         // Swap regions and reset alloc pointer
         std::mem::swap(&mut self.current, &mut self.other);
@@ -155,6 +214,7 @@ impl<'a> ObjectAllocator<'a> {
             self.other.used, self.current.used
         );
         self.other.reset();
+        self.used_after_last_gc = self.current.used;
 
         Ok(())
     }
@@ -183,10 +243,11 @@ impl<'a> ObjectAllocator<'a> {
                     self.visit_entries_of_array(&*object_ptr, class_resolver)?;
                 }
 
-                // Copy to other region as-is (with pointers to the current region)
+                // Copy to other region as-is (with pointers to the current region),
+                // header and thus identity hash code included
                 let new_address = self
                     .other
-                    .alloc(header.size())
+                    .alloc(header.size(), header.identity_hash_code())
                     .map(|alloc_entry| {
                         std::ptr::copy_nonoverlapping(
                             referred_object_ptr,
@@ -257,7 +318,7 @@ impl<'a> ObjectAllocator<'a> {
                 // No objects are kept alive by this GC-reachable array!
                 Ok(())
             }
-            ArrayEntryType::Object(_) => {
+            ArrayEntryType::Object(_) | ArrayEntryType::Array(..) => {
                 for i in 0..array.len().into_usize_safe() {
                     let value = array.get_element(i);
                     match value {
@@ -273,21 +334,21 @@ impl<'a> ObjectAllocator<'a> {
                 }
                 Ok(())
             }
-            ArrayEntryType::Array => {
-                todo!("arrays of arrays are not supported yet")
-            }
         }
     }
 
     /// Iterates over the copied objects in the new region, which still have pointers to the
     /// objects in the original semispace, by updating the references with the copies' addresses.
+    /// Returns the number of copied objects.
     unsafe fn fix_references_in_new_region(
         &mut self,
         class_resolver: &impl ClassByIdResolver<'a>,
-    ) -> Result<(), VmError> {
+    ) -> Result<usize, VmError> {
         let end_ptr = self.other.memory.add(self.other.used);
         let mut ptr = self.other.memory;
+        let mut copied_objects = 0;
         while ptr < end_ptr {
+            copied_objects += 1;
             let header = &mut *(ptr as *mut AllocHeader);
             let object = AbstractObject::from_raw_ptr(ptr);
 
@@ -300,7 +361,7 @@ impl<'a> ObjectAllocator<'a> {
             header.set_state(GcState::Unmarked);
             ptr = ptr.add(header.size());
         }
-        Ok(())
+        Ok(copied_objects)
     }
 
     /// Fixes all the references for each field in the given object
@@ -343,8 +404,8 @@ impl<'a> ObjectAllocator<'a> {
                 // No objects are kept alive by this GC-reachable array!
                 Ok(())
             }
-            ArrayEntryType::Object(class_id) => {
-                debug!("fixing entries of array {array:?} of type {class_id}");
+            ArrayEntryType::Object(_) | ArrayEntryType::Array(..) => {
+                debug!("fixing entries of array {array:?}");
                 for i in 0..array.len().into_usize_safe() {
                     let element_ptr = array.ptr_to_array_element(i);
                     debug!(
@@ -360,9 +421,6 @@ impl<'a> ObjectAllocator<'a> {
                 }
                 Ok(())
             }
-            ArrayEntryType::Array => {
-                todo!("arrays of arrays are not supported yet")
-            }
         }
     }
 
@@ -396,6 +454,21 @@ impl<'a> ObjectAllocator<'a> {
         self.fix_reference(root as *mut u8);
         debug!("  fixed gc root - new pointer is {:#0x}", root as u64);
     }
+
+    /// Updates a weak root so that it points to the new object, or clears it if its object
+    /// has not been copied to the new semispace
+    unsafe fn fix_weak_root(&self, weak_root: *mut Option<AbstractObject<'a>>) {
+        let Some(object) = (*weak_root).as_mut() else {
+            return;
+        };
+        let object_ptr = object as *mut AbstractObject<'a>;
+        let referred_object_ptr = *(object_ptr as *const *const u8);
+        let header = &*(referred_object_ptr as *const AllocHeader);
+        match header.state() {
+            GcState::Marked => self.fix_gc_root(object_ptr),
+            GcState::Unmarked => *weak_root = None,
+        }
+    }
 }
 
 impl<'a> fmt::Debug for ObjectAllocator<'a> {
@@ -0,0 +1,28 @@
+use crate::{
+    abstract_object::AbstractObject, call_frame::MethodCallResult,
+    class_and_method::ClassAndMethod, value::Value,
+};
+
+/// An event notified to the [InvocationListener] registered via
+/// [crate::vm::Vm::set_invocation_listener]. Unlike the events of an
+/// [crate::invocation_trace::InvocationTrace], these refer to the actual methods and values,
+/// so they are valid only while the listener is being invoked.
+#[derive(Debug)]
+pub enum InvocationEvent<'e, 'a> {
+    /// A method, either bytecode or native, is about to be executed. Like for the arguments
+    /// of native methods, longs and doubles are followed by a [Value::Uninitialized]
+    /// placeholder for their second slot.
+    Enter {
+        class_and_method: &'e ClassAndMethod<'a>,
+        receiver: Option<&'e AbstractObject<'a>>,
+        arguments: &'e [Value<'a>],
+    },
+    /// A method has completed, returning a value or throwing an exception
+    Exit {
+        class_and_method: &'e ClassAndMethod<'a>,
+        result: &'e MethodCallResult<'a>,
+    },
+}
+
+/// A callback notified of every method entry and exit, for example to profile a program
+pub type InvocationListener<'a> = Box<dyn FnMut(&InvocationEvent<'_, 'a>) + 'a>;
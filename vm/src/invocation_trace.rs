@@ -0,0 +1,208 @@
+use std::{
+    fmt,
+    fmt::{Display, Formatter},
+};
+
+use thiserror::Error;
+
+/// One event of an [InvocationTrace]. Methods are identified by a description such as
+/// `rjvm.Foo.bar(I)I`, and values are rendered as strings, so that traces of different
+/// runs can be compared and stored in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvocationTraceEvent {
+    /// A method has been invoked with the given arguments
+    Enter {
+        method: String,
+        arguments: Vec<String>,
+    },
+    /// A method has returned the given value, which is `void` for void methods
+    Exit { method: String, result: String },
+    /// A method has completed by throwing an exception of the given class
+    Throw { method: String, exception: String },
+}
+
+/// The sequence of method entries and exits recorded between
+/// [crate::vm::Vm::start_trace_recording] and [crate::vm::Vm::stop_trace_recording].
+///
+/// The text format, produced by `Display` and read by [InvocationTrace::parse], has one
+/// event per line, indented by the call depth, with tab-separated fields:
+/// `->` for a method entry, followed by the method and its arguments; `<-` for a method
+/// exit, followed by the method and its result; `<!` for an exception, followed by the
+/// method and the exception class.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InvocationTrace {
+    pub events: Vec<InvocationTraceEvent>,
+}
+
+/// Error returned when parsing a malformed trace
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("invalid invocation trace at line {line}")]
+pub struct InvalidInvocationTraceError {
+    pub line: usize,
+}
+
+impl InvocationTrace {
+    pub(crate) fn record(&mut self, event: InvocationTraceEvent) {
+        self.events.push(event);
+    }
+
+    /// Parses a trace in the format generated by `Display`
+    pub fn parse(text: &str) -> Result<Self, InvalidInvocationTraceError> {
+        let events = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                Self::parse_event(line.trim_start())
+                    .ok_or(InvalidInvocationTraceError { line: index + 1 })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { events })
+    }
+
+    fn parse_event(line: &str) -> Option<InvocationTraceEvent> {
+        let mut fields = line.split('\t');
+        let marker = fields.next()?;
+        let method = fields.next()?.to_string();
+        match marker {
+            "->" => Some(InvocationTraceEvent::Enter {
+                method,
+                arguments: fields.map(str::to_string).collect(),
+            }),
+            "<-" => Some(InvocationTraceEvent::Exit {
+                method,
+                result: fields.next()?.to_string(),
+            }),
+            "<!" => Some(InvocationTraceEvent::Throw {
+                method,
+                exception: fields.next()?.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Compares this trace with an expected one, for example a golden trace stored in a
+    /// file, and describes the first event that differs, if any.
+    pub fn first_difference(&self, expected: &InvocationTrace) -> Option<String> {
+        let mismatch = self
+            .events
+            .iter()
+            .zip(expected.events.iter())
+            .position(|(actual, expected)| actual != expected);
+        if let Some(index) = mismatch {
+            return Some(format!(
+                "event {index} differs: expected {:?}, got {:?}",
+                expected.events[index], self.events[index]
+            ));
+        }
+        if self.events.len() != expected.events.len() {
+            return Some(format!(
+                "expected {} events, got {}",
+                expected.events.len(),
+                self.events.len()
+            ));
+        }
+        None
+    }
+}
+
+impl Display for InvocationTrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut depth = 0usize;
+        for event in self.events.iter() {
+            match event {
+                InvocationTraceEvent::Enter { method, arguments } => {
+                    write!(f, "{:indent$}->\t{method}", "", indent = depth * 2)?;
+                    for argument in arguments.iter() {
+                        write!(f, "\t{argument}")?;
+                    }
+                    writeln!(f)?;
+                    depth += 1;
+                }
+                InvocationTraceEvent::Exit { method, result } => {
+                    depth = depth.saturating_sub(1);
+                    writeln!(
+                        f,
+                        "{:indent$}<-\t{method}\t{result}",
+                        "",
+                        indent = depth * 2
+                    )?;
+                }
+                InvocationTraceEvent::Throw { method, exception } => {
+                    depth = depth.saturating_sub(1);
+                    writeln!(
+                        f,
+                        "{:indent$}<!\t{method}\t{exception}",
+                        "",
+                        indent = depth * 2
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::invocation_trace::{
+        InvalidInvocationTraceError, InvocationTrace, InvocationTraceEvent,
+    };
+
+    #[test]
+    fn can_be_written_and_parsed_back() {
+        let trace = InvocationTrace {
+            events: vec![
+                InvocationTraceEvent::Enter {
+                    method: "rjvm.Foo.bar(ILjava/lang/String;)I".to_string(),
+                    arguments: vec!["1".to_string(), "\"a, b\"".to_string()],
+                },
+                InvocationTraceEvent::Enter {
+                    method: "rjvm.Foo.baz()V".to_string(),
+                    arguments: vec![],
+                },
+                InvocationTraceEvent::Throw {
+                    method: "rjvm.Foo.baz()V".to_string(),
+                    exception: "java/lang/RuntimeException".to_string(),
+                },
+                InvocationTraceEvent::Exit {
+                    method: "rjvm.Foo.bar(ILjava/lang/String;)I".to_string(),
+                    result: "2".to_string(),
+                },
+            ],
+        };
+
+        let text = trace.to_string();
+        assert_eq!(
+            "->\trjvm.Foo.bar(ILjava/lang/String;)I\t1\t\"a, b\"\n  \
+            ->\trjvm.Foo.baz()V\n  \
+            <!\trjvm.Foo.baz()V\tjava/lang/RuntimeException\n\
+            <-\trjvm.Foo.bar(ILjava/lang/String;)I\t2\n",
+            text
+        );
+        assert_eq!(Ok(trace), InvocationTrace::parse(&text));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_lines() {
+        assert_eq!(
+            Err(InvalidInvocationTraceError { line: 2 }),
+            InvocationTrace::parse("->\trjvm.Foo.bar()V\nhello\n")
+        );
+    }
+
+    #[test]
+    fn first_difference_describes_the_mismatch() {
+        let expected = InvocationTrace::parse("->\ta()V\n<-\ta()V\tvoid\n").unwrap();
+        let actual = InvocationTrace::parse("->\ta()V\n<!\ta()V\tjava/lang/Error\n").unwrap();
+        assert_eq!(None, expected.first_difference(&expected));
+        assert_eq!(
+            Some(
+                "event 1 differs: expected Exit { method: \"a()V\", result: \"void\" }, \
+                got Throw { method: \"a()V\", exception: \"java/lang/Error\" }"
+                    .to_string()
+            ),
+            actual.first_difference(&expected)
+        );
+    }
+}
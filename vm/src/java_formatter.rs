@@ -0,0 +1,459 @@
+use std::{iter::Peekable, str::CharIndices};
+
+use thiserror::Error;
+
+/// An argument of [format], already converted from the java value
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FormatArgument {
+    Null,
+    Boolean(bool),
+    Char(char),
+    /// A `byte`, `short`, `int` or `long`, with its size, needed to print negative
+    /// numbers in hexadecimal or octal
+    Integer {
+        value: i64,
+        bits: u32,
+    },
+    /// A `float` or a `double`
+    Float(f64),
+    /// For any other object, the result of its `toString`
+    Text(String),
+}
+
+/// Errors that the JDK reports with the exception of the same name
+#[derive(Error, Debug, PartialEq)]
+pub(crate) enum FormatError {
+    #[error("Conversion = '{0}'")]
+    UnknownFormatConversion(String),
+    #[error("Format specifier '{0}'")]
+    MissingFormatArgument(String),
+    /// The conversion character, and the index of the argument of the wrong type
+    #[error("conversion {0} used for argument {1}")]
+    IllegalFormatConversion(char, usize),
+}
+
+/// A format specifier, i.e. `%[argument_index$][flags][width][.precision]conversion`
+struct Specifier {
+    flags: String,
+    width: Option<usize>,
+    precision: Option<usize>,
+    conversion: char,
+}
+
+/// Formats the arguments like `java.util.Formatter` does. Only a subset of the syntax is
+/// supported: the conversions `b`, `c`, `d`, `o`, `x`, `e`, `f`, `s`, `n` and `%`,
+/// their upper case variants, explicit argument indexes, width, precision and the
+/// flags `-`, `+`, ` `, `0` and `,`. Locales are not supported: the output is always
+/// the one of `Locale.ROOT`.
+pub(crate) fn format(format: &str, args: &[FormatArgument]) -> Result<String, FormatError> {
+    let mut result = String::new();
+    let mut chars = format.char_indices().peekable();
+    let mut next_argument = 0;
+
+    while let Some((start, char)) = chars.next() {
+        if char != '%' {
+            result.push(char);
+            continue;
+        }
+
+        // The digits are an argument index if followed by $, else a width
+        let mut argument_index = None;
+        let mut flags = String::new();
+        let mut digits = take_digits(&mut chars);
+        if !digits.is_empty() && chars.next_if(|(_, c)| *c == '$').is_some() {
+            argument_index = digits.parse::<usize>().ok();
+            digits = String::new();
+        }
+        if digits.is_empty() {
+            while let Some((_, flag)) = chars.next_if(|(_, c)| "-#+ 0,(".contains(*c)) {
+                flags.push(flag);
+            }
+            digits = take_digits(&mut chars);
+        } else if digits.starts_with('0') {
+            // A leading zero is the flag, not part of the width
+            flags.push('0');
+            digits.remove(0);
+        }
+        let width = digits.parse::<usize>().ok();
+        let precision = if chars.next_if(|(_, c)| *c == '.').is_some() {
+            take_digits(&mut chars).parse::<usize>().ok()
+        } else {
+            None
+        };
+        let Some((end, conversion)) = chars.next() else {
+            return Err(FormatError::UnknownFormatConversion("%".to_string()));
+        };
+        let specifier = Specifier {
+            flags,
+            width,
+            precision,
+            conversion,
+        };
+
+        let text = match conversion {
+            '%' => "%".to_string(),
+            'n' => "\n".to_string(),
+            _ => {
+                let index = match argument_index {
+                    Some(index) => index.saturating_sub(1),
+                    None => {
+                        next_argument += 1;
+                        next_argument - 1
+                    }
+                };
+                let argument = args.get(index).ok_or_else(|| {
+                    FormatError::MissingFormatArgument(format[start..=end].to_string())
+                })?;
+                format_argument(&specifier, argument)
+                    .ok_or(FormatError::IllegalFormatConversion(conversion, index))??
+            }
+        };
+        result.push_str(&pad(&specifier, text));
+    }
+    Ok(result)
+}
+
+fn take_digits(chars: &mut Peekable<CharIndices>) -> String {
+    let mut digits = String::new();
+    while let Some((_, digit)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+        digits.push(digit);
+    }
+    digits
+}
+
+/// Returns `None` if the argument's type does not match the conversion
+fn format_argument(
+    specifier: &Specifier,
+    argument: &FormatArgument,
+) -> Option<Result<String, FormatError>> {
+    let conversion = specifier.conversion.to_ascii_lowercase();
+    if !"bscdoxfe".contains(conversion) {
+        return Some(Err(FormatError::UnknownFormatConversion(
+            specifier.conversion.to_string(),
+        )));
+    }
+
+    let text = match (conversion, argument) {
+        ('b', FormatArgument::Null) => "false".to_string(),
+        ('b', FormatArgument::Boolean(value)) => value.to_string(),
+        ('b', _) => "true".to_string(),
+
+        ('s', FormatArgument::Null) => "null".to_string(),
+        ('s', FormatArgument::Boolean(value)) => value.to_string(),
+        ('s', FormatArgument::Char(value)) => value.to_string(),
+        ('s', FormatArgument::Integer { value, .. }) => value.to_string(),
+        ('s', FormatArgument::Float(value)) => double_to_string(*value),
+        ('s', FormatArgument::Text(value)) => value.clone(),
+
+        (_, FormatArgument::Null) => "null".to_string(),
+        ('c', FormatArgument::Char(value)) => value.to_string(),
+        ('c', FormatArgument::Integer { value, bits }) if *bits <= 32 => {
+            char::from_u32(*value as u32)?.to_string()
+        }
+        ('d', FormatArgument::Integer { value, .. }) => format_decimal(specifier, *value),
+        ('x', FormatArgument::Integer { value, bits }) => {
+            format!("{:x}", unsigned_bits(*value, *bits))
+        }
+        ('o', FormatArgument::Integer { value, bits }) => {
+            format!("{:o}", unsigned_bits(*value, *bits))
+        }
+        ('f', FormatArgument::Float(value)) => {
+            let text = format_fixed(*value, specifier.precision.unwrap_or(6));
+            with_sign(specifier, *value >= 0.0, text)
+        }
+        ('e', FormatArgument::Float(value)) => {
+            let text = format_scientific(*value, specifier.precision.unwrap_or(6));
+            with_sign(specifier, *value >= 0.0, text)
+        }
+        _ => return None,
+    };
+
+    // Precision is the maximum number of characters for the general conversions
+    let text = match (conversion, specifier.precision) {
+        ('b' | 's', Some(precision)) => text.chars().take(precision).collect(),
+        _ => text,
+    };
+    if specifier.conversion.is_ascii_uppercase() {
+        Some(Ok(text.to_uppercase()))
+    } else {
+        Some(Ok(text))
+    }
+}
+
+fn unsigned_bits(value: i64, bits: u32) -> u64 {
+    if bits >= 64 {
+        value as u64
+    } else {
+        (value as u64) & ((1u64 << bits) - 1)
+    }
+}
+
+fn format_decimal(specifier: &Specifier, value: i64) -> String {
+    let digits = value.unsigned_abs().to_string();
+    let digits = if specifier.flags.contains(',') {
+        let mut grouped = String::new();
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index).is_multiple_of(3) {
+                grouped.push(',');
+            }
+            grouped.push(digit);
+        }
+        grouped
+    } else {
+        digits
+    };
+    with_sign(specifier, value >= 0, digits)
+}
+
+/// Java rounds half up the shortest decimal representation of the number, i.e. the
+/// one of `Double.toString`, while Rust rounds the exact binary value
+fn format_fixed(value: f64, precision: usize) -> String {
+    if !value.is_finite() {
+        return if value.is_nan() {
+            "NaN".to_string()
+        } else {
+            "Infinity".to_string()
+        };
+    }
+
+    let text = value.abs().to_string();
+    let (integer, fraction) = text.split_once('.').unwrap_or((&text, ""));
+    let mut digits: Vec<u8> = integer.bytes().collect();
+    digits.extend(
+        fraction
+            .bytes()
+            .chain(std::iter::repeat(b'0'))
+            .take(precision),
+    );
+    if fraction
+        .as_bytes()
+        .get(precision)
+        .is_some_and(|digit| *digit >= b'5')
+    {
+        let mut index = digits.len();
+        loop {
+            if index == 0 {
+                digits.insert(0, b'1');
+                break;
+            }
+            index -= 1;
+            if digits[index] == b'9' {
+                digits[index] = b'0';
+            } else {
+                digits[index] += 1;
+                break;
+            }
+        }
+    }
+
+    let integer_length = digits.len() - precision;
+    let mut result = String::from_utf8(digits[..integer_length].to_vec()).unwrap();
+    if precision > 0 {
+        result.push('.');
+        result.push_str(std::str::from_utf8(&digits[integer_length..]).unwrap());
+    }
+    if value < 0.0 {
+        result.insert(0, '-');
+    }
+    result
+}
+
+/// Java uses at least two digits for the exponent, and always writes its sign
+fn format_scientific(value: f64, precision: usize) -> String {
+    let text = format!("{:.*e}", precision, value.abs());
+    match text.split_once('e') {
+        Some((mantissa, exponent)) => {
+            let (sign, exponent) = match exponent.strip_prefix('-') {
+                Some(exponent) => ('-', exponent),
+                None => ('+', exponent),
+            };
+            format!("{mantissa}e{sign}{exponent:0>2}")
+        }
+        None => text,
+    }
+}
+
+/// Formats a double like `Double.toString`: the shortest decimal representation that
+/// identifies the number, in scientific notation if it is smaller than 10^-3 or
+/// at least 10^7
+pub(crate) fn double_to_string(value: f64) -> String {
+    floating_point_to_string(value, value.to_string(), format!("{value:e}"))
+}
+
+/// Formats a float like `Float.toString`, with the same rules as [double_to_string]
+pub(crate) fn float_to_string(value: f32) -> String {
+    floating_point_to_string(value.into(), value.to_string(), format!("{value:e}"))
+}
+
+fn floating_point_to_string(value: f64, decimal: String, scientific: String) -> String {
+    if value.is_nan() {
+        return "NaN".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+
+    let with_fraction = |digits: &str| {
+        if digits.contains('.') {
+            digits.to_string()
+        } else {
+            format!("{digits}.0")
+        }
+    };
+    if value == 0.0 || (1e-3..1e7).contains(&value.abs()) {
+        return with_fraction(&decimal);
+    }
+    match scientific.split_once('e') {
+        Some((mantissa, exponent)) => format!("{}E{exponent}", with_fraction(mantissa)),
+        None => scientific,
+    }
+}
+
+/// Adds the sign to a number formatted without it
+fn with_sign(specifier: &Specifier, is_positive: bool, text: String) -> String {
+    let text = text.trim_start_matches('-').to_string();
+    if !is_positive {
+        format!("-{text}")
+    } else if specifier.flags.contains('+') {
+        format!("+{text}")
+    } else if specifier.flags.contains(' ') {
+        format!(" {text}")
+    } else {
+        text
+    }
+}
+
+fn pad(specifier: &Specifier, text: String) -> String {
+    let Some(width) = specifier.width else {
+        return text;
+    };
+    let length = text.chars().count();
+    if length >= width {
+        return text;
+    }
+
+    let padding = width - length;
+    if specifier.flags.contains('-') {
+        format!("{text}{}", " ".repeat(padding))
+    } else if specifier.flags.contains('0') {
+        // Zeros go after the sign
+        let sign_length = text
+            .chars()
+            .take_while(|c| matches!(c, '-' | '+' | ' '))
+            .count();
+        let (sign, digits) = text.split_at(sign_length);
+        format!("{sign}{}{digits}", "0".repeat(padding))
+    } else {
+        format!("{}{text}", " ".repeat(padding))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::java_formatter::{
+        double_to_string, float_to_string, format, FormatArgument, FormatError,
+    };
+
+    fn int(value: i64) -> FormatArgument {
+        FormatArgument::Integer { value, bits: 32 }
+    }
+
+    #[test]
+    fn can_format_the_supported_conversions() {
+        let args = vec![
+            int(42),
+            FormatArgument::Text("hello".to_string()),
+            FormatArgument::Char('x'),
+            FormatArgument::Boolean(true),
+            FormatArgument::Float(1.5),
+            int(-1),
+            FormatArgument::Null,
+        ];
+        assert_eq!(
+            Ok("42 hello x true 1.500000 ffffffff null 100%\n".to_string()),
+            format("%d %s %c %b %f %x %s 100%%%n", &args)
+        );
+    }
+
+    #[test]
+    fn can_format_with_flags_width_and_precision() {
+        let args = vec![
+            int(-42),
+            FormatArgument::Float(1.875),
+            int(1234567),
+            FormatArgument::Float(1234567.0),
+        ];
+        assert_eq!(
+            Ok("[-00042] [  1.88] [1.88  ] [+1,234,567] [1.23e+06]".to_string()),
+            format("[%06d] [%6.2f] [%-6.2f] [%+,d] [%.2e]", &{
+                let mut with_repeated_float = args.clone();
+                with_repeated_float.insert(2, args[1].clone());
+                with_repeated_float
+            })
+        );
+    }
+
+    #[test]
+    fn rounds_half_up_like_java() {
+        let args = vec![
+            FormatArgument::Float(2.25),
+            FormatArgument::Float(9.995),
+            FormatArgument::Float(-0.5),
+        ];
+        assert_eq!(
+            Ok("2.3 10.00 -1".to_string()),
+            format("%.1f %.2f %.0f", &args)
+        );
+    }
+
+    #[test]
+    fn can_convert_floating_point_numbers_to_string_like_java() {
+        let cases = [
+            (1.5, "1.5"),
+            (100.0, "100.0"),
+            (-0.25, "-0.25"),
+            (0.001, "0.001"),
+            (0.0001, "1.0E-4"),
+            (1234567.0, "1234567.0"),
+            (12345678.0, "1.2345678E7"),
+            (-1e100, "-1.0E100"),
+            (0.0, "0.0"),
+            (-0.0, "-0.0"),
+            (f64::NAN, "NaN"),
+            (f64::NEG_INFINITY, "-Infinity"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(expected, double_to_string(value));
+        }
+        assert_eq!("0.1", float_to_string(0.1));
+        assert_eq!("3.4028235E38", float_to_string(f32::MAX));
+    }
+
+    #[test]
+    fn can_format_with_argument_indexes() {
+        let args = vec![
+            FormatArgument::Text("hello".to_string()),
+            FormatArgument::Text("world".to_string()),
+        ];
+        assert_eq!(
+            Ok("HEL world hello".to_string()),
+            format("%.3S %2$s %1$s", &args)
+        );
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(
+            Err(FormatError::MissingFormatArgument("%s".to_string())),
+            format("%d %s", &[int(1)])
+        );
+        assert_eq!(
+            Err(FormatError::UnknownFormatConversion("q".to_string())),
+            format("%q", &[int(1)])
+        );
+        assert_eq!(
+            Err(FormatError::IllegalFormatConversion('d', 0)),
+            format("%d", &[FormatArgument::Text("a".to_string())])
+        );
+    }
+}
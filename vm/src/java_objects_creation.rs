@@ -24,12 +24,6 @@ pub fn new_java_lang_string_object<'a>(
         .map(|c| Value::Int(c as i32))
         .collect();
 
-    let java_array = vm.new_array(ArrayEntryType::Base(BaseType::Char), char_array.len());
-    char_array
-        .into_iter()
-        .enumerate()
-        .for_each(|(index, value)| java_array.set_element(index, value).unwrap());
-
     // In our JRE's rt.jar, the fields for String are:
     //    private final char[] value;
     //    private int hash;
@@ -38,7 +32,19 @@ pub fn new_java_lang_string_object<'a>(
     //    public static final Comparator<String> CASE_INSENSITIVE_ORDER = new CaseInsensitiveComparator();
     //    private static final int HASHING_SEED;
     //    private transient int hash32;
+    //
+    // Creating the object might run the static initializer of String, and thus the gc:
+    // therefore we allocate the array only afterwards, or we would lose track of it.
+    // Allocating the array might run the gc too, which would move the object.
     let string_object = vm.new_object(call_stack, "java/lang/String")?;
+    vm.push_temporary_root(string_object);
+    let java_array = vm.new_array(ArrayEntryType::Base(BaseType::Char), char_array.len());
+    let string_object = vm.pop_temporary_root();
+    char_array
+        .into_iter()
+        .enumerate()
+        .for_each(|(index, value)| java_array.set_element(index, value).unwrap());
+
     string_object.set_field(0, Value::Object(java_array));
     string_object.set_field(1, Value::Int(0));
     string_object.set_field(6, Value::Int(0));
@@ -61,18 +67,46 @@ pub fn extract_str_from_java_lang_string<'a>(
     Err(VmError::ValidationException)
 }
 
+/// Creates a new instance of `java.lang.Class` with the given binary name. Prefer
+/// [Vm::get_class_object], which returns the same instance for the same class.
 pub fn new_java_lang_class_object<'a>(
     vm: &mut Vm<'a>,
     call_stack: &mut CallStack<'a>,
     class_name: &str,
 ) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+    // Like for strings, the static initializer of Class might run the gc, so we have to
+    // create the object before allocating the name. The latter might run the gc, too,
+    // which would move the object.
     let class_object = vm.new_object(call_stack, "java/lang/Class")?;
-    // TODO: build a proper instance of Class object
-    let string_object = new_java_lang_string_object(vm, call_stack, class_name)?;
-    class_object.set_field(5, Value::Object(string_object));
+    vm.push_temporary_root(class_object);
+    let string_object = new_java_lang_string_object(vm, call_stack, class_name);
+    let class_object = vm.pop_temporary_root();
+    class_object.set_field(
+        JAVA_LANG_CLASS_NAME_FIELD_INDEX,
+        Value::Object(string_object?),
+    );
     Ok(class_object)
 }
 
+// In our JRE's rt.jar, the sixth field of Class is
+//    private transient String name;
+const JAVA_LANG_CLASS_NAME_FIELD_INDEX: usize = 5;
+
+/// Given an instance of `java.lang.Class`, extracts the name of the class, as returned
+/// by `Class::getName` (i.e. `java.lang.String`)
+pub fn extract_class_name_from_java_lang_class<'a>(
+    vm: &Vm<'a>,
+    object: &impl Object<'a>,
+) -> Result<String, VmError> {
+    let class = vm.get_class_by_id(object.class_id())?;
+    if class.name == "java/lang/Class" {
+        if let Value::Object(name) = object.get_field(class, JAVA_LANG_CLASS_NAME_FIELD_INDEX) {
+            return extract_str_from_java_lang_string(vm, &name);
+        }
+    }
+    Err(VmError::ValidationException)
+}
+
 pub fn new_java_lang_stack_trace_element_object<'a>(
     vm: &mut Vm<'a>,
     call_stack: &mut CallStack<'a>,
@@ -1,5 +1,6 @@
 pub mod abstract_object;
 pub mod alloc_entry;
+pub mod allocation_site;
 pub mod array;
 pub mod array_entry_type;
 mod call_frame;
@@ -11,17 +12,25 @@ mod class_manager;
 mod class_path;
 mod class_path_entry;
 mod class_resolver_by_id;
+pub mod constant_pool_view;
+mod decoded_code;
 pub mod exceptions;
+pub mod execution_counters;
 mod file_system_class_path_entry;
 mod gc;
+pub mod invocation_listener;
+pub mod invocation_trace;
 mod jar_file_class_path_entry;
+mod java_formatter;
 pub mod java_objects_creation;
 mod native_methods_impl;
 pub mod native_methods_registry;
 pub mod object;
 pub mod stack_trace_element;
-mod time;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+pub mod time;
 pub mod value;
-mod value_stack;
+pub mod value_stack;
 pub mod vm;
 pub mod vm_error;
@@ -1,23 +1,29 @@
 use log::{debug, info};
 
-use rjvm_reader::type_conversion::ToUsizeSafe;
+use rjvm_reader::{
+    field_flags::FieldFlags,
+    field_type::{BaseType, FieldType},
+    type_conversion::ToUsizeSafe,
+};
 
 use crate::{
-    abstract_object::{AbstractObject, ObjectKind},
+    abstract_object::{string_from_char_array, AbstractObject, ObjectKind},
     array::Array,
+    array_entry_type::ArrayEntryType,
     call_frame::MethodCallResult,
     call_stack::CallStack,
-    exceptions::MethodCallFailed,
+    class_resolver_by_id::binary_class_name,
+    exceptions::{JavaException, MethodCallFailed},
+    java_formatter::{double_to_string, float_to_string, format, FormatArgument, FormatError},
     java_objects_creation::{
-        extract_str_from_java_lang_string, new_java_lang_class_object,
-        new_java_lang_stack_trace_element_object,
+        extract_class_name_from_java_lang_class, extract_str_from_java_lang_string,
+        new_java_lang_stack_trace_element_object, new_java_lang_string_object,
     },
-    native_methods_registry::NativeMethodsRegistry,
+    native_methods_registry::{NativeCallback, NativeMethodsRegistry},
     object::Object,
-    time::{get_current_time_millis, get_nano_time},
     value::{
-        expect_abstract_object_at, expect_array_at, expect_concrete_object_at, expect_double_at,
-        expect_float_at, expect_int_at, Value,
+        expect_abstract_object_at, expect_concrete_object_at, expect_double_at, expect_float_at,
+        expect_int_at, expect_long_at, Value,
     },
     vm::Vm,
     vm_error::VmError,
@@ -25,13 +31,19 @@ use crate::{
 
 /// Registers the built-in native methods
 pub(crate) fn register_natives(registry: &mut NativeMethodsRegistry) {
-    registry.register_temp_print(|vm, _, _, args| temp_print(vm, args));
+    registry.register_temp_print(|vm, stack, _, args| temp_print(vm, stack, args));
     register_noops(registry);
     register_time_methods(registry);
+    register_math_methods(registry);
+    register_floating_point_bits_methods(registry);
+    register_floating_point_to_string_methods(registry);
     register_gc_methods(registry);
     register_native_repr_methods(registry);
+    register_string_methods(registry);
     register_reflection_methods(registry);
     register_throwable_methods(registry);
+    register_enum_methods(registry);
+    register_format_methods(registry);
 }
 
 /// These various methods are noop, i.e. they do not do anything
@@ -57,23 +69,201 @@ fn register_noops(registry: &mut NativeMethodsRegistry) {
         "()V",
         |_, _, _, _| Ok(None),
     );
+    // Called by the static initializer of sun.misc.VM, used for example by Integer's
+    // cache of boxed values. The hotspot implementation only sets up some JVM flags.
+    registry.register("sun/misc/VM", "initialize", "()V", |_, _, _, _| Ok(None));
+    // The saved properties are populated by System.initializeSystemClass, which we do not
+    // run; the bytecode would throw because they are empty, so we just report none is set
+    registry.register_intrinsic(
+        "sun/misc/VM",
+        "getSavedProperty",
+        "(Ljava/lang/String;)Ljava/lang/String;",
+        |_, _, _, _| Ok(Some(Value::Null)),
+    );
 }
 
 /// Methods to access the system clock
 fn register_time_methods(registry: &mut NativeMethodsRegistry) {
-    registry.register("java/lang/System", "nanoTime", "()J", |_, _, _, _| {
-        Ok(Some(Value::Long(get_nano_time())))
+    registry.register("java/lang/System", "nanoTime", "()J", |vm, _, _, _| {
+        Ok(Some(Value::Long(vm.clock().now_nanos())))
     });
     registry.register(
         "java/lang/System",
         "currentTimeMillis",
         "()J",
-        |_, _, _, _| Ok(Some(Value::Long(get_current_time_millis()))),
+        |vm, _, _, _| Ok(Some(Value::Long(vm.clock().now_millis()))),
+    );
+}
+
+/// The native methods of `StrictMath`, to which most methods of `Math` delegate
+fn register_math_methods(registry: &mut NativeMethodsRegistry) {
+    let unary_functions: [(&str, NativeCallback); 16] = [
+        ("sin", |_, _, _, args| math_function(&args, f64::sin)),
+        ("cos", |_, _, _, args| math_function(&args, f64::cos)),
+        ("tan", |_, _, _, args| math_function(&args, f64::tan)),
+        ("asin", |_, _, _, args| math_function(&args, f64::asin)),
+        ("acos", |_, _, _, args| math_function(&args, f64::acos)),
+        ("atan", |_, _, _, args| math_function(&args, f64::atan)),
+        ("exp", |_, _, _, args| math_function(&args, f64::exp)),
+        ("log", |_, _, _, args| math_function(&args, f64::ln)),
+        ("log10", |_, _, _, args| math_function(&args, f64::log10)),
+        ("sqrt", |_, _, _, args| math_function(&args, f64::sqrt)),
+        ("cbrt", |_, _, _, args| math_function(&args, f64::cbrt)),
+        ("sinh", |_, _, _, args| math_function(&args, f64::sinh)),
+        ("cosh", |_, _, _, args| math_function(&args, f64::cosh)),
+        ("tanh", |_, _, _, args| math_function(&args, f64::tanh)),
+        ("expm1", |_, _, _, args| math_function(&args, f64::exp_m1)),
+        ("log1p", |_, _, _, args| math_function(&args, f64::ln_1p)),
+    ];
+    for (method_name, callback) in unary_functions {
+        registry.register("java/lang/StrictMath", method_name, "(D)D", callback);
+    }
+
+    let binary_functions: [(&str, NativeCallback); 3] = [
+        ("atan2", |_, _, _, args| {
+            math_binary_function(&args, f64::atan2)
+        }),
+        ("hypot", |_, _, _, args| {
+            math_binary_function(&args, f64::hypot)
+        }),
+        ("pow", |_, _, _, args| math_binary_function(&args, java_pow)),
+    ];
+    for (method_name, callback) in binary_functions {
+        registry.register("java/lang/StrictMath", method_name, "(DD)D", callback);
+    }
+}
+
+/// Conversions from the IEEE 754 representation to floating point numbers.
+/// The opposite ones are registered with the other native representation methods.
+fn register_floating_point_bits_methods(registry: &mut NativeMethodsRegistry) {
+    registry.register(
+        "java/lang/Double",
+        "longBitsToDouble",
+        "(J)D",
+        |_, _, _, args| {
+            let bits = expect_long_at(&args, 0)?;
+            Ok(Some(Value::Double(f64::from_bits(bits as u64))))
+        },
+    );
+    registry.register(
+        "java/lang/Float",
+        "intBitsToFloat",
+        "(I)F",
+        |_, _, _, args| {
+            let bits = expect_int_at(&args, 0)?;
+            Ok(Some(Value::Float(f32::from_bits(bits as u32))))
+        },
+    );
+}
+
+/// Conversions of floating point numbers to strings, also used by string concatenation.
+/// The bytecode relies on `sun.misc.FloatingDecimal`, whose static initializer
+/// needs `ThreadLocal` and thus `sun.misc.Unsafe`, which we do not support.
+fn register_floating_point_to_string_methods(registry: &mut NativeMethodsRegistry) {
+    registry.register_intrinsic(
+        "java/lang/Double",
+        "toString",
+        "(D)Ljava/lang/String;",
+        |vm, stack, _, args| {
+            let text = double_to_string(expect_double_at(&args, 0)?);
+            Ok(Some(Value::Object(new_java_lang_string_object(
+                vm, stack, &text,
+            )?)))
+        },
+    );
+    registry.register_intrinsic(
+        "java/lang/Float",
+        "toString",
+        "(F)Ljava/lang/String;",
+        |vm, stack, _, args| {
+            let text = float_to_string(expect_float_at(&args, 0)?);
+            Ok(Some(Value::Object(new_java_lang_string_object(
+                vm, stack, &text,
+            )?)))
+        },
+    );
+    registry.register_intrinsic(
+        "java/lang/AbstractStringBuilder",
+        "append",
+        "(D)Ljava/lang/AbstractStringBuilder;",
+        |vm, stack, receiver, args| {
+            let text = double_to_string(expect_double_at(&args, 0)?);
+            append_to_string_builder(vm, stack, receiver, &text)
+        },
     );
+    registry.register_intrinsic(
+        "java/lang/AbstractStringBuilder",
+        "append",
+        "(F)Ljava/lang/AbstractStringBuilder;",
+        |vm, stack, receiver, args| {
+            let text = float_to_string(expect_float_at(&args, 0)?);
+            append_to_string_builder(vm, stack, receiver, &text)
+        },
+    );
+}
+
+/// Appends the text by invoking `append(String)`, which returns the builder itself
+fn append_to_string_builder<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+    receiver: Option<AbstractObject<'a>>,
+    text: &str,
+) -> MethodCallResult<'a> {
+    let builder = receiver.ok_or(VmError::ValidationException)?;
+    let append_method = vm.resolve_class_method(
+        stack,
+        "java/lang/AbstractStringBuilder",
+        "append",
+        "(Ljava/lang/String;)Ljava/lang/AbstractStringBuilder;",
+    )?;
+    // Allocating the string might run the gc, which would move the builder
+    vm.push_temporary_root(builder);
+    let string = new_java_lang_string_object(vm, stack, text);
+    let builder = vm.pop_temporary_root();
+    let string = string?;
+    vm.invoke(
+        stack,
+        append_method,
+        Some(builder),
+        vec![Value::Object(string)],
+    )
+}
+
+fn math_function<'a>(args: &[Value<'a>], function: fn(f64) -> f64) -> MethodCallResult<'a> {
+    let value = expect_double_at(args, 0)?;
+    Ok(Some(Value::Double(function(value))))
+}
+
+fn math_binary_function<'a>(
+    args: &[Value<'a>],
+    function: fn(f64, f64) -> f64,
+) -> MethodCallResult<'a> {
+    let first = expect_double_at(args, 0)?;
+    // The first double is followed by the placeholder for its second slot
+    let second = expect_double_at(args, 2)?;
+    Ok(Some(Value::Double(function(first, second))))
+}
+
+/// Rust's `powf` follows C's `pow`, which differs from java's in a couple of special cases
+fn java_pow(base: f64, exponent: f64) -> f64 {
+    if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+        f64::NAN
+    } else {
+        base.powf(exponent)
+    }
 }
 
 /// Methods related to the garbage collector
 fn register_gc_methods(registry: &mut NativeMethodsRegistry) {
+    registry.register(
+        "java/lang/Object",
+        "hashCode",
+        "()I",
+        |_, _, receiver, _| {
+            let receiver = expect_some_receiver(receiver)?;
+            Ok(Some(Value::Int(receiver.identity_hash_code())))
+        },
+    );
     registry.register(
         "java/lang/System",
         "identityHashCode",
@@ -92,7 +282,7 @@ fn register_native_repr_methods(registry: &mut NativeMethodsRegistry) {
         "java/lang/System",
         "arraycopy",
         "(Ljava/lang/Object;ILjava/lang/Object;II)V",
-        |_, _, _, args| native_array_copy(args),
+        |vm, stack, _, args| native_array_copy(vm, stack, &args),
     );
     registry.register(
         "java/lang/Float",
@@ -108,8 +298,31 @@ fn register_native_repr_methods(registry: &mut NativeMethodsRegistry) {
     );
 }
 
+/// Methods of java.lang.String
+fn register_string_methods(registry: &mut NativeMethodsRegistry) {
+    registry.register(
+        "java/lang/String",
+        "intern",
+        "()Ljava/lang/String;",
+        |vm, _, receiver, _| intern(vm, receiver),
+    );
+}
+
+fn intern<'a>(vm: &mut Vm<'a>, receiver: Option<AbstractObject<'a>>) -> MethodCallResult<'a> {
+    let receiver = expect_some_receiver(receiver)?;
+    let content = extract_str_from_java_lang_string(vm, &receiver)?;
+    let interned = vm.intern_existing_string(receiver, content);
+    Ok(Some(Value::Object(interned)))
+}
+
 /// Methods related to reflection
 fn register_reflection_methods(registry: &mut NativeMethodsRegistry) {
+    registry.register(
+        "java/lang/Object",
+        "getClass",
+        "()Ljava/lang/Class;",
+        |vm, stack, receiver, _| get_class(vm, stack, receiver),
+    );
     registry.register(
         "java/lang/Class",
         "getClassLoader0",
@@ -128,6 +341,18 @@ fn register_reflection_methods(registry: &mut NativeMethodsRegistry) {
         "(Ljava/lang/String;)Ljava/lang/Class;",
         |vm, stack, _, args| get_primitive_class(vm, stack, &args),
     );
+    registry.register(
+        "java/lang/Class",
+        "getComponentType",
+        "()Ljava/lang/Class;",
+        |vm, stack, receiver, _| get_component_type(vm, stack, receiver),
+    );
+    registry.register(
+        "java/lang/reflect/Array",
+        "newArray",
+        "(Ljava/lang/Class;I)Ljava/lang/Object;",
+        |vm, stack, _, args| new_array_of_class(vm, stack, &args),
+    );
 }
 
 /// Methods of java.lang.Throwable
@@ -152,11 +377,25 @@ fn register_throwable_methods(registry: &mut NativeMethodsRegistry) {
     );
 }
 
-/// Debug method that does a "println", useful since we do not have real I/O
-fn temp_print<'a>(vm: &mut Vm<'a>, args: Vec<Value<'a>>) -> MethodCallResult<'a> {
-    let arg = args.get(0).ok_or(VmError::ValidationException)?;
+/// Debug method that does a "println", useful since we do not have real I/O.
+/// Like `PrintStream::println(char[])`, a char array is printed as text: it gets
+/// converted into a `java.lang.String`.
+fn temp_print<'a>(
+    vm: &mut Vm<'a>,
+    call_stack: &mut CallStack<'a>,
+    args: Vec<Value<'a>>,
+) -> MethodCallResult<'a> {
+    let mut arg = args.first().ok_or(VmError::ValidationException)?.clone();
+    if let Value::Object(array) = &arg {
+        if array.kind() == ObjectKind::Array
+            && array.elements_type() == ArrayEntryType::Base(BaseType::Char)
+        {
+            let string = string_from_char_array(array.clone())?;
+            arg = Value::Object(new_java_lang_string_object(vm, call_stack, &string)?);
+        }
+    }
 
-    let formatted = match arg {
+    let formatted = match &arg {
         Value::Object(object) if object.kind() == ObjectKind::Object => {
             let class = vm
                 .get_class_by_id(object.class_id())
@@ -171,7 +410,7 @@ fn temp_print<'a>(vm: &mut Vm<'a>, args: Vec<Value<'a>>) -> MethodCallResult<'a>
         _ => format!("{:?}", arg),
     };
     info!("TEMP implementation of native method: printing value {formatted}",);
-    vm.printed.push(arg.clone());
+    vm.printed.push(arg);
     Ok(None)
 }
 
@@ -180,18 +419,110 @@ fn identity_hash_code(args: Vec<Value<'_>>) -> MethodCallResult<'_> {
     Ok(Some(Value::Int(object.identity_hash_code())))
 }
 
-fn native_array_copy(args: Vec<Value>) -> MethodCallResult {
-    // TODO: handle NullPointerException with the correct error
+fn native_array_copy<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    // Like in the JVM, a null array is reported before an argument that is not an array
+    if matches!(args.first(), Some(Value::Null)) || matches!(args.get(2), Some(Value::Null)) {
+        return Err(vm.throw_npe(stack));
+    }
+    let src = array_copy_argument(vm, stack, args, 0, "source")?;
+    let src_pos = expect_int_at(args, 1)?;
+    let dest = array_copy_argument(vm, stack, args, 2, "destination")?;
+    let dest_pos = expect_int_at(args, 3)?;
+    let length = expect_int_at(args, 4)?;
+
+    let same_elements_type = src.elements_type() == dest.elements_type();
+    let both_references = !matches!(src.elements_type(), ArrayEntryType::Base(_))
+        && !matches!(dest.elements_type(), ArrayEntryType::Base(_));
+    if !same_elements_type && !both_references {
+        return Err(vm.throw_exception(
+            stack,
+            "java/lang/ArrayStoreException",
+            Some("arraycopy: type mismatch"),
+        ));
+    }
 
-    let src = expect_array_at(&args, 0)?;
-    let src_pos = expect_int_at(&args, 1)?;
-    let dest = expect_array_at(&args, 2)?;
-    let dest_pos = expect_int_at(&args, 3)?;
-    let length = expect_int_at(&args, 4)?;
-    array_copy(&src, src_pos, &dest, dest_pos, length.into_usize_safe())?;
+    let out_of_bounds = |pos: i32, array: &AbstractObject<'a>| {
+        pos < 0 || pos as i64 + length as i64 > array.len() as i64
+    };
+    if length < 0 || out_of_bounds(src_pos, &src) || out_of_bounds(dest_pos, &dest) {
+        let message = format!(
+            "arraycopy: cannot copy {length} elements from index {src_pos} of an array of \
+            length {} to index {dest_pos} of an array of length {}",
+            src.len(),
+            dest.len()
+        );
+        return Err(vm.throw_exception(
+            stack,
+            "java/lang/ArrayIndexOutOfBoundsException",
+            Some(&message),
+        ));
+    }
+
+    let length = length.into_usize_safe();
+    if same_elements_type {
+        array_copy(&src, src_pos, &dest, dest_pos, length)?;
+        return Ok(None);
+    }
+
+    // Arrays of different reference types: each element is checked, and the ones before
+    // the first that cannot be stored are copied anyway, as the spec requires
+    for i in 0..length {
+        let value = src.get_element(src_pos.into_usize_safe() + i)?;
+        if !can_store_in_array(vm, &dest, &value)? {
+            return Err(vm.throw_exception(
+                stack,
+                "java/lang/ArrayStoreException",
+                Some("arraycopy: element type mismatch"),
+            ));
+        }
+        dest.set_element(dest_pos.into_usize_safe() + i, value)?;
+    }
     Ok(None)
 }
 
+/// Returns the argument of arraycopy at the given index, which must not be null, throwing a
+/// `java.lang.ArrayStoreException` if it is not an array
+fn array_copy_argument<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+    args: &[Value<'a>],
+    index: usize,
+    description: &str,
+) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+    match args.get(index) {
+        Some(Value::Object(array)) if array.kind() == ObjectKind::Array => Ok(array.clone()),
+        Some(Value::Object(object)) => {
+            let message = format!(
+                "arraycopy: {description} type {} is not an array",
+                binary_class_name(vm, object)?
+            );
+            Err(vm.throw_exception(stack, "java/lang/ArrayStoreException", Some(&message)))
+        }
+        _ => Err(MethodCallFailed::InternalError(
+            VmError::ValidationException,
+        )),
+    }
+}
+
+/// Checks whether the given value can be an element of an array of references
+fn can_store_in_array(vm: &Vm, array: &AbstractObject, value: &Value) -> Result<bool, VmError> {
+    let Value::Object(_) = value else {
+        return Ok(matches!(value, Value::Null));
+    };
+    let Some(elements_type) = array.elements_type().into_field_type(vm) else {
+        return Err(VmError::ValidationException);
+    };
+    Ok(value.matches_type(elements_type, vm, |class_name| {
+        vm.find_class_by_name(class_name)
+    }))
+}
+
+/// Copies the given range of elements between two arrays with the same elements type.
+/// The two arrays can be the same one, even with overlapping ranges.
 pub fn array_copy<'a>(
     src: &impl Array<'a>,
     src_pos: i32,
@@ -204,12 +535,12 @@ pub fn array_copy<'a>(
         return Err(VmError::ValidationException);
     }
 
-    for i in 0..length {
-        let src_index = src_pos.into_usize_safe() + i;
-        let src_item = src.get_element(src_index)?;
-
-        let dest_index = dest_pos.into_usize_safe() + i;
-        dest.set_element(dest_index, src_item)?;
+    // Reading all the elements before writing any handles overlapping ranges
+    let items = (0..length)
+        .map(|i| src.get_element(src_pos.into_usize_safe() + i))
+        .collect::<Result<Vec<_>, _>>()?;
+    for (i, item) in items.into_iter().enumerate() {
+        dest.set_element(dest_pos.into_usize_safe() + i, item)?;
     }
 
     Ok(())
@@ -234,6 +565,85 @@ fn get_class_loader(receiver: Option<AbstractObject>) -> MethodCallResult {
     Ok(Some(Value::Null))
 }
 
+fn get_class<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+    receiver: Option<AbstractObject<'a>>,
+) -> MethodCallResult<'a> {
+    let receiver = expect_some_receiver(receiver)?;
+    let class_name = binary_class_name(vm, &receiver)?;
+    let java_lang_class_instance = vm.get_class_object(stack, &class_name)?;
+    Ok(Some(Value::Object(java_lang_class_instance)))
+}
+
+/// The names used by `Class::getName()` for the primitive types
+const PRIMITIVE_CLASS_NAMES: [(BaseType, &str); 8] = [
+    (BaseType::Byte, "byte"),
+    (BaseType::Char, "char"),
+    (BaseType::Double, "double"),
+    (BaseType::Float, "float"),
+    (BaseType::Int, "int"),
+    (BaseType::Long, "long"),
+    (BaseType::Short, "short"),
+    (BaseType::Boolean, "boolean"),
+];
+
+fn get_component_type<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+    receiver: Option<AbstractObject<'a>>,
+) -> MethodCallResult<'a> {
+    let receiver = expect_some_receiver(receiver)?;
+    let class_name = extract_class_name_from_java_lang_class(vm, &receiver)?;
+    let Some(component_descriptor) = class_name.strip_prefix('[') else {
+        // Not an array
+        return Ok(Some(Value::Null));
+    };
+    let component_name = match FieldType::parse(component_descriptor) {
+        Ok(FieldType::Base(base_type)) => PRIMITIVE_CLASS_NAMES
+            .iter()
+            .find(|(primitive_type, _)| *primitive_type == base_type)
+            .map(|(_, name)| name.to_string())
+            .ok_or(VmError::ValidationException)?,
+        Ok(FieldType::Object(name)) => name,
+        Ok(FieldType::Array(_)) => component_descriptor.to_string(),
+        Err(_) => {
+            return Err(MethodCallFailed::InternalError(
+                VmError::ValidationException,
+            ))
+        }
+    };
+    let component_class = vm.get_class_object(stack, &component_name)?;
+    Ok(Some(Value::Object(component_class)))
+}
+
+/// Implements `java.lang.reflect.Array::newArray`, used by `Array::newInstance`
+fn new_array_of_class<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    let component_class = expect_concrete_object_at(args, 0)?;
+    let length = expect_int_at(args, 1)?;
+    let component_name = extract_class_name_from_java_lang_class(vm, &component_class)?;
+
+    let component_type = if let Some((base_type, _)) = PRIMITIVE_CLASS_NAMES
+        .iter()
+        .find(|(_, name)| *name == component_name)
+    {
+        FieldType::Base(base_type.clone())
+    } else if component_name.starts_with('[') {
+        FieldType::parse(&component_name.replace('.', "/"))
+            .map_err(|_| MethodCallFailed::InternalError(VmError::ValidationException))?
+    } else {
+        FieldType::Object(component_name.replace('.', "/"))
+    };
+    let elements_type = vm.array_entry_type(stack, &component_type)?;
+    let length = vm.check_array_length(stack, length)?;
+    let array = vm.new_array(elements_type, length);
+    Ok(Some(Value::Object(array)))
+}
+
 fn get_primitive_class<'a>(
     vm: &mut Vm<'a>,
     stack: &mut CallStack<'a>,
@@ -241,7 +651,7 @@ fn get_primitive_class<'a>(
 ) -> MethodCallResult<'a> {
     let arg = expect_concrete_object_at(args, 0)?;
     let class_name = extract_str_from_java_lang_string(vm, &arg)?;
-    let java_lang_class_instance = new_java_lang_class_object(vm, stack, &class_name)?;
+    let java_lang_class_instance = vm.get_class_object(stack, &class_name)?;
     Ok(Some(Value::Object(java_lang_class_instance)))
 }
 
@@ -296,3 +706,219 @@ fn expect_some_receiver(receiver: Option<AbstractObject>) -> Result<AbstractObje
         None => Err(VmError::ValidationException),
     }
 }
+
+/// Methods of enums
+fn register_enum_methods(registry: &mut NativeMethodsRegistry) {
+    // The bytecode of valueOf uses reflection to find the enum constants
+    registry.register_intrinsic(
+        "java/lang/Enum",
+        "valueOf",
+        "(Ljava/lang/Class;Ljava/lang/String;)Ljava/lang/Enum;",
+        |vm, stack, _, args| enum_value_of(vm, stack, &args),
+    );
+}
+
+fn enum_value_of<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    let enum_class = expect_concrete_object_at(args, 0)?;
+    let constant_name = expect_concrete_object_at(args, 1)?;
+    let class_name = extract_class_name_from_java_lang_class(vm, &enum_class)?;
+    let constant_name = extract_str_from_java_lang_string(vm, &constant_name)?;
+
+    let class = vm.get_or_resolve_class(stack, &class_name.replace('.', "/"))?;
+    let constants = vm
+        .get_static_fields_values(class)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(field, _)| field.flags.contains(FieldFlags::ENUM))
+        .map(|(_, value)| value)
+        .collect::<Vec<_>>();
+    for constant in constants {
+        if let Value::Object(object) = &constant {
+            let name = vm.get_instance_field(object, "name")?;
+            let name = expect_concrete_object_at(&[name], 0)?;
+            if extract_str_from_java_lang_string(vm, &name)? == constant_name {
+                return Ok(Some(constant));
+            }
+        }
+    }
+
+    // The JDK uses the canonical name of the class in the message
+    let message = format!(
+        "No enum constant {}.{}",
+        class_name.replace('$', "."),
+        constant_name
+    );
+    Err(vm.throw_exception(stack, "java/lang/IllegalArgumentException", Some(&message)))
+}
+
+/// `String.format` and `PrintStream.printf`. The bytecode of `java.util.Formatter` needs
+/// too many parts of the JDK that we do not support, so we use our own implementation.
+fn register_format_methods(registry: &mut NativeMethodsRegistry) {
+    registry.register_intrinsic(
+        "java/lang/String",
+        "format",
+        "(Ljava/lang/String;[Ljava/lang/Object;)Ljava/lang/String;",
+        |vm, stack, _, args| {
+            let text = format_java_string(vm, stack, &args)?;
+            Ok(Some(Value::Object(new_java_lang_string_object(
+                vm, stack, &text,
+            )?)))
+        },
+    );
+    for method_name in ["printf", "format"] {
+        registry.register_intrinsic(
+            "java/io/PrintStream",
+            method_name,
+            "(Ljava/lang/String;[Ljava/lang/Object;)Ljava/io/PrintStream;",
+            |vm, stack, receiver, args| print_formatted(vm, stack, receiver, &args),
+        );
+    }
+}
+
+/// Since we do not have I/O, like tempPrint we store the formatted string in
+/// [Vm::printed], and return the stream itself for chaining
+fn print_formatted<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+    receiver: Option<AbstractObject<'a>>,
+    args: &[Value<'a>],
+) -> MethodCallResult<'a> {
+    let stream = receiver.ok_or(VmError::ValidationException)?;
+    // Formatting might invoke toString and allocating the string might run the gc,
+    // both of which would move the stream
+    vm.push_temporary_root(stream);
+    let string = format_java_string(vm, stack, args)
+        .and_then(|text| new_java_lang_string_object(vm, stack, &text));
+    let stream = vm.pop_temporary_root();
+    vm.printed.push(Value::Object(string?));
+    Ok(Some(Value::Object(stream)))
+}
+
+fn format_java_string<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+    args: &[Value<'a>],
+) -> Result<String, MethodCallFailed<'a>> {
+    let format_string = expect_concrete_object_at(args, 0)?;
+    let format_string = extract_str_from_java_lang_string(vm, &format_string)?;
+    let values = match args.get(1) {
+        Some(Value::Object(array)) => array.clone(),
+        Some(Value::Null) => return Err(vm.throw_npe(stack)),
+        _ => return Err(VmError::ValidationException.into()),
+    };
+
+    // Converting the values might invoke their toString, which can run the gc and move
+    // the array and its elements, so we keep it rooted and read it again every time
+    vm.push_temporary_root(values);
+    let arguments = to_format_arguments(vm, stack);
+    let values = vm.pop_temporary_root();
+
+    format(&format_string, &arguments?).map_err(|err| match err {
+        FormatError::UnknownFormatConversion(conversion) => vm.throw_exception(
+            stack,
+            "java/util/UnknownFormatConversionException",
+            Some(&conversion),
+        ),
+        FormatError::MissingFormatArgument(specifier) => vm.throw_exception(
+            stack,
+            "java/util/MissingFormatArgumentException",
+            Some(&specifier),
+        ),
+        FormatError::IllegalFormatConversion(conversion, index) => {
+            match values.get_element(index) {
+                Ok(value) => illegal_format_conversion(vm, stack, conversion, &value),
+                Err(err) => err.into(),
+            }
+        }
+    })
+}
+
+/// Converts the elements of the array of values on top of the temporary roots
+fn to_format_arguments<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+) -> Result<Vec<FormatArgument>, MethodCallFailed<'a>> {
+    let length = vm.top_temporary_root().len().into_usize_safe();
+    let mut arguments = Vec::with_capacity(length);
+    for index in 0..length {
+        let value = vm.top_temporary_root().get_element(index)?;
+        arguments.push(to_format_argument(vm, stack, &value)?);
+    }
+    Ok(arguments)
+}
+
+fn illegal_format_conversion<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+    conversion: char,
+    value: &Value<'a>,
+) -> MethodCallFailed<'a> {
+    let exception = expect_abstract_object_at(std::slice::from_ref(value), 0)
+        .map_err(MethodCallFailed::from)
+        .and_then(|object| Ok(binary_class_name(vm, &object)?))
+        .and_then(|class_name| vm.get_class_object(stack, &class_name))
+        .and_then(|class| {
+            vm.new_java_exception(
+                stack,
+                "java/util/IllegalFormatConversionException",
+                "(CLjava/lang/Class;)V",
+                vec![Value::Int(conversion as i32), Value::Object(class)],
+            )
+        });
+    match exception {
+        Ok(exception) => MethodCallFailed::ExceptionThrown(JavaException(exception)),
+        Err(err) => err,
+    }
+}
+
+fn to_format_argument<'a>(
+    vm: &mut Vm<'a>,
+    stack: &mut CallStack<'a>,
+    value: &Value<'a>,
+) -> Result<FormatArgument, MethodCallFailed<'a>> {
+    let object = match value {
+        Value::Object(object) => object,
+        _ => return Ok(FormatArgument::Null),
+    };
+    if object.kind() == ObjectKind::Array {
+        // Same as Object::toString
+        let text = format!(
+            "{}@{:x}",
+            binary_class_name(vm, object)?,
+            object.identity_hash_code()
+        );
+        return Ok(FormatArgument::Text(text));
+    }
+
+    let class = vm.get_class_by_id(object.class_id())?;
+    let boxed_value = || vm.get_instance_field(object, "value");
+    Ok(match (class.name.as_str(), boxed_value()) {
+        ("java/lang/String", _) => {
+            FormatArgument::Text(extract_str_from_java_lang_string(vm, object)?)
+        }
+        ("java/lang/Boolean", Ok(Value::Int(value))) => FormatArgument::Boolean(value != 0),
+        ("java/lang/Character", Ok(Value::Int(value))) => FormatArgument::Char(
+            char::from_u32(value as u32).unwrap_or(char::REPLACEMENT_CHARACTER),
+        ),
+        ("java/lang/Byte", Ok(Value::Int(value))) => FormatArgument::Integer {
+            value: value.into(),
+            bits: 8,
+        },
+        ("java/lang/Short", Ok(Value::Int(value))) => FormatArgument::Integer {
+            value: value.into(),
+            bits: 16,
+        },
+        ("java/lang/Integer", Ok(Value::Int(value))) => FormatArgument::Integer {
+            value: value.into(),
+            bits: 32,
+        },
+        ("java/lang/Long", Ok(Value::Long(value))) => FormatArgument::Integer { value, bits: 64 },
+        ("java/lang/Float", Ok(Value::Float(value))) => FormatArgument::Float(value.into()),
+        ("java/lang/Double", Ok(Value::Double(value))) => FormatArgument::Float(value),
+        _ => FormatArgument::Text(vm.to_string_recursion_safe(stack, object.clone())?),
+    })
+}
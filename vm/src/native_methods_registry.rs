@@ -1,4 +1,8 @@
-use std::{collections::HashMap, fmt, fmt::Formatter};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    fmt::Formatter,
+};
 
 use crate::{
     abstract_object::AbstractObject, call_frame::MethodCallResult, call_stack::CallStack,
@@ -13,11 +17,44 @@ pub type NativeCallback<'a> = fn(
     Vec<Value<'a>>,
 ) -> MethodCallResult<'a>;
 
+/// A host function, i.e. a rust closure exposed to java code via the native
+/// methods of the bridge class [HOST_BRIDGE_CLASS_NAME]
+pub type HostFunction<'a> = Box<dyn Fn(Vec<Value<'a>>) -> MethodCallResult<'a> + 'a>;
+
+/// The class whose native static methods are dispatched to the registered host functions
+pub const HOST_BRIDGE_CLASS_NAME: &str = "rjvm/Host";
+
+/// What the vm does when a native method is invoked, but no implementation for it
+/// has been registered
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UnresolvedNativeBehavior {
+    /// Fails the invocation with an internal [crate::vm_error::VmError::NotImplemented]
+    Error,
+    /// Pretends the method has executed, returning the default value of its return
+    /// type (i.e. zero or null)
+    ReturnDefaultValue,
+    /// Throws a `java.lang.UnsatisfiedLinkError`, as the real JVM does
+    #[default]
+    ThrowUnsatisfiedLinkError,
+}
+
 /// The registry of all known native methods
 #[derive(Default)]
 pub struct NativeMethodsRegistry<'a> {
     methods: HashMap<ClassMethodAndDescriptor, NativeCallback<'a>>,
 
+    /// Intrinsics are rust implementations of methods that do have bytecode in our
+    /// rt.jar, but whose bytecode relies on features that we do not support, such as
+    /// reflection. They are executed instead of the bytecode.
+    intrinsics: HashMap<ClassMethodAndDescriptor, NativeCallback<'a>>,
+
+    /// Names of the classes having at least one intrinsic, to quickly skip the lookup
+    /// of the intrinsics when invoking any other method
+    classes_with_intrinsics: HashSet<String>,
+
+    /// Host functions, keyed by method name and descriptor
+    host_functions: HashMap<(String, String), HostFunction<'a>>,
+
     // Hack for checking that integration tests can actually print the correct values:
     // this just stores the values printed by a method named `tempPrint` into an array
     // in the Vm object. This method is used for all classes whose name starts with rjvm.
@@ -26,7 +63,13 @@ pub struct NativeMethodsRegistry<'a> {
 
 impl<'a> fmt::Debug for NativeMethodsRegistry<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "NativeMethodsRegistry={:?}", self.methods.keys())
+        write!(
+            f,
+            "NativeMethodsRegistry={:?} intrinsics={:?} host_functions={:?}",
+            self.methods.keys(),
+            self.intrinsics.keys(),
+            self.host_functions.keys()
+        )
     }
 }
 
@@ -48,6 +91,61 @@ impl<'a> NativeMethodsRegistry<'a> {
         );
     }
 
+    pub fn register_intrinsic(
+        &mut self,
+        class_name: &str,
+        method_name: &str,
+        type_descriptor: &str,
+        callback: NativeCallback<'a>,
+    ) {
+        self.classes_with_intrinsics.insert(class_name.to_string());
+        self.intrinsics.insert(
+            ClassMethodAndDescriptor {
+                class: class_name.to_string(),
+                method: method_name.to_string(),
+                descriptor: type_descriptor.to_string(),
+            },
+            callback,
+        );
+    }
+
+    pub fn get_intrinsic(&self, class_and_method: &ClassAndMethod) -> Option<NativeCallback<'a>> {
+        if !self
+            .classes_with_intrinsics
+            .contains(&class_and_method.class.name)
+        {
+            return None;
+        }
+        self.intrinsics
+            .get(&ClassMethodAndDescriptor {
+                class: class_and_method.class.name.clone(),
+                method: class_and_method.method.name.clone(),
+                descriptor: class_and_method.method.type_descriptor.clone(),
+            })
+            .cloned()
+    }
+
+    pub fn register_host_function(
+        &mut self,
+        method_name: &str,
+        type_descriptor: &str,
+        function: HostFunction<'a>,
+    ) {
+        self.host_functions.insert(
+            (method_name.to_string(), type_descriptor.to_string()),
+            function,
+        );
+    }
+
+    pub fn get_host_function(
+        &self,
+        method_name: &str,
+        type_descriptor: &str,
+    ) -> Option<&HostFunction<'a>> {
+        self.host_functions
+            .get(&(method_name.to_string(), type_descriptor.to_string()))
+    }
+
     pub(crate) fn register_temp_print(&mut self, callback: NativeCallback<'a>) {
         self.temp_print_callback = Some(callback);
     }
@@ -0,0 +1,92 @@
+//! Assertions for the tests that execute java code, in this crate or in the ones using it.
+//! Enabled by the `test-support` feature.
+
+use std::fmt::Debug;
+
+use crate::{
+    exceptions::{JavaException, MethodCallFailed},
+    java_objects_creation::extract_str_from_java_lang_string,
+    object::Object,
+    value::Value,
+    vm::Vm,
+    vm_error::VmError,
+};
+
+/// Returns the message of the given java exception, i.e. what `Throwable::getMessage`
+/// would return for the exceptions that do not override it
+pub fn exception_message<'a>(
+    vm: &Vm<'a>,
+    exception: &JavaException<'a>,
+) -> Result<Option<String>, VmError> {
+    let class = vm.get_class_by_id(exception.0.class_id())?;
+    let (message_index, _) = class
+        .find_field("detailMessage")
+        .ok_or(VmError::ValidationException)?;
+    match exception.0.get_field(class, message_index) {
+        Value::Null => Ok(None),
+        Value::Object(message) => extract_str_from_java_lang_string(vm, &message).map(Some),
+        _ => Err(VmError::ValidationException),
+    }
+}
+
+/// Asserts that the given result is a thrown java exception, of the class `class_name` or
+/// one of its subclasses, and whose message contains `message_substring`. An empty substring
+/// matches any message, including a null one.
+#[track_caller]
+pub fn assert_throws<'a, T: Debug>(
+    vm: &Vm<'a>,
+    result: Result<T, MethodCallFailed<'a>>,
+    class_name: &str,
+    message_substring: &str,
+) {
+    check_thrown(vm, result, class_name, false, message_substring)
+}
+
+/// Same as [assert_throws], but the exception must be an instance of exactly `class_name`,
+/// for the tests where throwing one of its subclasses would be a bug
+#[track_caller]
+pub fn assert_throws_exactly<'a, T: Debug>(
+    vm: &Vm<'a>,
+    result: Result<T, MethodCallFailed<'a>>,
+    class_name: &str,
+    message_substring: &str,
+) {
+    check_thrown(vm, result, class_name, true, message_substring)
+}
+
+#[track_caller]
+fn check_thrown<'a, T: Debug>(
+    vm: &Vm<'a>,
+    result: Result<T, MethodCallFailed<'a>>,
+    class_name: &str,
+    exact_class: bool,
+    message_substring: &str,
+) {
+    let Err(MethodCallFailed::ExceptionThrown(exception)) = result else {
+        panic!("should have thrown a {class_name}, got {result:?}");
+    };
+    let exception_class = vm
+        .get_class_by_id(exception.0.class_id())
+        .expect("exception should have a valid class");
+    let matches_class = if exact_class {
+        exception_class.name == class_name
+    } else {
+        exception_class.is_subclass_of_class_named(class_name)
+    };
+    assert!(
+        matches_class,
+        "should have thrown a {class_name}, got a {}",
+        exception_class.name
+    );
+
+    let message = exception_message(vm, &exception).expect("should be able to get the message");
+    if !message_substring.is_empty() {
+        assert!(
+            message
+                .as_ref()
+                .is_some_and(|message| message.contains(message_substring)),
+            "the message of the {} should contain \"{message_substring}\", got {message:?}",
+            exception_class.name
+        );
+    }
+}
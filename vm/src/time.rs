@@ -1,18 +1,47 @@
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+/// The source of the time returned by `System.currentTimeMillis` and `System.nanoTime`.
+/// Embedders and tests can replace it via [crate::vm::Vm::set_clock], for example to make
+/// programs reading the time deterministic.
+pub trait Clock {
+    /// Returns the current epoch as milliseconds
+    fn now_millis(&self) -> i64;
+
+    /// Returns the current epoch as nano seconds
+    fn now_nanos(&self) -> i64;
+}
+
+/// The default clock, which reads the system time
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> i64 {
+        time_since_epoch().as_millis() as i64
+    }
+
+    fn now_nanos(&self) -> i64 {
+        time_since_epoch().as_nanos() as i64
+    }
+}
+
+/// A clock that is stopped at the given time since the epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub Duration);
+
+impl Clock for FixedClock {
+    fn now_millis(&self) -> i64 {
+        self.0.as_millis() as i64
+    }
+
+    fn now_nanos(&self) -> i64 {
+        self.0.as_nanos() as i64
+    }
+}
+
 fn time_since_epoch() -> Duration {
     let start = SystemTime::now();
     start
         .duration_since(UNIX_EPOCH)
         .expect("time went backwards")
 }
-
-/// Returns the current epoch as nano seconds
-pub(crate) fn get_nano_time() -> i64 {
-    time_since_epoch().as_nanos() as i64
-}
-
-/// Returns the current epoch as milliseconds
-pub(crate) fn get_current_time_millis() -> i64 {
-    time_since_epoch().as_millis() as i64
-}
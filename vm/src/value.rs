@@ -35,12 +35,35 @@ pub enum Value<'a> {
     /// Models an object value
     Object(AbstractObject<'a>),
 
-    /// Models a null object
+    /// Models a null object. Use [Value::as_reference] to handle both kinds of references
+    /// at once, as an `Option`.
     Null,
     // TODO: the JVM spec says we need to add return address, which are used to implement `finally`
 }
 
 impl<'a> Value<'a> {
+    /// Returns the default value for the given type, i.e. the one that an uninitialized
+    /// field of that type would have
+    pub fn default_for_type(field_type: &FieldType) -> Self {
+        match field_type {
+            FieldType::Base(BaseType::Long) => Value::Long(0),
+            FieldType::Base(BaseType::Float) => Value::Float(0f32),
+            FieldType::Base(BaseType::Double) => Value::Double(0f64),
+            FieldType::Base(_) => Value::Int(0),
+            FieldType::Object(_) | FieldType::Array(_) => Value::Null,
+        }
+    }
+
+    /// Returns the reference held by this value, `None` meaning null, or an error if the
+    /// value is not a reference at all
+    pub fn as_reference(&self) -> Result<Option<&AbstractObject<'a>>, VmError> {
+        match self {
+            Value::Object(object) => Ok(Some(object)),
+            Value::Null => Ok(None),
+            _ => Err(VmError::ValidationException),
+        }
+    }
+
     /// Used for runtime validations that the value matches the given type.
     /// Overly complex; these things, according to the JVM spec, should be checked
     /// at class linkage time, but we have not implemented that phase... :-)
@@ -82,15 +105,15 @@ impl<'a> Value<'a> {
             Value::Object(object) => {
                 if object.kind() == ObjectKind::Array {
                     match expected_type {
-                        FieldType::Array(expected_field_type) => {
-                            let array_entry_type =
-                                object.elements_type().into_field_type(class_resolver_by_id);
-                            if let Some(array_entry_type) = array_entry_type {
-                                array_entry_type == *expected_field_type
-                            } else {
-                                false
-                            }
-                        }
+                        // Arrays of objects are covariant, i.e. a String[] is also an Object[]
+                        FieldType::Array(expected_field_type) => object
+                            .elements_type()
+                            .is_assignable_to(class_resolver_by_id, &expected_field_type),
+                        // Arrays only extend Object and implement Cloneable and Serializable
+                        FieldType::Object(expected_class_name) => matches!(
+                            expected_class_name.as_str(),
+                            "java/lang/Object" | "java/lang/Cloneable" | "java/io/Serializable"
+                        ),
                         _ => false,
                     }
                 } else {
@@ -102,7 +125,7 @@ impl<'a> Value<'a> {
                                 class_resolver_by_id.find_class_by_id(object.class_id());
                             if let Some(object_class) = value_class {
                                 let expected_class = class_resolver_by_name(&expected_class_name);
-                                expected_class.map_or(false, |expected_class| {
+                                expected_class.is_some_and(|expected_class| {
                                     object_class.is_subclass_of(expected_class)
                                 })
                             } else {
@@ -169,6 +192,16 @@ pub fn expect_int_at(vec: &[Value], index: usize) -> Result<i32, VmError> {
     }
 }
 
+/// Checks that the element at the given index is a Long and returns it, or an error.
+pub fn expect_long_at(vec: &[Value], index: usize) -> Result<i64, VmError> {
+    let value = vec.get(index);
+    if let Some(Value::Long(long)) = value {
+        Ok(*long)
+    } else {
+        Err(VmError::ValidationException)
+    }
+}
+
 /// Checks that the element at the given index is a Float and returns it, or an error.
 pub fn expect_float_at(vec: &[Value], index: usize) -> Result<f32, VmError> {
     let value = vec.get(index);
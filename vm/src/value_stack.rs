@@ -14,6 +14,7 @@ use crate::value::Value;
 #[derive(Debug)]
 pub struct ValueStack<'a> {
     stack: Vec<Value<'a>>,
+    max_size: usize,
 }
 
 /// Errors returned from various stack operations
@@ -31,6 +32,7 @@ impl<'a> ValueStack<'a> {
     pub fn with_max_size(max_size: usize) -> Self {
         Self {
             stack: Vec::with_capacity(max_size),
+            max_size,
         }
     }
 
@@ -38,8 +40,12 @@ impl<'a> ValueStack<'a> {
         self.stack.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
     pub fn push(&mut self, value: Value<'a>) -> Result<(), ValueStackError> {
-        if self.stack.len() < self.stack.capacity() {
+        if self.stack.len() < self.max_size {
             self.stack.push(value);
             Ok(())
         } else {
@@ -62,7 +68,7 @@ impl<'a> ValueStack<'a> {
     }
 
     pub fn truncate(&mut self, len: usize) -> Result<(), ValueStackError> {
-        if len > self.stack.capacity() {
+        if len > self.max_size {
             Err(ValueStackError::MaximumCapacityReached)
         } else {
             self.stack.truncate(len);
@@ -74,11 +80,11 @@ impl<'a> ValueStack<'a> {
         self.stack.get(index)
     }
 
-    pub fn iter(&self) -> Iter<Value<'a>> {
+    pub fn iter(&self) -> Iter<'_, Value<'a>> {
         self.stack.iter()
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<Value<'a>> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, Value<'a>> {
         self.stack.iter_mut()
     }
 
@@ -118,6 +124,15 @@ impl<'a> ValueStack<'a> {
 
     pub fn dup2_x1(&mut self) -> Result<(), ValueStackError> {
         let value1 = self.pop()?;
+        if Self::is_category_2(&value1) {
+            // Form 2: value2, value1 -> value1, value2, value1 where value1 is a long or double
+            let value2 = self.pop()?;
+            self.push(value1.clone())?;
+            self.push(value2)?;
+            return self.push(value1);
+        }
+
+        // Form 1: value3, value2, value1 -> value2, value1, value3, value2, value1
         let value2 = self.pop()?;
         let value3 = self.pop()?;
         self.push(value2.clone())?;
@@ -127,6 +142,12 @@ impl<'a> ValueStack<'a> {
         self.push(value1)
     }
 
+    /// Longs and doubles take two slots in the JVM model of the stack, and thus some
+    /// instructions behave differently for them. We store them all in a single entry.
+    fn is_category_2(value: &Value<'a>) -> bool {
+        matches!(value, Value::Long(_) | Value::Double(_))
+    }
+
     pub fn dup2_x2(&mut self) -> Result<(), ValueStackError> {
         let value1 = self.pop()?;
         let value2 = self.pop()?;
@@ -252,6 +273,18 @@ mod tests {
         assert_eq!(Ok(Value::Int(2)), stack.pop());
     }
 
+    #[test]
+    fn can_invoke_dup2_x1_with_a_long() {
+        let mut stack = ValueStack::with_max_size(3);
+        stack.push(Value::Int(2)).expect("should be able to push");
+        stack.push(Value::Long(1)).expect("should be able to push");
+        stack.dup2_x1().expect("should be able to dup2_x1");
+        assert_eq!(3, stack.len());
+        assert_eq!(Ok(Value::Long(1)), stack.pop());
+        assert_eq!(Ok(Value::Int(2)), stack.pop());
+        assert_eq!(Ok(Value::Long(1)), stack.pop());
+    }
+
     #[test]
     fn can_invoke_dup2_x2() {
         let mut stack = ValueStack::with_max_size(6);
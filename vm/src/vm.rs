@@ -1,26 +1,50 @@
-use std::{collections::HashMap, string::ToString};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    string::ToString,
+};
 
 use log::{debug, error, info};
 use typed_arena::Arena;
 
-use rjvm_reader::type_conversion::ToUsizeSafe;
+use rjvm_reader::{
+    class_access_flags::ClassAccessFlags,
+    class_file_field::{ClassFileField, FieldConstantValue},
+    field_flags::FieldFlags,
+    field_type::FieldType,
+    instruction::Operand,
+    type_conversion::ToUsizeSafe,
+};
 
 use crate::{
     abstract_object::{AbstractObject, ObjectKind},
+    allocation_site::{AllocationSite, AllocationSites, LiveObjects},
     array::Array,
     array_entry_type::ArrayEntryType,
     call_frame::MethodCallResult,
-    call_stack::CallStack,
-    class::{ClassId, ClassRef},
+    call_stack::{CallStack, DEFAULT_MAX_DEPTH},
+    class::{ClassId, ClassInitState, ClassRef},
     class_and_method::ClassAndMethod,
-    class_manager::{ClassManager, ResolvedClass},
+    class_manager::ClassManager,
     class_path::ClassPathParseError,
     class_resolver_by_id::ClassByIdResolver,
-    exceptions::MethodCallFailed,
+    constant_pool_view::ResolvedConstant,
+    exceptions::{JavaException, MethodCallFailed},
+    execution_counters::ExecutionCounters,
     gc::ObjectAllocator,
+    invocation_listener::{InvocationEvent, InvocationListener},
+    invocation_trace::{InvocationTrace, InvocationTraceEvent},
+    java_objects_creation::{
+        extract_str_from_java_lang_string, new_java_lang_class_object, new_java_lang_string_object,
+    },
     native_methods_impl::array_copy,
-    native_methods_registry::NativeMethodsRegistry,
+    native_methods_registry::{
+        NativeMethodsRegistry, UnresolvedNativeBehavior, HOST_BRIDGE_CLASS_NAME,
+    },
+    object::Object,
     stack_trace_element::StackTraceElement,
+    time::{Clock, SystemClock},
     value::Value,
     vm_error::VmError,
 };
@@ -44,6 +68,12 @@ pub struct Vm<'a> {
     /// Stores native methods
     pub native_methods_registry: NativeMethodsRegistry<'a>,
 
+    /// What to do when invoking a native method without an implementation
+    unresolved_native_behavior: UnresolvedNativeBehavior,
+
+    /// The source of the time read by java code
+    clock: Box<dyn Clock>,
+
     /// Stores call stacks collected, and associate them with their throwable.
     /// In the classes that we are using, the Throwable implementation does not
     /// store the stack trace in the java fields, but rather relies on a native
@@ -53,11 +83,97 @@ pub struct Vm<'a> {
     /// clarity.
     throwable_call_stacks: HashMap<i32, Vec<StackTraceElement<'a>>>,
 
+    /// While executing [Vm::to_string_recursion_safe], contains the identity hash codes
+    /// of the objects whose `toString` is currently being executed, to detect cycles
+    to_string_in_progress: Option<HashSet<i32>>,
+
+    /// If set, the maximum length of the arrays that can be allocated by java code
+    max_array_length: Option<usize>,
+
+    /// Maximum number of frames of the call stacks allocated by [Vm::allocate_call_stack]
+    max_stack_depth: usize,
+
+    /// If set, all method invocations are recorded here
+    invocation_trace: Option<InvocationTrace>,
+
+    /// If set, notified of all method invocations
+    invocation_listener: Option<Rc<RefCell<InvocationListener<'a>>>>,
+
+    /// Interned strings, i.e. the `java.lang.String` instances shared by all the
+    /// string literals with the same content
+    interned_strings: HashMap<String, AbstractObject<'a>>,
+
+    /// The `java.lang.Class` instances, keyed by the binary name of the class they refer to
+    /// (i.e. `java.lang.String` or `[I`), so that each class has a single one
+    class_objects: HashMap<String, AbstractObject<'a>>,
+
+    /// Objects that the vm's own code holds while allocating other ones: since any
+    /// allocation can run the gc, they must be roots. See [Vm::push_temporary_root].
+    temporary_roots: Vec<AbstractObject<'a>>,
+
+    /// Usage statistics of [Vm::interned_strings]
+    intern_pool_stats: InternPoolStats,
+
+    /// Caches the methods referred by the `Methodref` and `InterfaceMethodref` constants
+    /// of each class, keyed by the class and the index of the constant, so that they are
+    /// resolved only once
+    resolved_method_references: HashMap<(ClassId, u16), ClassAndMethod<'a>>,
+
+    /// Caches the static fields referred by the `Fieldref` constants of each class, together
+    /// with the class declaring them and their index, keyed like
+    /// [Vm::resolved_method_references]. The fields accessed by `getfield` and `putfield`
+    /// are not cached, since they are looked up in the class of the object.
+    resolved_static_field_references:
+        HashMap<(ClassId, u16), (ClassRef<'a>, usize, &'a ClassFileField)>,
+
+    /// If set, a garbage collection is run by the bytecode allocating objects as soon as
+    /// this many bytes have been allocated since the previous one, rather than only when
+    /// the memory is full
+    gc_threshold: Option<usize>,
+
+    /// Number of native methods currently executing, including the ones that called back
+    /// into bytecode. While any is, [Vm::collect_if_over_threshold] does nothing.
+    active_native_calls: usize,
+
+    /// If false, all the classes referenced by the code of a class are loaded before it
+    /// is initialized, see [Vm::set_lazy_verification]
+    lazy_verification: bool,
+
+    /// Counts the work done, see [Vm::execution_counters]
+    pub(crate) execution_counters: ExecutionCounters,
+
+    /// If set, the number of instructions executed by each method, see [Vm::enable_profiling]
+    instruction_stats: Option<HashMap<String, u64>>,
+
+    /// If set, where each object has been allocated, see [Vm::set_leak_diagnosis]
+    allocation_sites: Option<AllocationSites<'a>>,
+
     /// Since we do not have I/O, we have a fake native method that does a println.
     /// To check in the tests what the java bytecode printed, we store it here.
     pub printed: Vec<Value<'a>>,
 }
 
+/// Statistics about the usage of the string intern pool, see [Vm::intern_pool_stats]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InternPoolStats {
+    /// Number of strings currently in the pool
+    pub size: usize,
+    /// Number of lookups that found an existing string
+    pub hits: usize,
+    /// Number of lookups that did not find the string, which was then added to the pool
+    pub misses: usize,
+}
+
+/// Statistics about the heap, see [Vm::heap_stats]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HeapStats {
+    /// Bytes currently allocated
+    pub used_memory: usize,
+    /// Number of objects and arrays currently allocated, including the unreachable
+    /// ones that have not been collected yet
+    pub allocated_objects: usize,
+}
+
 pub const ONE_MEGABYTE: usize = 1024 * 1024;
 const DEFAULT_MAX_MB_OF_MEMORY: usize = 100;
 pub const DEFAULT_MAX_MEMORY: usize = 100 * ONE_MEGABYTE;
@@ -78,7 +194,26 @@ impl<'a> Vm<'a> {
             call_stacks: Arena::new(),
             statics: Default::default(),
             native_methods_registry: Default::default(),
+            unresolved_native_behavior: Default::default(),
+            clock: Box::new(SystemClock),
             throwable_call_stacks: Default::default(),
+            to_string_in_progress: None,
+            max_array_length: None,
+            max_stack_depth: DEFAULT_MAX_DEPTH,
+            invocation_trace: None,
+            invocation_listener: None,
+            interned_strings: Default::default(),
+            class_objects: Default::default(),
+            temporary_roots: Vec::new(),
+            intern_pool_stats: Default::default(),
+            resolved_method_references: Default::default(),
+            resolved_static_field_references: Default::default(),
+            gc_threshold: None,
+            active_native_calls: 0,
+            lazy_verification: true,
+            execution_counters: Default::default(),
+            instruction_stats: None,
+            allocation_sites: None,
             printed: Vec::new(),
         };
         crate::native_methods_impl::register_natives(&mut result.native_methods_registry);
@@ -89,32 +224,316 @@ impl<'a> Vm<'a> {
         self.statics.get(&class_id).cloned()
     }
 
+    /// Returns the values of all the static fields declared by the given class, read from its
+    /// static instance. Returns `None` if the class has not been initialized yet.
+    pub fn get_static_fields_values(
+        &self,
+        class: ClassRef<'a>,
+    ) -> Option<Vec<(&'a ClassFileField, Value<'a>)>> {
+        let static_instance = self.get_static_instance(class.id)?;
+        Some(
+            class
+                .static_fields()
+                .map(|(index, field)| (field, static_instance.get_field(class, index)))
+                .collect(),
+        )
+    }
+
+    /// Reads the value of the instance field with the given name, declared by the object's
+    /// class or by one of its superclasses
+    pub fn get_instance_field(
+        &self,
+        object: &AbstractObject<'a>,
+        field_name: &str,
+    ) -> Result<Value<'a>, VmError> {
+        let (class, index, _) = self.find_instance_field(object, field_name)?;
+        Ok(object.get_field(class, index))
+    }
+
+    /// Writes the value of the instance field with the given name, declared by the object's
+    /// class or by one of its superclasses. The value must match the field's type.
+    pub fn set_instance_field(
+        &self,
+        object: &AbstractObject<'a>,
+        field_name: &str,
+        value: Value<'a>,
+    ) -> Result<(), VmError> {
+        let (_, index, field) = self.find_instance_field(object, field_name)?;
+        if !value.matches_type(field.type_descriptor.clone(), self, |class_name| {
+            self.find_class_by_name(class_name)
+        }) {
+            return Err(VmError::ValidationException);
+        }
+        object.set_field(index, value);
+        Ok(())
+    }
+
+    fn find_instance_field(
+        &self,
+        object: &AbstractObject<'a>,
+        field_name: &str,
+    ) -> Result<(ClassRef<'a>, usize, &'a ClassFileField), VmError> {
+        if object.kind() != ObjectKind::Object {
+            return Err(VmError::ValidationException);
+        }
+        let class = self.get_class_by_id(object.class_id())?;
+        match class.find_field(field_name) {
+            Some((index, field)) if !field.flags.contains(FieldFlags::STATIC) => {
+                Ok((class, index, field))
+            }
+            _ => Err(VmError::FieldNotFoundException(
+                class.name.clone(),
+                field_name.to_string(),
+            )),
+        }
+    }
+
     pub fn append_class_path(&mut self, class_path: &str) -> Result<(), ClassPathParseError> {
         self.class_manager.append_class_path(class_path)
     }
 
+    /// Returns the class with the given name, loading and initializing it if required.
+    /// Before a class is initialized, all its superclasses and interfaces that were not
+    /// initialized yet are initialized too, from the topmost base class down to the
+    /// requested one. Thus, a superclass' `<clinit>` always runs before the one of its
+    /// subclasses.
     pub fn get_or_resolve_class(
         &mut self,
         stack: &mut CallStack<'a>,
         class_name: &str,
     ) -> Result<ClassRef<'a>, MethodCallFailed<'a>> {
-        let class = self.class_manager.get_or_resolve_class(class_name)?;
-        if let ResolvedClass::NewClass(classes_to_init) = &class {
-            for class_to_init in classes_to_init.to_initialize.iter() {
-                self.init_class(stack, class_to_init)?;
+        let class = self.get_or_load_class(class_name)?;
+        self.initialize_class_if_needed(stack, class)?;
+        Ok(class)
+    }
+
+    /// Returns the class with the given name, loading it if required, but without
+    /// initializing it. This is what instructions such as `checkcast` or `anewarray` need,
+    /// since only the first _active_ use of a class, such as the creation of an instance,
+    /// the invocation of a static method or the access to a static field, runs `<clinit>`.
+    pub fn get_or_load_class(&mut self, class_name: &str) -> Result<ClassRef<'a>, VmError> {
+        Ok(self
+            .class_manager
+            .get_or_resolve_class(class_name)?
+            .get_class())
+    }
+
+    /// Same as [Vm::get_or_load_class], for the classes referenced by the bytecode: a class
+    /// that cannot be found makes the instruction throw `java.lang.NoClassDefFoundError`
+    pub(crate) fn load_class_referenced_by_code(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        class_name: &str,
+    ) -> Result<ClassRef<'a>, MethodCallFailed<'a>> {
+        match self.get_or_load_class(class_name) {
+            Err(VmError::ClassNotFoundException(_)) => {
+                Err(self.throw_exception(stack, "java/lang/NoClassDefFoundError", Some(class_name)))
+            }
+            result => Ok(result?),
+        }
+    }
+
+    /// Runs the static initializers of all the loaded classes which have not been
+    /// initialized yet, including the ones loaded while doing so. This allows surfacing the
+    /// errors of the static initializers eagerly, rather than at the first use of a class.
+    /// Stops at the first class whose initialization fails, returning its error.
+    pub fn initialize_all_loaded(
+        &mut self,
+        stack: &mut CallStack<'a>,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        loop {
+            let uninitialized: Vec<ClassRef<'a>> = self
+                .class_manager
+                .loaded_classes()
+                .into_iter()
+                .filter(|class| class.init_state() == ClassInitState::Unprepared)
+                .collect();
+            if uninitialized.is_empty() {
+                return Ok(());
+            }
+            for class in uninitialized {
+                if let Err(err) = self.initialize_class_if_needed(stack, class) {
+                    error!("initialization of class {} failed", class.name);
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    fn initialize_class_if_needed(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        class: ClassRef<'a>,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        match class.init_state() {
+            // Recursive accesses from the static initializer itself must not initialize
+            // the class again
+            ClassInitState::InProgress | ClassInitState::Initialized => return Ok(()),
+            ClassInitState::Erroneous => {
+                let message = format!(
+                    "Could not initialize class {}",
+                    class.name.replace('/', ".")
+                );
+                return Err(self.throw_exception(
+                    stack,
+                    "java/lang/NoClassDefFoundError",
+                    Some(&message),
+                ));
+            }
+            ClassInitState::Unprepared => {}
+        }
+
+        // Interfaces do not initialize their superinterfaces, and classes only initialize
+        // the superinterfaces declaring default methods, as required by the JVM specs
+        let mut dependencies = Vec::from_iter(class.superclass);
+        if !class.flags.contains(ClassAccessFlags::INTERFACE) {
+            Self::collect_superinterfaces_with_default_methods(class, &mut dependencies);
+        }
+        let mut result = Ok(());
+        for dependency in dependencies {
+            result = self.initialize_class_if_needed(stack, dependency);
+            if result.is_err() {
+                break;
+            }
+        }
+        // The static initializer of a superclass might have used, and thus initialized,
+        // this class already
+        if result.is_ok() && class.init_state() != ClassInitState::Unprepared {
+            return Ok(());
+        }
+        // A class whose superclass could not be initialized cannot be used either
+        let result = result
+            .and_then(|_| self.load_referenced_classes(stack, class))
+            .and_then(|_| self.init_class(stack, &class));
+        if result.is_ok() {
+            class.init_state.set(ClassInitState::Initialized);
+        } else {
+            class.init_state.set(ClassInitState::Erroneous);
+            // Invocations of the class' methods must throw NoClassDefFoundError from now on
+            self.resolved_method_references.clear();
+            self.resolved_static_field_references.clear();
+        }
+        result
+    }
+
+    /// Enumerates recursively the superinterfaces of the given class, in the order in which
+    /// they are declared, and collects those that declare a non-abstract, non-static method
+    fn collect_superinterfaces_with_default_methods(
+        class: ClassRef<'a>,
+        superinterfaces: &mut Vec<ClassRef<'a>>,
+    ) {
+        for interface in class.interfaces.iter() {
+            let declares_default_methods = interface
+                .methods
+                .iter()
+                .any(|method| !method.is_abstract() && !method.is_static());
+            if declares_default_methods
+                && !superinterfaces
+                    .iter()
+                    .any(|collected| collected.id == interface.id)
+            {
+                superinterfaces.push(interface);
+            }
+            Self::collect_superinterfaces_with_default_methods(interface, superinterfaces);
+        }
+    }
+
+    /// When verification is not lazy, ensures that all the classes referenced by the code
+    /// of the given class can be loaded, throwing a `java.lang.NoClassDefFoundError` otherwise
+    fn load_referenced_classes(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        class: ClassRef<'a>,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        if self.lazy_verification {
+            return Ok(());
+        }
+        let constant_pool = class.constant_pool();
+        for code in class
+            .methods
+            .iter()
+            .filter_map(|method| method.code.as_ref())
+        {
+            let instructions = code
+                .instructions()
+                .map_err(|_| VmError::ValidationException)?;
+            let referenced_classes = instructions
+                .iter()
+                .flat_map(|instruction| instruction.operands())
+                .filter_map(|operand| match operand {
+                    Operand::ConstantPoolIndex(index) => constant_pool.get(index),
+                    _ => None,
+                })
+                .filter_map(|constant| match constant {
+                    ResolvedConstant::Class(class_name) => Some(class_name),
+                    ResolvedConstant::FieldReference(member)
+                    | ResolvedConstant::MethodReference(member)
+                    | ResolvedConstant::InterfaceMethodReference(member) => Some(member.class_name),
+                    _ => None,
+                });
+            for class_name in referenced_classes {
+                // Arrays' classes are not modelled, but their elements' classes must exist
+                let class_name = if class_name.starts_with('[') {
+                    match class_name
+                        .trim_start_matches('[')
+                        .strip_prefix('L')
+                        .and_then(|class_name| class_name.strip_suffix(';'))
+                    {
+                        Some(elements_class_name) => elements_class_name,
+                        None => continue,
+                    }
+                } else {
+                    class_name
+                };
+                if self.get_or_load_class(class_name).is_err() {
+                    return Err(self.throw_exception(
+                        stack,
+                        "java/lang/NoClassDefFoundError",
+                        Some(class_name),
+                    ));
+                }
             }
         }
-        Ok(class.get_class())
+        Ok(())
     }
 
+    /// Initializes the class, running its static initializers. If they throw an exception
+    /// which is not an `Error`, it is wrapped in a `java.lang.ExceptionInInitializerError`.
     fn init_class(
         &mut self,
         stack: &mut CallStack<'a>,
         class_to_init: &ClassRef<'a>,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        match self.run_static_initializer(stack, class_to_init) {
+            Err(MethodCallFailed::ExceptionThrown(JavaException(exception))) => {
+                let error_class = self.get_or_resolve_class(stack, "java/lang/Error")?;
+                let exception_class = self.get_class_by_id(exception.class_id())?;
+                if exception_class.is_subclass_of(error_class) {
+                    Err(MethodCallFailed::ExceptionThrown(JavaException(exception)))
+                } else {
+                    let error = self.new_java_exception(
+                        stack,
+                        "java/lang/ExceptionInInitializerError",
+                        "(Ljava/lang/Throwable;)V",
+                        vec![Value::Object(exception)],
+                    )?;
+                    Err(MethodCallFailed::ExceptionThrown(JavaException(error)))
+                }
+            }
+            result => result,
+        }
+    }
+
+    fn run_static_initializer(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        class_to_init: &ClassRef<'a>,
     ) -> Result<(), MethodCallFailed<'a>> {
         debug!("creating static instance of {}", class_to_init.name);
         let static_instance = self.new_object_of_class(class_to_init);
         self.statics.insert(class_to_init.id, static_instance);
+        class_to_init.init_state.set(ClassInitState::InProgress);
+        self.set_static_constant_values(stack, class_to_init)?;
         if let Some(clinit_method) = class_to_init.find_method("<clinit>", "()V") {
             debug!("invoking {}::<clinit>()", class_to_init.name);
             self.invoke(
@@ -130,15 +549,167 @@ impl<'a> Vm<'a> {
         Ok(())
     }
 
+    /// Static fields with a `ConstantValue` attribute are not assigned by `<clinit>`, so we
+    /// need to set them before running it. String constants are interned, like literals.
+    fn set_static_constant_values(
+        &mut self,
+        stack: &mut CallStack<'a>,
+        class: ClassRef<'a>,
+    ) -> Result<(), MethodCallFailed<'a>> {
+        for (index, field) in class.static_fields() {
+            let value = match &field.constant_value {
+                None => continue,
+                Some(FieldConstantValue::Int(value)) => Value::Int(*value),
+                Some(FieldConstantValue::Float(value)) => Value::Float(*value),
+                Some(FieldConstantValue::Long(value)) => Value::Long(*value),
+                Some(FieldConstantValue::Double(value)) => Value::Double(*value),
+                Some(FieldConstantValue::String(value)) => {
+                    Value::Object(self.intern_string(stack, value)?)
+                }
+            };
+            // Interning might have triggered a garbage collection, moving the static instance
+            let static_instance = self
+                .get_static_instance(class.id)
+                .ok_or(VmError::ValidationException)?;
+            static_instance.set_field(index, value);
+        }
+        Ok(())
+    }
+
+    /// Replaces the definition of an already loaded class, with the given class file, to
+    /// support reloading code during development. Similarly to the JVM's own redefinition,
+    /// only the methods' bodies can change, so that all the existing instances remain valid.
+    /// Methods that are currently executing will keep running the old code, and so will
+    /// the methods inherited by the subclasses already loaded.
+    pub fn redefine_class(
+        &mut self,
+        class_name: &str,
+        class_file_bytes: &[u8],
+    ) -> Result<(), VmError> {
+        self.class_manager
+            .redefine_class(class_name, class_file_bytes)?;
+        // Both the constants of the class and the methods referred by the other classes
+        // have changed
+        self.resolved_method_references.clear();
+        self.resolved_static_field_references.clear();
+        Ok(())
+    }
+
+    /// Unloads the class with the given name, which will be loaded and initialized again,
+    /// as a brand new class, at its next usage. Its static fields are discarded, so the
+    /// objects referred only by them can be garbage collected. Returns whether the class
+    /// was loaded. The class must not be executing any method.
+    pub fn forget_class(&mut self, class_name: &str) -> bool {
+        let Some(class) = self.class_manager.forget_class(class_name) else {
+            return false;
+        };
+        self.statics.remove(&class.id);
+        self.evict_class_objects(class_name);
+        // Other classes might have resolved references to the forgotten class' members
+        self.resolved_method_references.clear();
+        self.resolved_static_field_references.clear();
+        true
+    }
+
+    /// Removes the `java.lang.Class` instances of the class with the given name, and of
+    /// the arrays of it, which must not be shared with a new definition of the class.
+    /// Redefining a class, instead, keeps its id and thus its `java.lang.Class` instance.
+    fn evict_class_objects(&mut self, class_name: &str) {
+        let binary_name = class_name.replace('/', ".");
+        let array_element_name = format!("L{binary_name};");
+        self.class_objects.retain(|name, _| {
+            name != &binary_name && name.trim_start_matches('[') != array_element_name
+        });
+    }
+
+    pub(crate) fn get_resolved_method_reference(
+        &self,
+        class: ClassRef<'a>,
+        constant_index: u16,
+    ) -> Option<ClassAndMethod<'a>> {
+        if !self.is_current_definition(class) {
+            return None;
+        }
+        self.resolved_method_references
+            .get(&(class.id, constant_index))
+            .cloned()
+    }
+
+    pub(crate) fn cache_resolved_method_reference(
+        &mut self,
+        class: ClassRef<'a>,
+        constant_index: u16,
+        method: ClassAndMethod<'a>,
+    ) {
+        self.execution_counters.method_references_resolved += 1;
+        if self.is_current_definition(class) {
+            self.resolved_method_references
+                .insert((class.id, constant_index), method);
+        }
+    }
+
+    pub(crate) fn get_resolved_static_field_reference(
+        &self,
+        class: ClassRef<'a>,
+        constant_index: u16,
+    ) -> Option<(ClassRef<'a>, usize, &'a ClassFileField)> {
+        if !self.is_current_definition(class) {
+            return None;
+        }
+        self.resolved_static_field_references
+            .get(&(class.id, constant_index))
+            .copied()
+    }
+
+    pub(crate) fn cache_resolved_static_field_reference(
+        &mut self,
+        class: ClassRef<'a>,
+        constant_index: u16,
+        field: (ClassRef<'a>, usize, &'a ClassFileField),
+    ) {
+        self.execution_counters.static_field_references_resolved += 1;
+        if self.is_current_definition(class) {
+            self.resolved_static_field_references
+                .insert((class.id, constant_index), field);
+        }
+    }
+
+    /// After a class is redefined, the frames that were executing its old definition keep
+    /// using the old constant pool, which must not share the cache with the new one
+    fn is_current_definition(&self, class: ClassRef<'a>) -> bool {
+        self.find_class_by_id(class.id)
+            .is_some_and(|current| std::ptr::eq(current, class))
+    }
+
+    /// Returns whether the static initializer of the given class has completed successfully
+    pub fn is_class_initialized(&self, class_id: ClassId) -> bool {
+        self.find_class_by_id(class_id)
+            .is_some_and(|class| class.init_state() == ClassInitState::Initialized)
+    }
+
     pub fn get_class_by_id(&self, class_id: ClassId) -> Result<ClassRef<'a>, VmError> {
         self.find_class_by_id(class_id)
             .ok_or(VmError::ValidationException)
     }
 
+    /// Returns whether the instances of the given class are also instances of the class or
+    /// interface with the given name, i.e. whether it is the class itself, one of its
+    /// superclasses or one of the interfaces they implement, directly or not
+    pub fn is_assignable_from(
+        &self,
+        sub_class_id: ClassId,
+        target_name: &str,
+    ) -> Result<bool, VmError> {
+        let class = self.get_class_by_id(sub_class_id)?;
+        Ok(class.is_subclass_of_class_named(target_name))
+    }
+
     pub fn find_class_by_name(&self, class_name: &str) -> Option<ClassRef<'a>> {
         self.class_manager.find_class_by_name(class_name)
     }
 
+    /// Finds the method with the given name and descriptor, declared by the class or
+    /// inherited from one of its superclasses
     pub fn resolve_class_method(
         &mut self,
         call_stack: &mut CallStack<'a>,
@@ -148,9 +719,12 @@ impl<'a> Vm<'a> {
     ) -> Result<ClassAndMethod<'a>, MethodCallFailed<'a>> {
         self.get_or_resolve_class(call_stack, class_name)
             .and_then(|class| {
-                class
-                    .find_method(method_name, method_type_descriptor)
-                    .map(|method| ClassAndMethod { class, method })
+                std::iter::successors(Some(class), |class| class.superclass)
+                    .find_map(|class| {
+                        class
+                            .find_method(method_name, method_type_descriptor)
+                            .map(|method| ClassAndMethod { class, method })
+                    })
                     .ok_or(MethodCallFailed::InternalError(
                         VmError::MethodNotFoundException(
                             class_name.to_string(),
@@ -168,19 +742,226 @@ impl<'a> Vm<'a> {
         object: Option<AbstractObject<'a>>,
         args: Vec<Value<'a>>,
     ) -> MethodCallResult<'a> {
-        if class_and_method.method.is_native() {
-            return self.invoke_native(call_stack, class_and_method, object, args);
+        if self.to_string_in_progress.is_some()
+            && class_and_method.method.name == "toString"
+            && class_and_method.method.type_descriptor == "()Ljava/lang/String;"
+        {
+            if let Some(receiver) = object {
+                return self.invoke_to_string_guarded(call_stack, class_and_method, receiver);
+            }
         }
+        self.invoke_unguarded(call_stack, class_and_method, object, args)
+    }
 
-        // Generic bytecode method
-        let mut frame = call_stack.add_frame(class_and_method, object, args)?;
+    fn invoke_unguarded(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        class_and_method: ClassAndMethod<'a>,
+        object: Option<AbstractObject<'a>>,
+        args: Vec<Value<'a>>,
+    ) -> MethodCallResult<'a> {
+        let Some(listener) = self.invocation_listener.clone() else {
+            return self.invoke_traced(call_stack, class_and_method, object, args);
+        };
+        (listener.borrow_mut())(&InvocationEvent::Enter {
+            class_and_method: &class_and_method,
+            receiver: object.as_ref(),
+            arguments: &args,
+        });
+        let result = self.invoke_traced(call_stack, class_and_method.clone(), object, args);
+        // A native method could have replaced or removed the listener in the meantime, but
+        // the exit goes to the one notified of the entry, so that they stay balanced
+        (listener.borrow_mut())(&InvocationEvent::Exit {
+            class_and_method: &class_and_method,
+            result: &result,
+        });
+        result
+    }
+
+    fn invoke_traced(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        class_and_method: ClassAndMethod<'a>,
+        object: Option<AbstractObject<'a>>,
+        args: Vec<Value<'a>>,
+    ) -> MethodCallResult<'a> {
+        if self.invocation_trace.is_none() {
+            return self.execute_method(call_stack, class_and_method, object, args);
+        }
+
+        let method = class_and_method.description();
+        // Longs and doubles are followed by a placeholder for their second slot, which we skip
+        let arguments = args
+            .iter()
+            .filter(|arg| !matches!(arg, Value::Uninitialized))
+            .map(|arg| self.describe_value(arg))
+            .collect();
+        self.record_invocation_event(InvocationTraceEvent::Enter {
+            method: method.clone(),
+            arguments,
+        });
+        let result = self.execute_method(call_stack, class_and_method, object, args);
+        let event = match &result {
+            Ok(return_value) => InvocationTraceEvent::Exit {
+                method,
+                result: return_value
+                    .as_ref()
+                    .map_or("void".to_string(), |value| self.describe_value(value)),
+            },
+            Err(MethodCallFailed::ExceptionThrown(JavaException(exception))) => {
+                InvocationTraceEvent::Throw {
+                    method,
+                    exception: self.describe_class_of(exception),
+                }
+            }
+            Err(MethodCallFailed::InternalError(err)) => InvocationTraceEvent::Throw {
+                method,
+                exception: format!("internal error: {err}"),
+            },
+        };
+        self.record_invocation_event(event);
+        result
+    }
+
+    fn execute_method(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        class_and_method: ClassAndMethod<'a>,
+        object: Option<AbstractObject<'a>>,
+        args: Vec<Value<'a>>,
+    ) -> MethodCallResult<'a> {
+        if class_and_method.is_native() {
+            self.execution_counters.native_calls += 1;
+            self.active_native_calls += 1;
+            let result = self.invoke_native(call_stack, class_and_method, object, args);
+            self.active_native_calls -= 1;
+            return result;
+        }
+        if class_and_method.is_abstract() {
+            // Can only happen if the classes were compiled separately and are now inconsistent
+            return Err(self.throw_exception(
+                call_stack,
+                "java/lang/AbstractMethodError",
+                Some(&class_and_method.description()),
+            ));
+        }
+
+        if let Some(intrinsic) = self
+            .native_methods_registry
+            .get_intrinsic(&class_and_method)
+        {
+            debug!("executing intrinsic {}", class_and_method.description());
+            self.execution_counters.native_calls += 1;
+            return intrinsic(self, call_stack, object, args);
+        }
+
+        // Generic bytecode method. As for monitorenter and monitorexit, synchronized methods
+        // need no special handling: we are single-threaded, so there is no monitor that
+        // could be left acquired when the method returns or throws.
+        let mut frame = call_stack.add_frame(self, class_and_method, object, args)?;
+        // Once the method completes, the allocations are attributed to the instruction of
+        // the caller again, for example when it is a native method that allocates objects
+        let allocation_site = self.allocation_sites.as_ref().map(AllocationSites::current);
         let result = frame.as_mut().execute(self, call_stack);
+        if let (Some(allocation_sites), Some(site)) =
+            (self.allocation_sites.as_mut(), allocation_site)
+        {
+            allocation_sites.restore_current(site);
+        }
         call_stack
             .pop_frame()
             .expect("should be able to pop the frame we just pushed");
         result
     }
 
+    /// Invokes `toString` on the given object, for logging purposes. Cycles in the object
+    /// graph are detected: if the `toString` of an object is invoked again while we are
+    /// computing its string representation, the nested call will return the truncated
+    /// representation `class@identity_hash` rather than recursing forever.
+    pub fn to_string_recursion_safe(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        object: AbstractObject<'a>,
+    ) -> Result<String, MethodCallFailed<'a>> {
+        if object.kind() != ObjectKind::Object {
+            return Err(MethodCallFailed::InternalError(
+                VmError::ValidationException,
+            ));
+        }
+
+        let mut class = Some(self.get_class_by_id(object.class_id())?);
+        let class_and_method = loop {
+            let current_class = class.ok_or(VmError::ValidationException)?;
+            if let Some(method) = current_class.find_method("toString", "()Ljava/lang/String;") {
+                break ClassAndMethod {
+                    class: current_class,
+                    method,
+                };
+            }
+            class = current_class.superclass;
+        };
+
+        // Nested calls reuse the set of the outermost one
+        let is_outermost_call = self.to_string_in_progress.is_none();
+        if is_outermost_call {
+            self.to_string_in_progress = Some(HashSet::new());
+        }
+        let result = self.invoke(call_stack, class_and_method, Some(object), Vec::new());
+        if is_outermost_call {
+            self.to_string_in_progress = None;
+        }
+
+        match result? {
+            Some(Value::Object(string)) => Ok(extract_str_from_java_lang_string(self, &string)?),
+            Some(Value::Null) => Ok("null".to_string()),
+            _ => Err(MethodCallFailed::InternalError(
+                VmError::ValidationException,
+            )),
+        }
+    }
+
+    fn invoke_to_string_guarded(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        class_and_method: ClassAndMethod<'a>,
+        receiver: AbstractObject<'a>,
+    ) -> MethodCallResult<'a> {
+        let hash = receiver.identity_hash_code();
+        let is_new = self
+            .to_string_in_progress
+            .as_mut()
+            .is_some_and(|in_progress| in_progress.insert(hash));
+        if !is_new {
+            debug!("cycle detected while invoking toString on {:?}", receiver);
+            let class = self.get_class_by_id(receiver.class_id())?;
+            let truncated = format!("{}@{:x}", class.name.replace('/', "."), hash);
+            let string = new_java_lang_string_object(self, call_stack, &truncated)?;
+            return Ok(Some(Value::Object(string)));
+        }
+
+        let result =
+            self.invoke_unguarded(call_stack, class_and_method, Some(receiver), Vec::new());
+        if let Some(in_progress) = self.to_string_in_progress.as_mut() {
+            in_progress.remove(&hash);
+        }
+        result
+    }
+
+    /// Exposes a rust closure to java code. The closure will be invoked when calling the
+    /// static native method with the given name and descriptor of the class `rjvm/Host`.
+    pub fn register_host_function(
+        &mut self,
+        method_name: &str,
+        type_descriptor: &str,
+        function: impl Fn(Vec<Value<'a>>) -> MethodCallResult<'a> + 'a,
+    ) {
+        self.native_methods_registry.register_host_function(
+            method_name,
+            type_descriptor,
+            Box::new(function),
+        );
+    }
+
     fn invoke_native(
         &mut self,
         call_stack: &mut CallStack<'a>,
@@ -188,6 +969,19 @@ impl<'a> Vm<'a> {
         object: Option<AbstractObject<'a>>,
         args: Vec<Value<'a>>,
     ) -> MethodCallResult<'a> {
+        if class_and_method.class.name == HOST_BRIDGE_CLASS_NAME {
+            if let Some(host_function) = self.native_methods_registry.get_host_function(
+                &class_and_method.method.name,
+                &class_and_method.method.type_descriptor,
+            ) {
+                debug!(
+                    "executing host function {} {}",
+                    class_and_method.method.name, class_and_method.method.type_descriptor
+                );
+                return host_function(args);
+            }
+        }
+
         let native_callback = self.native_methods_registry.get_method(&class_and_method);
         if let Some(native_callback) = native_callback {
             debug!(
@@ -204,14 +998,116 @@ impl<'a> Vm<'a> {
                 class_and_method.method.name,
                 class_and_method.method.type_descriptor
             );
-            Err(MethodCallFailed::InternalError(VmError::NotImplemented))
+            match self.unresolved_native_behavior {
+                UnresolvedNativeBehavior::Error => {
+                    Err(MethodCallFailed::InternalError(VmError::NotImplemented))
+                }
+                UnresolvedNativeBehavior::ReturnDefaultValue => Ok(class_and_method
+                    .return_type()
+                    .map(|return_type| Value::default_for_type(&return_type))),
+                UnresolvedNativeBehavior::ThrowUnsatisfiedLinkError => Err(self.throw_exception(
+                    call_stack,
+                    "java/lang/UnsatisfiedLinkError",
+                    Some(&class_and_method.description()),
+                )),
+            }
         }
     }
 
+    /// Sets a listener notified whenever a method, either bytecode or native, is entered
+    /// or exited, or removes the current one if `None`. The methods being executed when
+    /// this is called still notify their exit to the previous listener.
+    pub fn set_invocation_listener(&mut self, listener: Option<InvocationListener<'a>>) {
+        self.invocation_listener = listener.map(|listener| Rc::new(RefCell::new(listener)));
+    }
+
+    /// Starts recording all the method invocations, with their arguments and results.
+    /// Any trace previously being recorded is discarded.
+    pub fn start_trace_recording(&mut self) {
+        self.invocation_trace = Some(InvocationTrace::default());
+    }
+
+    /// Stops recording the method invocations and returns what has been recorded
+    pub fn stop_trace_recording(&mut self) -> InvocationTrace {
+        self.invocation_trace.take().unwrap_or_default()
+    }
+
+    fn record_invocation_event(&mut self, event: InvocationTraceEvent) {
+        if let Some(trace) = self.invocation_trace.as_mut() {
+            trace.record(event);
+        }
+    }
+
+    /// Renders a value for an invocation trace. Objects are described by their class,
+    /// since their addresses change from one run to another, except strings, which are
+    /// described by their content.
+    fn describe_value(&self, value: &Value<'a>) -> String {
+        match value {
+            Value::Uninitialized => "uninitialized".to_string(),
+            Value::Int(value) => value.to_string(),
+            Value::Long(value) => format!("{value}L"),
+            Value::Float(value) => format!("{value:?}f"),
+            Value::Double(value) => format!("{value:?}d"),
+            Value::Null => "null".to_string(),
+            Value::Object(object) => {
+                if object.kind() == ObjectKind::Object {
+                    let is_string = self
+                        .get_class_by_id(object.class_id())
+                        .is_ok_and(|class| class.name == "java/lang/String");
+                    if is_string {
+                        if let Ok(string) = extract_str_from_java_lang_string(self, object) {
+                            return format!("{string:?}");
+                        }
+                    }
+                }
+                self.describe_class_of(object)
+            }
+        }
+    }
+
+    fn describe_class_of(&self, object: &AbstractObject<'a>) -> String {
+        match object.kind() {
+            ObjectKind::Object => self
+                .get_class_by_id(object.class_id())
+                .map_or("<unknown class>".to_string(), |class| class.name.clone()),
+            ObjectKind::Array => format!("array of length {}", object.len()),
+        }
+    }
+
+    /// Sets what happens when java code invokes a native method that has not been
+    /// registered. By default, a `java.lang.UnsatisfiedLinkError` is thrown.
+    pub fn set_unresolved_native_behavior(&mut self, behavior: UnresolvedNativeBehavior) {
+        self.unresolved_native_behavior = behavior;
+    }
+
+    /// Replaces the clock used by `System.currentTimeMillis` and `System.nanoTime`,
+    /// which by default is the system one
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
     /// Allocates a new call stack. We need to store it to be able to refer it later, for
     /// extracting the gc roots.
     pub fn allocate_call_stack(&mut self) -> &'a mut CallStack<'a> {
-        let stack = self.call_stacks.alloc(CallStack::new());
+        self.allocate_call_stack_with_max_depth(self.max_stack_depth)
+    }
+
+    /// Sets the maximum number of frames of the call stacks allocated from now on by
+    /// [Vm::allocate_call_stack]. Deeper calls will throw a `java.lang.StackOverflowError`.
+    pub fn set_max_stack_depth(&mut self, max_stack_depth: usize) {
+        self.max_stack_depth = max_stack_depth;
+    }
+
+    /// Like [Vm::allocate_call_stack], but the stack will contain at most `max_depth` frames
+    pub fn allocate_call_stack_with_max_depth(
+        &mut self,
+        max_depth: usize,
+    ) -> &'a mut CallStack<'a> {
+        let stack = self.call_stacks.alloc(CallStack::with_max_depth(max_depth));
         unsafe {
             let stack_ptr: *mut CallStack<'a> = stack;
             &mut *stack_ptr
@@ -227,9 +1123,187 @@ impl<'a> Vm<'a> {
         Ok(self.new_object_of_class(class))
     }
 
+    /// Allocates a new java exception, invoking the constructor with the given descriptor
+    pub(crate) fn new_java_exception(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        class_name: &str,
+        constructor_descriptor: &str,
+        args: Vec<Value<'a>>,
+    ) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+        let constructor =
+            self.resolve_class_method(call_stack, class_name, "<init>", constructor_descriptor)?;
+        let exception = self.new_object_of_class(constructor.class);
+        self.invoke(call_stack, constructor, Some(exception.clone()), args)?;
+        Ok(exception)
+    }
+
+    /// Creates a `java.lang.ArrayIndexOutOfBoundsException`, with a message describing
+    /// the invalid index, and returns it as a failed method call
+    pub fn throw_array_index_out_of_bounds(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        index: i32,
+        length: u32,
+    ) -> MethodCallFailed<'a> {
+        let message = format!("Index {index} out of bounds for length {length}");
+        self.throw_exception(
+            call_stack,
+            "java/lang/ArrayIndexOutOfBoundsException",
+            Some(&message),
+        )
+    }
+
+    /// Creates a `java.lang.NullPointerException`, for the instructions that dereference null
+    pub fn throw_npe(&mut self, call_stack: &mut CallStack<'a>) -> MethodCallFailed<'a> {
+        self.throw_exception(call_stack, "java/lang/NullPointerException", None)
+    }
+
+    /// Creates a `java.lang.ArithmeticException` with the given message, such as `/ by zero`
+    pub fn throw_arithmetic(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        message: &str,
+    ) -> MethodCallFailed<'a> {
+        self.throw_exception(call_stack, "java/lang/ArithmeticException", Some(message))
+    }
+
+    /// Creates an exception of the given class, with the given message, to be thrown by
+    /// the vm itself (for example a `java.lang.NullPointerException`). The stack trace
+    /// is captured by the constructor, as it happens for exceptions created in java code.
+    /// If the exception cannot be created, the error that happened is returned instead.
+    pub fn throw_exception(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        class_name: &str,
+        message: Option<&str>,
+    ) -> MethodCallFailed<'a> {
+        let exception = match message {
+            Some(message) => {
+                new_java_lang_string_object(self, call_stack, message).and_then(|message| {
+                    self.new_java_exception(
+                        call_stack,
+                        class_name,
+                        "(Ljava/lang/String;)V",
+                        vec![Value::Object(message)],
+                    )
+                })
+            }
+            None => self.new_java_exception(call_stack, class_name, "()V", Vec::new()),
+        };
+        match exception {
+            Ok(exception) => MethodCallFailed::ExceptionThrown(JavaException(exception)),
+            Err(err) => err,
+        }
+    }
+
+    /// Returns the interned `java.lang.String` with the given content, allocating it
+    /// the first time it is requested
+    pub fn intern_string(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        content: &str,
+    ) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+        if let Some(string) = self.interned_strings.get(content) {
+            self.intern_pool_stats.hits += 1;
+            return Ok(string.clone());
+        }
+        self.intern_pool_stats.misses += 1;
+        let string = new_java_lang_string_object(self, call_stack, content)?;
+        self.interned_strings
+            .insert(content.to_string(), string.clone());
+        Ok(string)
+    }
+
+    /// Implements `String::intern`: returns the interned string with the same content as
+    /// the given one, which becomes the interned one if there is none yet
+    pub(crate) fn intern_existing_string(
+        &mut self,
+        string: AbstractObject<'a>,
+        content: String,
+    ) -> AbstractObject<'a> {
+        if let Some(interned) = self.interned_strings.get(&content) {
+            self.intern_pool_stats.hits += 1;
+            return interned.clone();
+        }
+        self.intern_pool_stats.misses += 1;
+        self.interned_strings.insert(content, string.clone());
+        string
+    }
+
+    /// Returns the `java.lang.Class` instance of the class with the given binary name,
+    /// allocating it the first time it is requested
+    pub fn get_class_object(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        class_name: &str,
+    ) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+        if let Some(class_object) = self.class_objects.get(class_name) {
+            return Ok(class_object.clone());
+        }
+        let class_object = new_java_lang_class_object(self, call_stack, class_name)?;
+        self.class_objects
+            .insert(class_name.to_string(), class_object.clone());
+        Ok(class_object)
+    }
+
+    /// Keeps the given object alive, and up to date, while allocating other objects:
+    /// the gc might move it, so it must be retrieved with [Vm::pop_temporary_root]
+    pub(crate) fn push_temporary_root(&mut self, object: AbstractObject<'a>) {
+        self.temporary_roots.push(object);
+    }
+
+    /// Returns the object most recently passed to [Vm::push_temporary_root], at its
+    /// current address, without removing it
+    pub(crate) fn top_temporary_root(&self) -> AbstractObject<'a> {
+        self.temporary_roots
+            .last()
+            .cloned()
+            .expect("should have a temporary root")
+    }
+
+    /// Returns the object most recently passed to [Vm::push_temporary_root], at its
+    /// current address
+    pub(crate) fn pop_temporary_root(&mut self) -> AbstractObject<'a> {
+        self.temporary_roots
+            .pop()
+            .expect("should have a temporary root")
+    }
+
+    /// Adds the given strings to the intern pool, so that they will not need to be
+    /// allocated when loaded by java code. Useful for embedders that run many short
+    /// programs on the same vm. Seeding does not count as a hit or miss in the stats.
+    pub fn seed_intern_pool(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        strings: &[&str],
+    ) -> Result<(), MethodCallFailed<'a>> {
+        for content in strings {
+            if !self.interned_strings.contains_key(*content) {
+                let string = new_java_lang_string_object(self, call_stack, content)?;
+                self.interned_strings.insert(content.to_string(), string);
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes all the strings from the intern pool and resets its statistics
+    pub fn clear_intern_pool(&mut self) {
+        self.interned_strings.clear();
+        self.intern_pool_stats = Default::default();
+    }
+
+    pub fn intern_pool_stats(&self) -> InternPoolStats {
+        InternPoolStats {
+            size: self.interned_strings.len(),
+            ..self.intern_pool_stats
+        }
+    }
+
     pub fn new_object_of_class(&mut self, class: ClassRef<'a>) -> AbstractObject<'a> {
         debug!("allocating new instance of {}", class.name);
-        match self.object_allocator.allocate_object(class) {
+        self.execution_counters.objects_allocated += 1;
+        let object = match self.object_allocator.allocate_object(class) {
             Some(object) => object,
             None => {
                 self.run_garbage_collection()
@@ -238,7 +1312,9 @@ impl<'a> Vm<'a> {
                     .allocate_object(class)
                     .expect("cannot allocate object even after full garbage collection!")
             }
-        }
+        };
+        self.record_allocation_site(&object);
+        object
     }
 
     pub fn new_array(
@@ -246,7 +1322,8 @@ impl<'a> Vm<'a> {
         elements_type: ArrayEntryType,
         length: usize,
     ) -> AbstractObject<'a> {
-        match self
+        self.execution_counters.objects_allocated += 1;
+        let array = match self
             .object_allocator
             .allocate_array(elements_type.clone(), length)
         {
@@ -258,7 +1335,125 @@ impl<'a> Vm<'a> {
                     .allocate_array(elements_type, length)
                     .expect("cannot allocate array even after full garbage collection!")
             }
+        };
+        self.record_allocation_site(&array);
+        array
+    }
+
+    /// Limits the length of the arrays that java code can allocate. Requesting a longer
+    /// array will throw a `java.lang.OutOfMemoryError`, rather than exhausting the memory.
+    pub fn set_max_array_length(&mut self, max_array_length: usize) {
+        self.max_array_length = Some(max_array_length);
+    }
+
+    /// Validates the length of an array that java code is creating, returning it as usize
+    pub(crate) fn check_array_length(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        length: i32,
+    ) -> Result<usize, MethodCallFailed<'a>> {
+        let Ok(length) = usize::try_from(length) else {
+            return Err(self.throw_exception(
+                call_stack,
+                "java/lang/NegativeArraySizeException",
+                Some(&length.to_string()),
+            ));
+        };
+        match self.max_array_length {
+            Some(max_array_length) if length > max_array_length => Err(self.throw_exception(
+                call_stack,
+                "java/lang/OutOfMemoryError",
+                Some("Requested array size exceeds VM limit"),
+            )),
+            _ => Ok(length),
+        }
+    }
+
+    /// Returns the type of the entries of an array whose component has the given type,
+    /// loading the class of its innermost elements if required
+    pub(crate) fn array_entry_type(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        component_type: &FieldType,
+    ) -> Result<ArrayEntryType, MethodCallFailed<'a>> {
+        match component_type {
+            FieldType::Base(base_type) => Ok(ArrayEntryType::Base(base_type.clone())),
+            FieldType::Object(class_name) => Ok(ArrayEntryType::Object(
+                self.load_class_referenced_by_code(call_stack, class_name)?
+                    .id,
+            )),
+            FieldType::Array(component_type) => self
+                .array_entry_type(call_stack, component_type)?
+                .array_of()
+                .ok_or(MethodCallFailed::InternalError(
+                    VmError::ValidationException,
+                )),
+        }
+    }
+
+    /// Allocates a multi-dimensional array of the given type, as done by `multianewarray`.
+    /// Only the first `dimensions.len()` levels are created, with the given lengths;
+    /// the entries of the deepest allocated level are left null if the type has more.
+    pub fn allocate_multi_array(
+        &mut self,
+        call_stack: &mut CallStack<'a>,
+        array_type: &FieldType,
+        dimensions: &[i32],
+    ) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+        let mut lengths = Vec::with_capacity(dimensions.len());
+        for length in dimensions {
+            lengths.push(self.check_array_length(call_stack, *length)?);
+        }
+
+        let mut elements_types = Vec::with_capacity(dimensions.len());
+        let mut level_type = array_type;
+        for _ in dimensions {
+            let FieldType::Array(component_type) = level_type else {
+                return Err(MethodCallFailed::InternalError(
+                    VmError::ValidationException,
+                ));
+            };
+            elements_types.push(self.array_entry_type(call_stack, component_type)?);
+            level_type = component_type;
+        }
+
+        // Only the outermost array will be reachable from the call stack, so no garbage
+        // collection can happen while we are creating the nested ones
+        let mut required_memory = 0usize;
+        let mut arrays_in_level = 1usize;
+        for length in lengths.iter() {
+            required_memory = required_memory.saturating_add(
+                arrays_in_level.saturating_mul(AbstractObject::size_of_array(*length)),
+            );
+            arrays_in_level = arrays_in_level.saturating_mul(*length);
+        }
+        if required_memory > self.object_allocator.free_memory() {
+            self.run_garbage_collection()?;
+            if required_memory > self.object_allocator.free_memory() {
+                return Err(self.throw_exception(
+                    call_stack,
+                    "java/lang/OutOfMemoryError",
+                    Some("Java heap space"),
+                ));
+            }
+        }
+
+        self.new_multi_array_level(&elements_types, &lengths)
+    }
+
+    fn new_multi_array_level(
+        &mut self,
+        elements_types: &[ArrayEntryType],
+        lengths: &[usize],
+    ) -> Result<AbstractObject<'a>, MethodCallFailed<'a>> {
+        let array = self.new_array(elements_types[0].clone(), lengths[0]);
+        if lengths.len() > 1 {
+            for index in 0..lengths[0] {
+                let sub_array = self.new_multi_array_level(&elements_types[1..], &lengths[1..])?;
+                array.set_element(index, Value::Object(sub_array))?;
+            }
         }
+        Ok(array)
     }
 
     pub fn clone_array(&mut self, value: Value<'a>) -> Result<Value<'a>, VmError> {
@@ -290,25 +1485,172 @@ impl<'a> Vm<'a> {
             .get(&throwable.identity_hash_code())
     }
 
+    pub fn execution_counters(&self) -> ExecutionCounters {
+        self.execution_counters
+    }
+
+    /// Resets all the [ExecutionCounters] to zero, for example before running the code
+    /// whose performance should be measured
+    pub fn reset_execution_counters(&mut self) {
+        self.execution_counters = Default::default();
+    }
+
+    /// Enables or disables counting the instructions executed by each method, to find the
+    /// hot ones. It is off by default; disabling it discards the counts collected so far.
+    pub fn enable_profiling(&mut self, enabled: bool) {
+        if !enabled {
+            self.instruction_stats = None;
+        } else if self.instruction_stats.is_none() {
+            self.instruction_stats = Some(HashMap::new());
+        }
+    }
+
+    /// Returns the number of instructions executed by each method since profiling was enabled,
+    /// keyed by the [ClassAndMethod::description] of the method. The instructions of the
+    /// methods it invokes are not included. Unlike [ExecutionCounters::instructions_executed],
+    /// the counts are only updated when a frame completes.
+    pub fn instruction_stats(&self) -> HashMap<String, u64> {
+        self.instruction_stats.clone().unwrap_or_default()
+    }
+
+    pub(crate) fn record_executed_instructions(
+        &mut self,
+        class_and_method: &ClassAndMethod<'a>,
+        executed_instructions: u64,
+    ) {
+        if let Some(stats) = self.instruction_stats.as_mut() {
+            *stats.entry(class_and_method.description()).or_default() += executed_instructions;
+        }
+    }
+
+    /// Enables or disables recording where each object is allocated, to find the code
+    /// responsible for a leak with [Vm::live_objects_by_allocation_site]. It is off by
+    /// default; disabling it discards the sites recorded so far.
+    pub fn set_leak_diagnosis(&mut self, enabled: bool) {
+        if !enabled {
+            self.allocation_sites = None;
+        } else if self.allocation_sites.is_none() {
+            self.allocation_sites = Some(AllocationSites::default());
+        }
+    }
+
+    /// Runs a garbage collection and groups the reachable objects by the site that allocated
+    /// them. Objects allocated while leak diagnosis was disabled are not included.
+    pub fn live_objects_by_allocation_site(
+        &mut self,
+    ) -> Result<HashMap<AllocationSite, LiveObjects>, VmError> {
+        self.run_garbage_collection()?;
+        Ok(self
+            .allocation_sites
+            .as_ref()
+            .map(AllocationSites::live_objects)
+            .unwrap_or_default())
+    }
+
+    /// Sets the instruction to which the objects allocated from now on are attributed
+    pub(crate) fn set_current_allocation_site(
+        &mut self,
+        class_and_method: &ClassAndMethod<'a>,
+        pc: u16,
+    ) {
+        if let Some(allocation_sites) = self.allocation_sites.as_mut() {
+            allocation_sites.set_current(class_and_method, pc);
+        }
+    }
+
+    fn record_allocation_site(&mut self, object: &AbstractObject<'a>) {
+        if let Some(allocation_sites) = self.allocation_sites.as_mut() {
+            allocation_sites.record(object);
+        }
+    }
+
     pub fn debug_stats(&self) {
         debug!(
-            "VM classes={:?} allocator={:?}",
-            self.class_manager, self.object_allocator
+            "VM classes={:?} allocator={:?} counters={:?}",
+            self.class_manager, self.object_allocator, self.execution_counters
         )
     }
 
+    /// Makes the garbage collection run every time the bytecode allocates an object after
+    /// the given number of bytes has been allocated, or only when the memory is full if `None`
+    pub fn set_gc_threshold(&mut self, gc_threshold: Option<usize>) {
+        self.gc_threshold = gc_threshold;
+    }
+
+    /// Chooses when the classes referenced by the code are loaded. If lazy, which is the
+    /// default, a class is loaded only when an instruction referring to it is executed, so a
+    /// missing class is not an error unless that code runs. Otherwise, the references of all
+    /// the methods of a class are loaded before initializing it, and a missing one makes the
+    /// initialization fail with a `java.lang.NoClassDefFoundError`.
+    pub fn set_lazy_verification(&mut self, lazy_verification: bool) {
+        self.lazy_verification = lazy_verification;
+    }
+
+    /// Runs the garbage collection if the threshold has been crossed. It is invoked only by
+    /// the instructions that allocate objects, and does nothing while a native method is
+    /// executing, even if it called back into bytecode: native code might hold references
+    /// that are not tracked, while otherwise all the live values are stored in the call
+    /// frames. A full heap, instead, is collected anyway, so native methods must still use
+    /// [Vm::push_temporary_root] for the objects they hold while invoking or allocating.
+    pub(crate) fn collect_if_over_threshold(&mut self) {
+        if self.active_native_calls == 0
+            && self.gc_threshold.is_some_and(|threshold| {
+                self.object_allocator.allocated_since_last_gc() >= threshold
+            })
+        {
+            self.run_garbage_collection()
+                .expect("could run garbage collection");
+        }
+    }
+
+    pub fn heap_stats(&self) -> HeapStats {
+        HeapStats {
+            used_memory: self.object_allocator.used_memory(),
+            allocated_objects: self.object_allocator.allocated_objects(),
+        }
+    }
+
+    /// Collects the objects that are not reachable from the gc roots, i.e. the static fields,
+    /// the interned strings, the class objects, the temporary roots and the values in the
+    /// call stacks' frames. Since objects are moved, any other reference to them becomes
+    /// invalid.
     pub fn run_garbage_collection(&mut self) -> Result<(), VmError> {
+        self.execution_counters.garbage_collections += 1;
         let mut roots = vec![];
         roots.extend(
             self.statics
                 .iter_mut()
                 .map(|(_, object)| object as *mut AbstractObject<'a>),
         );
+        roots.extend(
+            self.interned_strings
+                .values_mut()
+                .map(|object| object as *mut AbstractObject<'a>),
+        );
+        roots.extend(
+            self.class_objects
+                .values_mut()
+                .map(|object| object as *mut AbstractObject<'a>),
+        );
+        roots.extend(
+            self.temporary_roots
+                .iter_mut()
+                .map(|object| object as *mut AbstractObject<'a>),
+        );
         roots.extend(self.call_stacks.iter_mut().flat_map(|s| s.gc_roots()));
 
+        let weak_roots = self
+            .allocation_sites
+            .as_mut()
+            .map(AllocationSites::weak_roots)
+            .unwrap_or_default();
+
         unsafe {
             self.object_allocator
-                .do_garbage_collection(roots, &self.class_manager)?;
+                .do_garbage_collection(roots, weak_roots, &self.class_manager)?;
+        }
+        if let Some(allocation_sites) = self.allocation_sites.as_mut() {
+            allocation_sites.remove_collected();
         }
         Ok(())
     }
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use rjvm_reader::program_counter::ProgramCounter;
+
 use crate::value_stack::ValueStackError;
 
 /// Various errors that are thrown when executing java bytecode
@@ -30,10 +32,6 @@ pub enum VmError {
     #[error("validation exception - invalid class file")]
     ValidationException,
 
-    /// TODO: this should become throwing a real `java.lang.ArithmeticException`
-    #[error("arithmetic exception")]
-    ArithmeticException,
-
     #[error("not yet implemented")]
     NotImplemented,
 
@@ -41,14 +39,19 @@ pub enum VmError {
     #[error("array index out of bounds")]
     ArrayIndexOutOfBoundsException,
 
-    /// TODO: this should become throwing a real `java.lang.ClassCastException`
-    #[error("class cast exception")]
-    ClassCastException,
+    /// An operation on the operand stack failed, i.e. the bytecode pushed more values than
+    /// the declared `max_stack` of the method, or popped from an empty stack. This can
+    /// only happen with malformed bytecode.
+    #[error("operand stack error: {0}")]
+    ValueStackError(ValueStackError),
+
+    /// Like `ValueStackError`, but with the address of the instruction that failed
+    #[error("verify error at pc {1}: {0}")]
+    VerifyError(ValueStackError, ProgramCounter),
 }
 
-// TODO: remove once we implement exceptions
 impl From<ValueStackError> for VmError {
-    fn from(_: ValueStackError) -> Self {
-        Self::ValidationException
+    fn from(value: ValueStackError) -> Self {
+        Self::ValueStackError(value)
     }
 }
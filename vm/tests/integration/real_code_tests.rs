@@ -1,8 +1,37 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use rjvm_reader::{
+    class_access_flags::ClassAccessFlags,
+    class_file::ClassFile,
+    class_file_method::{ClassFileMethod, ClassFileMethodCode},
+    field_flags::FieldFlags,
+    field_type::{BaseType, FieldType},
+    instruction::Instruction,
+    method_descriptor::MethodDescriptor,
+    method_flags::MethodFlags,
+    program_counter::ProgramCounter,
+};
 use rjvm_vm::{
+    allocation_site::AllocationSite,
+    array::Array,
+    array_entry_type::ArrayEntryType,
+    class::{Class, ClassId, ClassInitState},
+    class_and_method::ClassAndMethod,
+    constant_pool_view::{MemberReference, ResolvedConstant},
     exceptions::MethodCallFailed,
-    java_objects_creation::extract_str_from_java_lang_string,
-    value::{expect_concrete_object_at, Value},
-    vm::{Vm, DEFAULT_MAX_MEMORY},
+    invocation_listener::InvocationEvent,
+    invocation_trace::{InvocationTrace, InvocationTraceEvent},
+    java_objects_creation::{
+        extract_class_name_from_java_lang_class, extract_str_from_java_lang_string,
+    },
+    native_methods_registry::UnresolvedNativeBehavior,
+    object::Object,
+    test_support::{assert_throws, assert_throws_exactly, exception_message},
+    time::FixedClock,
+    value::{expect_abstract_object_at, expect_concrete_object_at, expect_int_at, Value},
+    value_stack::ValueStackError,
+    vm::{Vm, DEFAULT_MAX_MEMORY, ONE_MEGABYTE},
+    vm_error::VmError,
 };
 
 // This file tests the real classes in ../resources/rjvm
@@ -40,6 +69,46 @@ fn extract_printed_string(vm: &Vm, index: usize) -> String {
     extract_str_from_java_lang_string(vm, &string).expect("should have a valid string")
 }
 
+/// Creates a class, which is not registered in the vm, with a single public static method
+/// having the given bytecode, to test code that javac would not generate
+fn class_with_code(
+    method_name: &str,
+    type_descriptor: &str,
+    max_stack: u16,
+    max_locals: u16,
+    code: Vec<u8>,
+) -> &'static Class<'static> {
+    let method = ClassFileMethod {
+        flags: MethodFlags::PUBLIC | MethodFlags::STATIC,
+        name: method_name.to_string(),
+        type_descriptor: type_descriptor.to_string(),
+        parsed_type_descriptor: MethodDescriptor::parse(type_descriptor).unwrap(),
+        attributes: Vec::new(),
+        code: Some(ClassFileMethodCode {
+            max_stack,
+            max_locals,
+            code,
+            ..Default::default()
+        }),
+        deprecated: false,
+        thrown_exceptions: Vec::new(),
+        parameters: Vec::new(),
+        annotations: Vec::new(),
+    };
+    let class_file = ClassFile {
+        name: "rjvm/HandCrafted".to_string(),
+        flags: ClassAccessFlags::PUBLIC,
+        methods: vec![method],
+        ..Default::default()
+    };
+    Box::leak(Box::new(Class::new(
+        ClassId::new(u32::MAX),
+        class_file,
+        None,
+        Vec::new(),
+    )))
+}
+
 #[test_log::test]
 fn simple_main() {
     let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
@@ -134,6 +203,158 @@ fn numeric_types() {
     );
 }
 
+#[test_log::test]
+fn narrowing_conversions_saturate() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let cases = [
+        ("d2i", "(D)I", Value::Double(1e10), Value::Int(i32::MAX)),
+        ("d2i", "(D)I", Value::Double(-1e10), Value::Int(i32::MIN)),
+        ("d2i", "(D)I", Value::Double(f64::NAN), Value::Int(0)),
+        ("d2i", "(D)I", Value::Double(-3.9), Value::Int(-3)),
+        ("d2l", "(D)J", Value::Double(1e30), Value::Long(i64::MAX)),
+        (
+            "d2l",
+            "(D)J",
+            Value::Double(f64::NEG_INFINITY),
+            Value::Long(i64::MIN),
+        ),
+        ("d2l", "(D)J", Value::Double(f64::NAN), Value::Long(0)),
+        ("f2i", "(F)I", Value::Float(1e10), Value::Int(i32::MAX)),
+        (
+            "f2i",
+            "(F)I",
+            Value::Float(f32::NEG_INFINITY),
+            Value::Int(i32::MIN),
+        ),
+        ("f2i", "(F)I", Value::Float(f32::NAN), Value::Int(0)),
+        (
+            "f2l",
+            "(F)J",
+            Value::Float(f32::INFINITY),
+            Value::Long(i64::MAX),
+        ),
+        ("f2l", "(F)J", Value::Float(-1e30), Value::Long(i64::MIN)),
+        ("f2l", "(F)J", Value::Float(f32::NAN), Value::Long(0)),
+    ];
+    for (method_name, descriptor, argument, expected) in cases {
+        let call_stack = vm.allocate_call_stack();
+        let method = vm
+            .resolve_class_method(
+                call_stack,
+                "rjvm/NarrowingConversions",
+                method_name,
+                descriptor,
+            )
+            .expect("should find the method");
+        let result = vm.invoke(call_stack, method, None, vec![argument.clone()]);
+        assert_eq!(Ok(Some(expected)), result, "{method_name} of {argument:?}");
+    }
+}
+
+#[test_log::test]
+fn numeric_edge_cases() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/NumericEdgeCases",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(
+        vec![
+            Value::Int(1),
+            Value::Int(0),
+            Value::Int(0),
+            Value::Int(0),
+            Value::Int(0),
+            Value::Int(1),
+            Value::Int(0),
+            Value::Int(0),
+            Value::Long(1024),
+            Value::Long(3072),
+            Value::Long(1 << 52),
+            Value::Int(i32::MIN),
+            Value::Long(i64::MIN),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn math_functions() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/MathFunctions",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    let expected_doubles = [
+        2f64.sqrt(),
+        f64::NAN,
+        1f64.sin(),
+        1f64.cos(),
+        1024.0,
+        f64::NAN,
+        -3.0,
+        -2.0,
+        2.5,
+    ];
+    for (index, expected) in expected_doubles.into_iter().enumerate() {
+        let Value::Double(actual) = vm.printed[index] else {
+            panic!("should have printed a double at {index}");
+        };
+        assert!(
+            actual == expected || (actual.is_nan() && expected.is_nan()),
+            "at {index} expected {expected} but got {actual}"
+        );
+    }
+    assert_eq!(
+        vec![
+            Value::Float(2.5),
+            Value::Int(7),
+            Value::Int(i32::MIN),
+            Value::Long(7),
+            Value::Double(1f64.exp()),
+            Value::Double(10f64.ln()),
+            Value::Double(1f64.atan2(2.0)),
+        ],
+        vm.printed[9..]
+    );
+}
+
+#[test_log::test]
+fn math_min_and_max() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/MathFunctions", "minAndMax", "()V");
+    assert_eq!(Ok(None), result);
+    assert_eq!(
+        vec![
+            Value::Int(-4),
+            Value::Int(3),
+            Value::Long(3),
+            Value::Long(1 << 40),
+            Value::Float(-2.5),
+        ],
+        vm.printed[..5]
+    );
+    assert!(matches!(vm.printed[5], Value::Float(value) if value.is_nan()));
+    // Math.min and max consider -0.0 smaller than 0.0, which compare equal in Rust
+    assert!(
+        matches!(vm.printed[6], Value::Double(value) if value == 0.0 && value.is_sign_negative())
+    );
+    assert!(
+        matches!(vm.printed[7], Value::Double(value) if value == 0.0 && value.is_sign_positive())
+    );
+    assert_eq!(
+        vec![Value::Double(2.5), Value::Double(1.0)],
+        vm.printed[8..]
+    );
+}
+
 #[test_log::test]
 fn numeric_arrays() {
     let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
@@ -233,6 +454,13 @@ fn instance_of() {
             Value::Int(0),
             Value::Int(1),
             Value::Int(1),
+            // null
+            Value::Int(0),
+            Value::Int(0),
+            Value::Int(0),
+            Value::Int(0),
+            Value::Int(0),
+            Value::Int(1),
         ],
         vm.printed
     );
@@ -279,6 +507,28 @@ fn strings() {
     );
 }
 
+#[test_log::test]
+fn constant_pool_view() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let class = vm
+        .get_or_load_class("rjvm/Strings")
+        .expect("should be able to load the class");
+    let constant_pool = class.constant_pool();
+
+    let strings: Vec<&str> = constant_pool.strings().collect();
+    assert!(strings.contains(&"Andrea"));
+    assert!(strings.contains(&", you were born in "));
+    assert!(constant_pool
+        .iter()
+        .any(|(_, constant)| constant == ResolvedConstant::Class("java/lang/StringBuilder")));
+    assert!(constant_pool.iter().any(|(_, constant)| constant
+        == ResolvedConstant::MethodReference(MemberReference {
+            class_name: "rjvm/Strings",
+            name: "sayHello",
+            type_descriptor: "(Ljava/lang/String;I)V",
+        })));
+}
+
 #[test_log::test]
 fn invoke_interface() {
     let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
@@ -296,13 +546,126 @@ fn invoke_interface() {
     );
 }
 
+#[test_log::test]
+fn invoke_interface_of_inherited_implementation() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/InterfaceDispatch",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![Value::Int(4), Value::Int(3), Value::Int(4)],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn package_private_methods_dispatch() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/PackagePrivateDispatch",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            Value::Int(1),
+            Value::Int(2),
+            Value::Int(1),
+            Value::Int(3),
+            Value::Int(4),
+            Value::Int(5),
+        ],
+        vm.printed
+    );
+}
+
 #[test_log::test]
 fn check_cast() {
     let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
     let main_result = invoke(&mut vm, "rjvm/CheckCast", "main", "([Ljava/lang/String;)V");
     assert_eq!(Ok(None), main_result);
 
-    assert_eq!(vec![Value::Int(1)], vm.printed);
+    assert_eq!(8, vm.printed.len());
+    assert_eq!(
+        vec![Value::Int(1), Value::Int(1), Value::Int(1), Value::Int(1)],
+        vm.printed[..4]
+    );
+    assert_eq!(
+        "rjvm.CheckCast$C1 cannot be cast to rjvm.CheckCast$C2",
+        extract_printed_string(&vm, 4)
+    );
+    assert_eq!(
+        "rjvm.CheckCast$C2 cannot be cast to java.lang.Runnable",
+        extract_printed_string(&vm, 5)
+    );
+    assert_eq!(
+        "[Ljava.lang.String; cannot be cast to java.lang.Runnable",
+        extract_printed_string(&vm, 6)
+    );
+    assert_eq!(
+        "[I cannot be cast to java.lang.Runnable",
+        extract_printed_string(&vm, 7)
+    );
+}
+
+#[test_log::test]
+fn array_covariance() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ArrayCovariance",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            Value::Int(1),
+            Value::Int(2),
+            Value::Int(3),
+            Value::Int(4),
+            Value::Int(5),
+            Value::Int(1),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn check_cast_failure_throws_class_cast_exception() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/CheckCast", "uncaughtCastFailure", "()V");
+    assert_throws(&vm, result, "java/lang/ClassCastException", "");
+}
+
+#[test_log::test]
+fn assignability() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let class = vm
+        .get_or_load_class("rjvm/InstanceOf$C3")
+        .expect("should load the class");
+    for (target, expected) in [
+        ("rjvm/InstanceOf$C3", true),
+        ("java/lang/Object", true),
+        ("rjvm/InstanceOf$Intf1", true),
+        ("rjvm/InstanceOf$C4", false),
+        ("java/lang/Runnable", false),
+    ] {
+        assert_eq!(
+            Ok(expected),
+            vm.is_assignable_from(class.id, target),
+            "{target}"
+        );
+    }
 }
 
 #[test_log::test]
@@ -370,9 +733,2570 @@ fn gabarge_collector() {
     assert_eq!(Ok(None), main_result);
 }
 
+#[test_log::test]
+fn resolve_inherited_method() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack();
+    let object = vm
+        .new_object(call_stack, "rjvm/SuperClasses$DerivedClass")
+        .expect("should be able to create an object");
+
+    let set_base_value = vm
+        .resolve_class_method(
+            call_stack,
+            "rjvm/SuperClasses$DerivedClass",
+            "setBaseValue",
+            "(I)V",
+        )
+        .expect("should find the inherited method");
+    assert_eq!("rjvm/SuperClasses$BaseClass", set_base_value.class.name);
+    let result = vm.invoke(
+        call_stack,
+        set_base_value,
+        Some(object.clone()),
+        vec![Value::Int(5)],
+    );
+    assert_eq!(Ok(None), result);
+
+    let sum = vm
+        .resolve_class_method(call_stack, "rjvm/SuperClasses$DerivedClass", "sum", "()I")
+        .expect("should find the overridden method");
+    assert_eq!("rjvm/SuperClasses$DerivedClass", sum.class.name);
+    let result = vm.invoke(call_stack, sum, Some(object), vec![]);
+    assert_eq!(Ok(Some(Value::Int(6))), result);
+}
+
+#[test_log::test]
+fn resolve_method_inherited_from_object() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack();
+    let to_string = vm
+        .resolve_class_method(
+            call_stack,
+            "rjvm/SuperClasses$DerivedClass",
+            "toString",
+            "()Ljava/lang/String;",
+        )
+        .expect("should find the method declared by java.lang.Object");
+    assert_eq!("java/lang/Object", to_string.class.name);
+
+    let missing = vm.resolve_class_method(
+        call_stack,
+        "rjvm/SuperClasses$DerivedClass",
+        "missing",
+        "()V",
+    );
+    assert_eq!(
+        Some(MethodCallFailed::InternalError(
+            VmError::MethodNotFoundException(
+                "rjvm/SuperClasses$DerivedClass".to_string(),
+                "missing".to_string(),
+                "()V".to_string(),
+            )
+        )),
+        missing.err()
+    );
+}
+
+#[test_log::test]
+fn garbage_collection_frees_unreachable_objects() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    assert_eq!(
+        Ok(None),
+        invoke(&mut vm, "rjvm/ObjectGraph", "buildAndDrop", "()V")
+    );
+    let before = vm.heap_stats();
+    // Every node has an array, too
+    assert!(before.allocated_objects >= 200);
+
+    vm.run_garbage_collection()
+        .expect("should be able to run the gc");
+    let after_drop = vm.heap_stats();
+    assert!(after_drop.allocated_objects <= before.allocated_objects - 200);
+    assert!(after_drop.used_memory < before.used_memory);
+
+    assert_eq!(
+        Ok(None),
+        invoke(&mut vm, "rjvm/ObjectGraph", "buildAndKeep", "()V")
+    );
+    vm.run_garbage_collection()
+        .expect("should be able to run the gc");
+    assert_eq!(
+        after_drop.allocated_objects + 200,
+        vm.heap_stats().allocated_objects
+    );
+}
+
+#[test_log::test]
+fn garbage_collection_threshold() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.set_gc_threshold(Some(1024));
+    assert_eq!(
+        Ok(None),
+        invoke(&mut vm, "rjvm/ObjectGraph", "buildAndDrop", "()V")
+    );
+    assert!(vm.execution_counters().garbage_collections > 0);
+}
+
+#[test_log::test]
+fn byte_constants_are_sign_extended() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ByteConstants",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(
+        vec![Value::Int(-10), Value::Int(-128), Value::Int(127)],
+        vm.printed
+    );
+}
+
 #[test_log::test]
 fn generic() {
     let mut vm = create_base_vm(10_000_000);
     let main_result = invoke(&mut vm, "rjvm/Generic", "main", "([Ljava/lang/String;)V");
     assert_eq!(Ok(None), main_result);
 }
+
+#[test_log::test]
+fn host_functions() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.register_host_function("sum", "(II)I", |args| {
+        let a = expect_int_at(&args, 0)?;
+        let b = expect_int_at(&args, 1)?;
+        Ok(Some(Value::Int(a + b)))
+    });
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/HostFunctions",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(vec![Value::Int(7), Value::Int(-8)], vm.printed);
+}
+
+#[test_log::test]
+fn static_fields() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/StaticFields",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    let class = vm
+        .find_class_by_name("rjvm/StaticFields")
+        .expect("class should have been loaded");
+    let statics = vm
+        .get_static_fields_values(class)
+        .expect("class should have been initialized");
+    assert_eq!(5, statics.len());
+
+    let names_and_types: Vec<(&str, FieldType)> = statics
+        .iter()
+        .map(|(field, _)| (field.name.as_str(), field.type_descriptor.clone()))
+        .collect();
+    assert_eq!(
+        vec![
+            ("anInt", FieldType::Base(BaseType::Int)),
+            ("aLong", FieldType::Base(BaseType::Long)),
+            ("aDouble", FieldType::Base(BaseType::Double)),
+            ("aString", FieldType::Object("java/lang/String".to_string())),
+            ("aNull", FieldType::Object("java/lang/Object".to_string())),
+        ],
+        names_and_types
+    );
+
+    assert_eq!(Value::Int(42), statics[0].1);
+    assert_eq!(Value::Long(123456789012), statics[1].1);
+    assert_eq!(Value::Double(3.5), statics[2].1);
+    let string = expect_concrete_object_at(&[statics[3].1.clone()], 0)
+        .expect("should have a string in the static field");
+    assert_eq!(
+        "hello",
+        extract_str_from_java_lang_string(&vm, &string).expect("should have a valid string")
+    );
+    assert_eq!(Value::Null, statics[4].1);
+}
+
+#[test_log::test]
+fn char_arithmetic() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/CharArithmetic",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            Value::Int('b' as i32),
+            Value::Int(65536),
+            Value::Int(0),
+            Value::Int(1),
+            Value::Int(0),
+            Value::Int(65535),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn two_slot_locals() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/TwoSlotLocals",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            Value::Long(-1),
+            Value::Int(-2),
+            Value::Double(3.5),
+            Value::Long(11),
+            Value::Int(12),
+            Value::Double(13.5),
+            Value::Long(i64::MAX),
+            Value::Int(9),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn null_dereference_can_be_caught() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/NullDereference",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            Value::Int(1),
+            Value::Int(2),
+            Value::Int(3),
+            Value::Int(4),
+            Value::Int(5),
+            Value::Int(6),
+            Value::Int(7),
+            Value::Int(8),
+            Value::Int(9)
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn null_dereference_throws_null_pointer_exception() {
+    for (method, descriptor) in [
+        ("uncaughtGetField", "()I"),
+        ("uncaughtPutField", "()V"),
+        ("uncaughtInvokeVirtual", "()I"),
+        ("uncaughtInvokeInterface", "()V"),
+        ("uncaughtMonitorEnter", "()V"),
+        ("uncaughtArrayLength", "()I"),
+        ("uncaughtArrayLoad", "()I"),
+        ("uncaughtArrayStore", "()V"),
+        ("uncaughtThrowNull", "()V"),
+    ] {
+        let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+        let result = invoke(&mut vm, "rjvm/NullDereference", method, descriptor);
+        assert_throws(&vm, result, "java/lang/NullPointerException", "");
+    }
+}
+
+#[test_log::test]
+fn array_creation() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ArrayCreation",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(6, vm.printed.len());
+    assert_eq!(
+        vec![Value::Int(2), Value::Int(3), Value::Int(1), Value::Int(4)],
+        vm.printed[..4]
+    );
+    assert_eq!("-1", extract_printed_string(&vm, 4));
+    assert_eq!("-2", extract_printed_string(&vm, 5));
+}
+
+#[test_log::test]
+fn array_creation_with_negative_length() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/ArrayCreation", "negativeLength", "()I");
+    assert_throws(&vm, result, "java/lang/NegativeArraySizeException", "");
+}
+
+#[test_log::test]
+fn multi_dimensional_arrays() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.set_gc_threshold(Some(1024));
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/MultiArrays",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert!(vm.execution_counters().garbage_collections > 0);
+
+    assert_eq!(
+        vec![
+            Value::Int(2),
+            Value::Int(3),
+            Value::Int(36),
+            Value::Long(42),
+            Value::Int(2),
+            Value::Int(1),
+            Value::Int(5),
+            Value::Int(1),
+            Value::Int(12),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn multi_dimensional_array_clone_is_shallow() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/MultiArrays", "shallowClone", "()V");
+    assert_eq!(Ok(None), result);
+    assert_eq!(
+        vec![
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(30),
+            Value::Int(1),
+            Value::Int(1),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn multi_dimensional_array_with_negative_length() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/MultiArrays", "negativeDimension", "()V");
+    assert_eq!(Ok(None), result);
+    assert_eq!("-1", extract_printed_string(&vm, 0));
+}
+
+#[test_log::test]
+fn multi_dimensional_arrays_keep_the_type_of_their_elements() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/MultiArrays", "nestedTypes", "()V");
+    assert_eq!(Ok(None), result);
+    assert_eq!(
+        vec![Value::Int(1), Value::Int(1), Value::Int(0), Value::Int(0)],
+        vm.printed[0..4]
+    );
+    assert_eq!(
+        vec![Value::Int(1), Value::Int(0), Value::Int(1), Value::Int(0)],
+        vm.printed[4..8]
+    );
+    assert_eq!("[[[I", extract_printed_string(&vm, 8));
+    assert_eq!(
+        "[[Ljava.lang.String; cannot be cast to [[I",
+        extract_printed_string(&vm, 9)
+    );
+    assert_eq!("[Ljava.lang.Integer;", extract_printed_string(&vm, 10));
+    assert_eq!(vec![Value::Int(1), Value::Int(0)], vm.printed[11..]);
+}
+
+#[test_log::test]
+fn stack_overflow_can_be_caught() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack_with_max_depth(100);
+    let main_method = vm
+        .resolve_class_method(
+            call_stack,
+            "rjvm/StackOverflow",
+            "main",
+            "([Ljava/lang/String;)V",
+        )
+        .expect("should find main method");
+    let main_result = vm.invoke(call_stack, main_method, None, vec![Value::Null]);
+    assert_eq!(Ok(None), main_result);
+
+    // One frame is used by main, the others by the recursive method
+    assert_eq!(vec![Value::Int(99)], vm.printed);
+    assert_eq!(0, call_stack.depth());
+    assert_eq!(100, call_stack.max_depth());
+}
+
+#[test_log::test]
+fn max_stack_depth_is_configurable() {
+    for max_depth in [10, 50, 200] {
+        let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+        vm.set_max_stack_depth(max_depth);
+        let main_result = invoke(
+            &mut vm,
+            "rjvm/StackOverflow",
+            "main",
+            "([Ljava/lang/String;)V",
+        );
+        assert_eq!(Ok(None), main_result);
+        assert_eq!(vec![Value::Int(max_depth as i32 - 1)], vm.printed);
+    }
+}
+
+#[test_log::test]
+fn uncaught_stack_overflow() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack_with_max_depth(100);
+    let method = vm
+        .resolve_class_method(call_stack, "rjvm/StackOverflow", "uncaught", "()V")
+        .expect("should find method");
+    let result = vm.invoke(call_stack, method, None, vec![]);
+    assert_throws(&vm, result, "java/lang/StackOverflowError", "");
+    assert_eq!(0, call_stack.depth());
+}
+
+#[test_log::test]
+fn division_by_zero() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/DivisionByZero",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(9, vm.printed.len());
+    for index in 0..4 {
+        assert_eq!("/ by zero", extract_printed_string(&vm, index));
+    }
+    assert_eq!(
+        vec![
+            Value::Int(i32::MIN),
+            Value::Long(0),
+            Value::Float(f32::INFINITY)
+        ],
+        vm.printed[4..7]
+    );
+    assert!(matches!(vm.printed[7], Value::Float(value) if value.is_nan()));
+    assert_eq!(Value::Double(f64::NEG_INFINITY), vm.printed[8]);
+}
+
+#[test_log::test]
+fn uncaught_division_by_zero() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/DivisionByZero", "uncaught", "()I");
+    assert_throws(&vm, result, "java/lang/ArithmeticException", "");
+}
+
+#[test_log::test]
+fn concurrent_modification_is_detected() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ConcurrentModification",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(
+        vec![Value::Int(1), Value::Int(2), Value::Int(1)],
+        vm.printed
+    );
+
+    let result = invoke(&mut vm, "rjvm/ConcurrentModification", "uncaught", "()V");
+    assert_throws(&vm, result, "java/util/ConcurrentModificationException", "");
+}
+
+#[test_log::test]
+fn null_references() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/NullReferences",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(vec![Value::Int(1); 8], vm.printed);
+}
+
+#[test_log::test]
+fn null_field_defaults() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack();
+    let object = vm
+        .new_object(call_stack, "rjvm/NullReferences")
+        .expect("should be able to create an object");
+    let class = vm
+        .get_class_by_id(object.class_id())
+        .expect("should find the class");
+    for field_name in ["field", "array"] {
+        let (index, _) = class.find_field(field_name).expect("should find the field");
+        let value = object.get_field(class, index);
+        assert_eq!(Value::Null, value);
+        assert_eq!(Ok(None), value.as_reference());
+    }
+
+    assert_ne!(Value::Null, Value::Object(object));
+    assert_eq!(
+        Err(VmError::ValidationException),
+        Value::Int(0).as_reference()
+    );
+}
+
+#[test_log::test]
+fn array_copy() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(&mut vm, "rjvm/ArrayCopy", "main", "([Ljava/lang/String;)V");
+    assert_eq!(Ok(None), main_result);
+
+    let mut expected: Vec<Value> = [1, 1, 2, 3, 4, 2, 3, 4, 5, 5]
+        .into_iter()
+        .map(Value::Int)
+        .collect();
+    expected.extend(vec![Value::Int(1); 7]);
+    assert_eq!(expected, vm.printed);
+}
+
+#[test_log::test]
+fn identity_hash_codes() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.set_gc_threshold(Some(1024));
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/IdentityHashCodes",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert!(vm.execution_counters().garbage_collections > 0);
+    assert_eq!(vec![Value::Int(1); 6], vm.printed);
+}
+
+#[test_log::test]
+fn class_objects_are_unique() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.set_gc_threshold(Some(1024));
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ClassObjects",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert!(vm.execution_counters().garbage_collections > 0);
+    assert_eq!(vec![Value::Int(1); 8], vm.printed[..8]);
+    assert_eq!("rjvm.ClassObjects", extract_printed_string(&vm, 8));
+}
+
+#[test_log::test]
+fn array_copy_of_arrays_of_arrays() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/ArrayCopy", "arraysOfArrays", "()V");
+    assert_eq!(Ok(None), result);
+    assert_eq!(vec![Value::Int(1); 4], vm.printed);
+}
+
+#[test_log::test]
+fn array_copy_exception_messages() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/ArrayCopy", "outOfBounds", "()V");
+    assert_throws(
+        &vm,
+        result,
+        "java/lang/ArrayIndexOutOfBoundsException",
+        "cannot copy 2 elements from index 2 of an array of length 3",
+    );
+}
+
+#[test_log::test]
+fn array_copy_checks_null_before_the_types() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(
+        &mut vm,
+        "rjvm/ArrayCopy",
+        "nullDestinationOfAnotherType",
+        "()V",
+    );
+    assert_throws_exactly(&vm, result, "java/lang/NullPointerException", "");
+}
+
+#[test_log::test]
+fn boxing() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(&mut vm, "rjvm/Boxing", "main", "([Ljava/lang/String;)V");
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(
+        vec![
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(2000),
+            Value::Long(1 << 40),
+            Value::Int(1),
+            Value::Double(5.0),
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(1),
+            Value::Float(2.5),
+            Value::Long(1000),
+            Value::Int(-123),
+            Value::Long(9_000_000_000),
+        ],
+        vm.printed[..14]
+    );
+    assert_eq!("For input string: \"12x\"", extract_printed_string(&vm, 14));
+    assert_eq!(Value::Int(30), vm.printed[15]);
+}
+
+#[test_log::test]
+fn injected_clock() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.set_clock(Box::new(FixedClock(Duration::from_millis(42))));
+    let main_result = invoke(&mut vm, "rjvm/Clock", "main", "([Ljava/lang/String;)V");
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(vec![Value::Long(42), Value::Long(42_000_000)], vm.printed);
+}
+
+#[test_log::test]
+fn string_methods() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/StringMethods",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(
+        vec![
+            Value::Int(12),
+            Value::Int('o' as i32),
+            Value::Int(1),
+            Value::Int(0),
+            Value::Int(0),
+            Value::Int(4),
+            Value::Int(8),
+            Value::Int(-1),
+            Value::Int(-1),
+            Value::Int(1),
+            Value::Int(0),
+        ],
+        vm.printed[..11]
+    );
+    assert_eq!("world", extract_printed_string(&vm, 11));
+    assert_eq!(vec![Value::Int(1); 4], vm.printed[12..]);
+}
+
+#[test_log::test]
+fn string_concatenation() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/StringConcatenation",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(
+        "i=-42 l=1099511627776 f=1.5 d=-0.25 b=true c=x null",
+        extract_printed_string(&vm, 0)
+    );
+    assert_eq!("98765432109876543210", extract_printed_string(&vm, 1));
+    assert_eq!(Value::Int(20), vm.printed[2]);
+    assert_eq!("1.0E10 0.1 NaN 1.0E-5", extract_printed_string(&vm, 3));
+}
+
+#[test_log::test]
+fn floating_point_concatenation_with_a_small_heap() {
+    let mut vm = create_base_vm(2 * ONE_MEGABYTE);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/StringConcatenation",
+        "concatenateInLoop",
+        "()V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(vec![Value::Int(0)], vm.printed);
+    assert!(vm.execution_counters().garbage_collections > 0);
+}
+
+#[test_log::test]
+fn static_field_access() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/StaticFieldAccess",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(
+        vec![
+            Value::Int(42),
+            Value::Int(43),
+            Value::Long(1 << 40),
+            Value::Double(2.5),
+        ],
+        vm.printed[..4]
+    );
+    assert_eq!("set in clinit", extract_printed_string(&vm, 4));
+    // Derived's static initializer, which would print 2, never runs
+    assert_eq!(
+        vec![Value::Int(1), Value::Int(10), Value::Int(20), Value::Int(4)],
+        vm.printed[5..]
+    );
+    let derived = vm
+        .find_class_by_name("rjvm/StaticFieldAccess$Derived")
+        .expect("should have loaded the subclass");
+    assert!(!vm.is_class_initialized(derived.id));
+}
+
+#[test_log::test]
+fn switches() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(&mut vm, "rjvm/Switches", "main", "([Ljava/lang/String;)V");
+    assert_eq!(Ok(None), main_result);
+    let expected = [
+        // Dense keys, with a tableswitch
+        &[-1, 10, 11, 12, 13, -1, -1][..],
+        // Sparse keys, with a lookupswitch
+        &[1, 2, 0, 3, 0, 4],
+        // Falling through the cases
+        &[3, 2],
+        // On a char, on a string and on an enum
+        &[2, 2, 0, 3],
+    ]
+    .concat();
+    assert_eq!(
+        expected.into_iter().map(Value::Int).collect::<Vec<_>>(),
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn class_objects_survive_a_gc_while_allocating_their_name() {
+    let mut vm = create_base_vm(ONE_MEGABYTE);
+    let call_stack = vm.allocate_call_stack();
+
+    // Before creating each class object, we fill the heap leaving a bit of free space,
+    // different every time, so that the gc runs while allocating the object or its name
+    let class_names: Vec<String> = (0..500)
+        .map(|index| format!("rjvm.Generated{index}"))
+        .collect();
+    for (index, class_name) in class_names.iter().enumerate() {
+        let semi_space_size = ONE_MEGABYTE / 2;
+        let free_memory = semi_space_size - vm.heap_stats().used_memory;
+        let slack = 64 + index % 32 * 24;
+        vm.new_array(
+            ArrayEntryType::Base(BaseType::Long),
+            free_memory.saturating_sub(slack) / 8,
+        );
+        vm.get_class_object(call_stack, class_name)
+            .expect("should be able to create the class object");
+    }
+    assert!(vm.execution_counters().garbage_collections > 0);
+
+    for class_name in class_names.iter() {
+        let class_object = vm
+            .get_class_object(call_stack, class_name)
+            .expect("should find the class object");
+        assert_eq!(
+            Ok(class_name.clone()),
+            extract_class_name_from_java_lang_class(&vm, &class_object)
+        );
+    }
+}
+
+#[test_log::test]
+fn strings_survive_a_gc_while_allocating_their_characters() {
+    let mut vm = create_base_vm(ONE_MEGABYTE);
+    let call_stack = vm.allocate_call_stack();
+
+    // Before creating each string, we fill the heap leaving a bit of free space,
+    // different every time, so that the gc runs while allocating the object or its chars
+    let contents: Vec<String> = (0..500).map(|index| format!("string {index}")).collect();
+    for (index, content) in contents.iter().enumerate() {
+        let semi_space_size = ONE_MEGABYTE / 2;
+        let free_memory = semi_space_size - vm.heap_stats().used_memory;
+        let slack = 64 + index % 32 * 24;
+        vm.new_array(
+            ArrayEntryType::Base(BaseType::Long),
+            free_memory.saturating_sub(slack) / 8,
+        );
+        vm.intern_string(call_stack, content)
+            .expect("should be able to create the string");
+    }
+    assert!(vm.execution_counters().garbage_collections > 0);
+
+    for content in contents.iter() {
+        let string = vm
+            .intern_string(call_stack, content)
+            .expect("should find the interned string");
+        assert_eq!(
+            Ok(content.clone()),
+            extract_str_from_java_lang_string(&vm, &string)
+        );
+    }
+}
+
+#[test_log::test]
+fn array_bounds_checking() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ArrayBounds",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(4, vm.printed.len());
+    assert_eq!(
+        "Index 3 out of bounds for length 3",
+        extract_printed_string(&vm, 0)
+    );
+    assert_eq!(
+        "Index -1 out of bounds for length 3",
+        extract_printed_string(&vm, 1)
+    );
+    assert_eq!(
+        "Index 1 out of bounds for length 1",
+        extract_printed_string(&vm, 2)
+    );
+    assert_eq!(
+        "Index 0 out of bounds for length 0",
+        extract_printed_string(&vm, 3)
+    );
+}
+
+#[test_log::test]
+fn array_store_past_the_end() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/ArrayBounds", "storePastTheEnd", "()V");
+    assert_throws(&vm, result, "java/lang/ArrayIndexOutOfBoundsException", "");
+}
+
+#[test_log::test]
+fn exception_propagation() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ExceptionPropagation",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![Value::Int(1), Value::Int(2), Value::Int(3), Value::Int(4)],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn uncaught_exception_propagates_to_the_caller() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/ExceptionPropagation", "uncaught", "()V");
+    assert_throws(&vm, result, "rjvm/ExceptionPropagation$Inner", "");
+}
+
+#[test_log::test]
+fn floating_point_remainder() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/FloatingPointRemainder",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(14, vm.printed.len());
+    assert_eq!(Value::Double(-2.0), vm.printed[0]);
+    assert_eq!(Value::Double(2.0), vm.printed[1]);
+    assert_eq!(Value::Double(5.0), vm.printed[2]);
+    for value in &vm.printed[3..7] {
+        assert!(matches!(value, Value::Double(d) if d.is_nan()));
+    }
+    assert_eq!(Value::Float(-2.0), vm.printed[7]);
+    assert_eq!(Value::Float(2.0), vm.printed[8]);
+    assert_eq!(Value::Float(5.0), vm.printed[9]);
+    for value in &vm.printed[10..14] {
+        assert!(matches!(value, Value::Float(f) if f.is_nan()));
+    }
+}
+
+#[test_log::test]
+fn class_initialization_failure() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ClassInitializationFailure",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(4, vm.printed.len());
+    assert_eq!(Value::Int(1), vm.printed[0]);
+    assert_eq!(Value::Int(1), vm.printed[1]);
+    assert_eq!(Value::Int(2), vm.printed[2]);
+    assert_eq!(
+        "Could not initialize class rjvm.ClassInitializationFailure$Failing",
+        extract_printed_string(&vm, 3)
+    );
+    let failing = vm
+        .find_class_by_name("rjvm/ClassInitializationFailure$Failing")
+        .expect("class should have been loaded");
+    assert_eq!(ClassInitState::Erroneous, failing.init_state());
+    assert!(!vm.is_class_initialized(failing.id));
+}
+
+#[test_log::test]
+fn class_introspection() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack();
+    let class = vm
+        .get_or_resolve_class(call_stack, "rjvm/StaticFields")
+        .expect("should be able to load the class");
+
+    let main = class
+        .methods()
+        .iter()
+        .find(|method| method.name == "main")
+        .expect("should find the main method");
+    assert_eq!("([Ljava/lang/String;)V", main.type_descriptor);
+    assert!(main
+        .flags
+        .contains(MethodFlags::PUBLIC | MethodFlags::STATIC));
+
+    let constructor = class
+        .methods()
+        .iter()
+        .find(|method| method.name == "<init>")
+        .expect("should find the constructor");
+    assert!(!constructor.is_static());
+
+    let field_names: Vec<&str> = class
+        .fields()
+        .iter()
+        .map(|field| field.name.as_str())
+        .collect();
+    assert_eq!(
+        vec!["anInt", "aLong", "aDouble", "aString", "aNull", "notStatic"],
+        field_names
+    );
+    let not_static = &class.fields()[5];
+    assert_eq!(FieldType::Base(BaseType::Int), not_static.type_descriptor);
+    assert!(not_static.flags.contains(FieldFlags::PRIVATE));
+    assert!(!not_static.flags.contains(FieldFlags::STATIC));
+}
+
+#[test_log::test]
+fn increments() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(&mut vm, "rjvm/Increments", "main", "([Ljava/lang/String;)V");
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            Value::Int(9),
+            Value::Int(-91),
+            Value::Int(909),
+            Value::Int(-29091),
+            Value::Int(i32::MIN),
+            Value::Int((i32::MAX - 10).wrapping_add(1000)),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn class_initialization_order() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ClassInitializationOrder",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(vec![Value::Int(70), Value::Int(7)], vm.printed);
+}
+
+#[test_log::test]
+fn cyclic_to_string() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let cycle = invoke(
+        &mut vm,
+        "rjvm/CyclicToString",
+        "createCycle",
+        "()Lrjvm/CyclicToString;",
+    )
+    .expect("should be able to create the objects");
+    let cycle = expect_abstract_object_at(&[cycle.expect("should return an object")], 0)
+        .expect("should return an object");
+
+    let call_stack = vm.allocate_call_stack();
+    let string = vm
+        .to_string_recursion_safe(call_stack, cycle)
+        .expect("should be able to invoke toString");
+    assert!(
+        string.starts_with("first -> second -> rjvm.CyclicToString@"),
+        "unexpected string {string}"
+    );
+}
+
+#[test_log::test]
+fn floating_point_constants() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/FloatingPointConstants",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            Value::Int((-0.0f32).to_bits() as i32),
+            Value::Int(f32::from_bits(1).to_bits() as i32),
+            Value::Long((-0.0f64).to_bits() as i64),
+            Value::Long(1),
+            Value::Long(2.2250738585072E-308f64.to_bits() as i64),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn vm_generated_exception() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack();
+    let exception = vm.throw_exception(
+        call_stack,
+        "java/lang/IllegalStateException",
+        Some("something went wrong"),
+    );
+
+    let MethodCallFailed::ExceptionThrown(exception) = exception else {
+        panic!("should have created a java exception, got {exception:?}");
+    };
+    let class = vm
+        .get_class_by_id(exception.0.class_id())
+        .expect("exception should have a valid class");
+    assert_eq!("java/lang/IllegalStateException", class.name);
+    assert_eq!(
+        Ok(Some("something went wrong".to_string())),
+        exception_message(&vm, &exception)
+    );
+}
+
+#[test_log::test]
+fn assert_throws_matches_class_and_message() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack();
+    for class_name in [
+        "java/lang/NullPointerException",
+        "java/lang/RuntimeException",
+        "java/lang/Throwable",
+    ] {
+        let exception = vm.throw_exception(
+            call_stack,
+            "java/lang/NullPointerException",
+            Some("cannot read field value"),
+        );
+        assert_throws::<()>(&vm, Err(exception), class_name, "field value");
+    }
+
+    let result = invoke(&mut vm, "rjvm/NullDereference", "uncaughtGetField", "()I");
+    assert_throws(&vm, result, "java/lang/NullPointerException", "");
+}
+
+#[test_log::test]
+#[should_panic(expected = "should have thrown a java/lang/IllegalStateException")]
+fn assert_throws_rejects_other_classes() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/NullDereference", "uncaughtGetField", "()I");
+    assert_throws(&vm, result, "java/lang/IllegalStateException", "");
+}
+
+#[test_log::test]
+#[should_panic(expected = "should have thrown a java/lang/RuntimeException")]
+fn assert_throws_exactly_rejects_subclasses() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/NullDereference", "uncaughtGetField", "()I");
+    assert_throws_exactly(&vm, result, "java/lang/RuntimeException", "");
+}
+
+#[test_log::test]
+#[should_panic(expected = "should contain \"something else\"")]
+fn assert_throws_rejects_other_messages() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack();
+    let exception = vm.throw_exception(
+        call_stack,
+        "java/lang/NullPointerException",
+        Some("cannot read field value"),
+    );
+    assert_throws::<()>(
+        &vm,
+        Err(exception),
+        "java/lang/NullPointerException",
+        "something else",
+    );
+}
+
+#[test_log::test]
+fn print_char_array() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/PrintCharArray",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(2, vm.printed.len());
+    assert_eq!("hello", extract_printed_string(&vm, 0));
+    assert_eq!("", extract_printed_string(&vm, 1));
+}
+
+#[test_log::test]
+fn max_array_length() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.set_max_array_length(1000);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/LargeArrays",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(3, vm.printed.len());
+    assert_eq!(Value::Int(1000), vm.printed[0]);
+    assert_eq!(
+        "Requested array size exceeds VM limit",
+        extract_printed_string(&vm, 1)
+    );
+    assert_eq!(
+        "Requested array size exceeds VM limit",
+        extract_printed_string(&vm, 2)
+    );
+}
+
+#[test_log::test]
+fn instance_fields_access_by_name() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let object = invoke(
+        &mut vm,
+        "rjvm/FieldAccess",
+        "create",
+        "()Lrjvm/FieldAccess$Derived;",
+    )
+    .expect("should be able to create the object");
+    let object = expect_abstract_object_at(&[object.expect("should return an object")], 0)
+        .expect("should return an object");
+
+    assert_eq!(Ok(Value::Int(1)), vm.get_instance_field(&object, "x"));
+    assert_eq!(Ok(Value::Long(2)), vm.get_instance_field(&object, "y"));
+    assert_eq!(
+        Err(VmError::FieldNotFoundException(
+            "rjvm/FieldAccess$Derived".to_string(),
+            "z".to_string()
+        )),
+        vm.get_instance_field(&object, "z")
+    );
+
+    vm.set_instance_field(&object, "x", Value::Int(40))
+        .expect("should be able to set the field");
+    assert_eq!(
+        Err(VmError::ValidationException),
+        vm.set_instance_field(&object, "y", Value::Int(3))
+    );
+    assert_eq!(Ok(Value::Int(40)), vm.get_instance_field(&object, "x"));
+
+    let call_stack = vm.allocate_call_stack();
+    let sum_method = vm
+        .resolve_class_method(call_stack, "rjvm/FieldAccess$Derived", "sum", "()J")
+        .expect("should find the method");
+    let sum = vm.invoke(call_stack, sum_method, Some(object), vec![]);
+    assert_eq!(Ok(Some(Value::Long(42))), sum);
+}
+
+#[test_log::test]
+fn areturn_of_subtype() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ReturnSubtype",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(2, vm.printed.len());
+    assert_eq!("hello", extract_printed_string(&vm, 0));
+    assert_eq!(Value::Int(1), vm.printed[1]);
+}
+
+#[test_log::test]
+fn areturn_of_incompatible_type() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(
+        &mut vm,
+        "rjvm/ReturnSubtype",
+        "returnIncompatible",
+        "()Lrjvm/ReturnSubtype$Base;",
+    );
+    assert_eq!(
+        Err(MethodCallFailed::InternalError(
+            VmError::ValidationException
+        )),
+        result
+    );
+}
+
+#[test_log::test]
+fn redefine_class() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let old_instance = invoke(
+        &mut vm,
+        "rjvm/Redefinable",
+        "create",
+        "()Lrjvm/Redefinable;",
+    )
+    .expect("should be able to create the object")
+    .expect("should return an object");
+    let old_instance = expect_abstract_object_at(&[old_instance], 0).expect("should be an object");
+
+    let call_stack = vm.allocate_call_stack();
+    let value_method = vm
+        .resolve_class_method(call_stack, "rjvm/Redefinable", "value", "()I")
+        .expect("should find the method");
+    let value = vm.invoke(call_stack, value_method, Some(old_instance.clone()), vec![]);
+    assert_eq!(Ok(Some(Value::Int(11))), value);
+
+    vm.redefine_class(
+        "rjvm/Redefinable",
+        include_bytes!("../resources/redefinitions/body_changed/rjvm/Redefinable.class"),
+    )
+    .expect("should be able to redefine the class");
+
+    let value_method = vm
+        .resolve_class_method(call_stack, "rjvm/Redefinable", "value", "()I")
+        .expect("should find the method");
+    let value = vm.invoke(call_stack, value_method.clone(), Some(old_instance), vec![]);
+    assert_eq!(Ok(Some(Value::Int(20))), value);
+
+    let new_instance = invoke(
+        &mut vm,
+        "rjvm/Redefinable",
+        "create",
+        "()Lrjvm/Redefinable;",
+    )
+    .expect("should be able to create the object")
+    .expect("should return an object");
+    let new_instance = expect_abstract_object_at(&[new_instance], 0).expect("should be an object");
+    let value = vm.invoke(call_stack, value_method, Some(new_instance), vec![]);
+    assert_eq!(Ok(Some(Value::Int(20))), value);
+}
+
+#[test_log::test]
+fn forgotten_class_statics_are_collected() {
+    let mut vm = create_base_vm(10_000_000);
+    assert_eq!(
+        Ok(None),
+        invoke(&mut vm, "rjvm/ForgottenStatics", "touch", "()V")
+    );
+    let old_class = vm
+        .find_class_by_name("rjvm/ForgottenStatics")
+        .expect("class should be loaded");
+    assert!(vm.forget_class("rjvm/ForgottenStatics"));
+    assert!(!vm.forget_class("rjvm/ForgottenStatics"));
+    assert!(vm.find_class_by_name("rjvm/ForgottenStatics").is_none());
+
+    // The class is initialized again, and its new array can only be allocated if the
+    // one referred by the old statics has been collected
+    assert_eq!(
+        Ok(None),
+        invoke(&mut vm, "rjvm/ForgottenStatics", "touch", "()V")
+    );
+    let new_class = vm
+        .find_class_by_name("rjvm/ForgottenStatics")
+        .expect("class should be loaded again");
+    assert_ne!(old_class.id, new_class.id);
+    assert!(vm.get_static_fields_values(old_class).is_none());
+    assert!(vm.execution_counters().garbage_collections > 0);
+    assert_eq!(vec![Value::Int(393216), Value::Int(393216)], vm.printed);
+}
+
+#[test_log::test]
+fn forget_class_discards_its_class_objects() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack();
+    vm.get_or_resolve_class(call_stack, "rjvm/ForgottenStatics")
+        .expect("should be able to load the class");
+    let class_names = [
+        "rjvm.ForgottenStatics",
+        "[Lrjvm.ForgottenStatics;",
+        "[[Lrjvm.ForgottenStatics;",
+        "java.lang.String",
+    ];
+    let old_class_objects: Vec<_> = class_names
+        .iter()
+        .map(|class_name| vm.get_class_object(call_stack, class_name).unwrap())
+        .collect();
+
+    assert!(vm.forget_class("rjvm/ForgottenStatics"));
+    let new_class_objects: Vec<_> = class_names
+        .iter()
+        .map(|class_name| vm.get_class_object(call_stack, class_name).unwrap())
+        .collect();
+
+    assert!(old_class_objects[0] != new_class_objects[0]);
+    assert!(old_class_objects[1] != new_class_objects[1]);
+    assert!(old_class_objects[2] != new_class_objects[2]);
+    assert!(old_class_objects[3] == new_class_objects[3]);
+    assert_eq!(0, vm.execution_counters().garbage_collections);
+}
+
+#[test_log::test]
+fn redefine_class_cannot_change_fields() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack();
+    vm.get_or_resolve_class(call_stack, "rjvm/Redefinable")
+        .expect("should be able to load the class");
+
+    let result = vm.redefine_class(
+        "rjvm/Redefinable",
+        include_bytes!("../resources/redefinitions/field_added/rjvm/Redefinable.class"),
+    );
+    assert!(matches!(result, Err(VmError::ClassLoadingError(_))));
+}
+
+#[test_log::test]
+fn array_methods() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ArrayMethods",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(8, vm.printed.len());
+    assert_eq!("[I", extract_printed_string(&vm, 0));
+    assert_eq!("[Ljava.lang.String;", extract_printed_string(&vm, 1));
+    assert_eq!("java.lang.Object", extract_printed_string(&vm, 2));
+    assert_eq!(
+        vec![
+            Value::Int(3),
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(0)
+        ],
+        vm.printed[3..]
+    );
+}
+
+#[test_log::test]
+fn native_methods_calling_back_into_bytecode() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.native_methods_registry.register(
+        "rjvm/NativeCallback",
+        "callBack",
+        "(I)I",
+        |vm, call_stack, _, args| {
+            let value = expect_int_at(&args, 0)?;
+            let depth = call_stack.depth();
+            let method =
+                vm.resolve_class_method(call_stack, "rjvm/NativeCallback", "step", "(I)I")?;
+            let result = vm.invoke(call_stack, method, None, vec![Value::Int(value)]);
+            assert_eq!(depth, call_stack.depth());
+            result
+        },
+    );
+    let call_stack = vm.allocate_call_stack();
+    let main_method = vm
+        .resolve_class_method(
+            call_stack,
+            "rjvm/NativeCallback",
+            "main",
+            "([Ljava/lang/String;)V",
+        )
+        .expect("should find main method");
+    let main_result = vm.invoke(call_stack, main_method, None, vec![Value::Null]);
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(0, call_stack.depth());
+
+    // callBack(4) goes through step four times, summing 4 + 3 + 2 + 1
+    assert_eq!(
+        vec![Value::Int(1107), Value::Long(1 << 40), Value::Int(7)],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn instructions_executed_are_counted_while_a_frame_runs() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.native_methods_registry.register(
+        "rjvm/NativeCallback",
+        "callBack",
+        "(I)I",
+        |vm, call_stack, _, args| {
+            let value = expect_int_at(&args, 0)?;
+            let instructions_executed = vm.execution_counters().instructions_executed;
+            vm.printed.push(Value::Long(instructions_executed as i64));
+            let method =
+                vm.resolve_class_method(call_stack, "rjvm/NativeCallback", "step", "(I)I")?;
+            vm.invoke(call_stack, method, None, vec![Value::Int(value)])
+        },
+    );
+    let result = {
+        let call_stack = vm.allocate_call_stack();
+        let method = vm
+            .resolve_class_method(call_stack, "rjvm/NativeCallback", "step", "(I)I")
+            .expect("should find the method");
+        vm.invoke(call_stack, method, None, vec![Value::Int(2)])
+    };
+    assert_eq!(Ok(Some(Value::Int(3))), result);
+
+    // The counters are read by callBack(1), invoked by step(2), and then by callBack(0),
+    // invoked by step(1) while its own frame is still running
+    let [Value::Long(before), Value::Long(after)] = vm.printed[..] else {
+        panic!("should have recorded two counters, got {:?}", vm.printed);
+    };
+    assert!(after > before);
+}
+
+#[test_log::test]
+fn no_threshold_collections_while_a_native_method_runs() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.set_gc_threshold(Some(1024));
+    vm.native_methods_registry.register(
+        "rjvm/NativeCallback",
+        "callAllocate",
+        "()I",
+        |vm, call_stack, _, _| {
+            let collections_before = vm.execution_counters().garbage_collections;
+            let method =
+                vm.resolve_class_method(call_stack, "rjvm/NativeCallback", "allocate", "()I")?;
+            assert_eq!(
+                Ok(Some(Value::Int(100))),
+                vm.invoke(call_stack, method, None, vec![])
+            );
+            let collections = vm.execution_counters().garbage_collections - collections_before;
+            Ok(Some(Value::Int(collections as i32)))
+        },
+    );
+    let call_stack = vm.allocate_call_stack();
+    let method = vm
+        .resolve_class_method(call_stack, "rjvm/NativeCallback", "callAllocate", "()I")
+        .expect("should find the method");
+    assert_eq!(
+        Ok(Some(Value::Int(0))),
+        vm.invoke(call_stack, method, None, vec![])
+    );
+
+    // Once the native method has returned, the bytecode collects again
+    let method = vm
+        .resolve_class_method(call_stack, "rjvm/NativeCallback", "allocate", "()I")
+        .expect("should find the method");
+    assert_eq!(
+        Ok(Some(Value::Int(100))),
+        vm.invoke(call_stack, method, None, vec![])
+    );
+    assert!(vm.execution_counters().garbage_collections > 0);
+}
+
+#[test_log::test]
+fn methods_without_code() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.native_methods_registry
+        .register("rjvm/NoCodeMethods", "answer", "()I", |_, _, _, _| {
+            Ok(Some(Value::Int(42)))
+        });
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/NoCodeMethods",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(vec![Value::Int(42), Value::Int(4)], vm.printed);
+
+    let call_stack = vm.allocate_call_stack();
+    let abstract_method = vm
+        .resolve_class_method(call_stack, "rjvm/NoCodeMethods$Shape", "sides", "()I")
+        .expect("should find abstract method");
+    assert!(abstract_method.method.code.is_none());
+    let receiver = vm
+        .new_object(call_stack, "rjvm/NoCodeMethods$Square")
+        .expect("should be able to create object");
+    let result = vm.invoke(call_stack, abstract_method, Some(receiver), vec![]);
+    assert_throws(&vm, result, "java/lang/AbstractMethodError", "");
+}
+
+#[test_log::test]
+fn operand_stack_overflow() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+
+    // The method declares a max stack size of 2, but pushes three values
+    // iconst_1, iconst_2, iconst_3, ireturn
+    let class = class_with_code("overflow", "()I", 2, 0, vec![0x04, 0x05, 0x06, 0xac]);
+
+    let call_stack = vm.allocate_call_stack();
+    let class_and_method = ClassAndMethod {
+        class,
+        method: &class.methods[0],
+    };
+    let result = vm.invoke(call_stack, class_and_method, None, vec![]);
+    assert_eq!(
+        Err(MethodCallFailed::InternalError(VmError::VerifyError(
+            ValueStackError::MaximumCapacityReached,
+            ProgramCounter(2)
+        ))),
+        result
+    );
+}
+
+#[test_log::test]
+fn athrow_of_non_throwable() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+
+    // aload_0, athrow
+    let class = class_with_code(
+        "throwObject",
+        "(Ljava/lang/Object;)V",
+        1,
+        1,
+        vec![0x2a, 0xbf],
+    );
+
+    let call_stack = vm.allocate_call_stack();
+    let object = vm
+        .new_object(call_stack, "java/lang/Object")
+        .expect("should be able to create an object");
+    let class_and_method = ClassAndMethod {
+        class,
+        method: &class.methods[0],
+    };
+    let result = vm.invoke(
+        call_stack,
+        class_and_method,
+        None,
+        vec![Value::Object(object)],
+    );
+
+    assert_throws(
+        &vm,
+        result,
+        "java/lang/VerifyError",
+        "Can only throw instances of java.lang.Throwable, got java.lang.Object",
+    );
+}
+
+#[test_log::test]
+fn ireturn_narrows_to_the_declared_type() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+
+    // sipush 300, ireturn
+    let returns_300 = vec![0x11, 0x01, 0x2c, 0xac];
+    // sipush 300, sipush 300, imul, ireturn
+    let returns_90000 = vec![0x11, 0x01, 0x2c, 0x11, 0x01, 0x2c, 0x68, 0xac];
+    // iconst_m1, ireturn
+    let returns_minus_one = vec![0x02, 0xac];
+    let classes = [
+        ("()I", returns_300.clone(), Value::Int(300)),
+        ("()B", returns_300.clone(), Value::Int(44)),
+        ("()Z", returns_300, Value::Int(0)),
+        ("()Z", returns_minus_one.clone(), Value::Int(1)),
+        ("()C", returns_minus_one, Value::Int(65535)),
+        ("()S", returns_90000, Value::Int(24464)),
+    ]
+    .map(|(descriptor, code, expected)| {
+        (class_with_code("narrow", descriptor, 2, 0, code), expected)
+    });
+
+    let call_stack = vm.allocate_call_stack();
+    for (class, expected) in classes {
+        let method = &class.methods[0];
+        let result = vm.invoke(call_stack, ClassAndMethod { class, method }, None, vec![]);
+        assert_eq!(Ok(Some(expected)), result, "{}", method.type_descriptor);
+    }
+}
+
+#[test_log::test]
+fn wide_constants() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/WideConstants",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(4, vm.printed.len());
+    assert_eq!("wide string", extract_printed_string(&vm, 0));
+    assert_eq!(Value::Int(123456789), vm.printed[1]);
+    assert_eq!(Value::Float(1.5), vm.printed[2]);
+    assert_eq!("rjvm.WideConstants", extract_printed_string(&vm, 3));
+}
+
+#[test_log::test]
+fn unresolved_native_throws_unsatisfied_link_error_by_default() {
+    for behavior in [
+        None,
+        Some(UnresolvedNativeBehavior::ThrowUnsatisfiedLinkError),
+    ] {
+        let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+        if let Some(behavior) = behavior {
+            vm.set_unresolved_native_behavior(behavior);
+        }
+        let result = invoke(&mut vm, "rjvm/NoCodeMethods", "answer", "()I");
+        assert_throws(&vm, result, "java/lang/UnsatisfiedLinkError", "");
+    }
+}
+
+#[test_log::test]
+fn unresolved_native_can_return_an_error() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.set_unresolved_native_behavior(UnresolvedNativeBehavior::Error);
+    let result = invoke(&mut vm, "rjvm/NoCodeMethods", "answer", "()I");
+    assert_eq!(
+        Err(MethodCallFailed::InternalError(VmError::NotImplemented)),
+        result
+    );
+}
+
+#[test_log::test]
+fn unresolved_native_can_return_the_default_value() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.set_unresolved_native_behavior(UnresolvedNativeBehavior::ReturnDefaultValue);
+    let result = invoke(&mut vm, "rjvm/NoCodeMethods", "answer", "()I");
+    assert_eq!(Ok(Some(Value::Int(0))), result);
+}
+
+#[test_log::test]
+fn invocation_trace_recording() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    // Run once to initialize all the classes, since the static initializers of the
+    // jre classes depend on the current time and would make the trace non-deterministic
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/TraceRecording",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    vm.start_trace_recording();
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/TraceRecording",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    let trace = vm.stop_trace_recording();
+    assert_eq!(20, trace.events.len());
+    assert_eq!(
+        InvocationTraceEvent::Enter {
+            method: "rjvm.TraceRecording.square(I)I".to_string(),
+            arguments: vec!["3".to_string()],
+        },
+        trace.events[1]
+    );
+    assert_eq!(
+        InvocationTraceEvent::Exit {
+            method: "rjvm.TraceRecording.add(J)J".to_string(),
+            result: "10L".to_string(),
+        },
+        trace.events[8]
+    );
+    assert_eq!(
+        InvocationTraceEvent::Exit {
+            method: "rjvm.TraceRecording.main([Ljava/lang/String;)V".to_string(),
+            result: "void".to_string(),
+        },
+        trace.events[19]
+    );
+    assert_eq!(
+        include_str!("../resources/traces/TraceRecording.trace"),
+        trace.to_string()
+    );
+
+    let golden_trace =
+        InvocationTrace::parse(include_str!("../resources/traces/TraceRecording.trace"))
+            .expect("golden trace should be valid");
+    if let Some(difference) = trace.first_difference(&golden_trace) {
+        panic!("trace differs from the golden one: {difference}");
+    }
+}
+
+#[test_log::test]
+fn invocation_listener() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    // Initialize the classes first, so that only the methods of main are notified
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/TraceRecording",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let recorded_events = events.clone();
+    vm.set_invocation_listener(Some(Box::new(move |event| {
+        let recorded = match event {
+            InvocationEvent::Enter {
+                class_and_method, ..
+            } => (
+                true,
+                class_and_method.description(),
+                class_and_method.is_native(),
+                None,
+            ),
+            InvocationEvent::Exit {
+                class_and_method,
+                result,
+            } => (
+                false,
+                class_and_method.description(),
+                class_and_method.is_native(),
+                result.as_ref().ok().cloned().flatten(),
+            ),
+        };
+        recorded_events.borrow_mut().push(recorded);
+    })));
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/TraceRecording",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    vm.set_invocation_listener(None);
+
+    let events = events.borrow();
+    assert_eq!(20, events.len());
+    let mut entered = Vec::new();
+    for (is_enter, method, _, _) in events.iter() {
+        if *is_enter {
+            entered.push(method.clone());
+        } else {
+            assert_eq!(Some(method.clone()), entered.pop());
+        }
+    }
+    assert!(entered.is_empty());
+
+    let native_entries = events
+        .iter()
+        .filter(|(is_enter, _, is_native, _)| *is_enter && *is_native)
+        .count();
+    assert_eq!(3, native_entries);
+    assert!(events.contains(&(
+        false,
+        "rjvm.TraceRecording.square(I)I".to_string(),
+        false,
+        Some(Value::Int(9))
+    )));
+}
+
+#[test_log::test]
+fn invocation_listener_replaced_during_a_call_is_not_notified_of_its_exit() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.native_methods_registry.register(
+        "rjvm/NativeCallback",
+        "callAllocate",
+        "()I",
+        |vm, _, _, _| {
+            vm.set_invocation_listener(Some(Box::new(|event| {
+                assert!(
+                    !matches!(event, InvocationEvent::Exit { .. }),
+                    "should not be notified of the exit of a method entered before it was set"
+                );
+            })));
+            Ok(Some(Value::Int(1)))
+        },
+    );
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let recorded_events = events.clone();
+    vm.set_invocation_listener(Some(Box::new(move |event| {
+        let recorded = match event {
+            InvocationEvent::Enter {
+                class_and_method, ..
+            } => (true, class_and_method.description()),
+            InvocationEvent::Exit {
+                class_and_method, ..
+            } => (false, class_and_method.description()),
+        };
+        recorded_events.borrow_mut().push(recorded);
+    })));
+    let result = invoke(&mut vm, "rjvm/NativeCallback", "callAllocate", "()I");
+    assert_eq!(Ok(Some(Value::Int(1))), result);
+
+    // The events before are the ones of the initialization of the class
+    let events = events.borrow();
+    let method = "rjvm.NativeCallback.callAllocate()I".to_string();
+    assert_eq!(
+        vec![(true, method.clone()), (false, method)],
+        events[events.len() - 2..]
+    );
+}
+
+#[test_log::test]
+fn invokestatic_of_instance_method() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/StaticMismatch", "invokeStatic", "()I");
+    assert_throws_exactly(&vm, result, "java/lang/IncompatibleClassChangeError", "");
+}
+
+#[test_log::test]
+fn invokevirtual_of_static_method() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/StaticMismatch", "invokeVirtual", "()I");
+    assert_throws_exactly(&vm, result, "java/lang/IncompatibleClassChangeError", "");
+}
+
+#[test_log::test]
+fn receiver_is_passed_in_local_zero() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack();
+    let receiver = vm
+        .new_object(call_stack, "rjvm/ThisArgument")
+        .expect("should be able to create object");
+    let constructor = vm
+        .resolve_class_method(call_stack, "rjvm/ThisArgument", "<init>", "(I)V")
+        .expect("should find constructor");
+    let result = vm.invoke(
+        call_stack,
+        constructor,
+        Some(receiver.clone()),
+        vec![Value::Int(1000)],
+    );
+    assert_eq!(Ok(None), result);
+
+    // Arguments start at local 1, and the long takes two slots
+    let method = vm
+        .resolve_class_method(call_stack, "rjvm/ThisArgument", "offset", "(IJI)I")
+        .expect("should find method");
+    let args = vec![
+        Value::Int(200),
+        Value::Long(30),
+        Value::Uninitialized,
+        Value::Int(4),
+    ];
+    let result = vm.invoke(call_stack, method.clone(), Some(receiver), args.clone());
+    assert_eq!(Ok(Some(Value::Int(1234))), result);
+
+    let result = vm.invoke(call_stack, method, None, args);
+    assert_throws(&vm, result, "java/lang/NullPointerException", "");
+}
+
+#[test_log::test]
+fn enum_value_of() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/EnumValueOf",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(3, vm.printed.len());
+    assert_eq!(Value::Int(1), vm.printed[0]);
+    assert_eq!(
+        "No enum constant rjvm.EnumValueOf.Color.PURPLE",
+        extract_printed_string(&vm, 1)
+    );
+    assert_eq!(
+        "No enum constant rjvm.EnumValueOf.Color.BLUE",
+        extract_printed_string(&vm, 2)
+    );
+}
+
+#[test_log::test]
+fn long_comparisons() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/LongComparisons",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![1, 1, 1, 1, 1, 1, 0, 1, 0, 0]
+            .into_iter()
+            .map(Value::Int)
+            .collect::<Vec<_>>(),
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn intern_pool_seeding() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack();
+    vm.seed_intern_pool(call_stack, &["hello", "world"])
+        .expect("should be able to seed the intern pool");
+    let seeded = vm
+        .intern_string(call_stack, "hello")
+        .expect("should find the seeded string");
+    assert_eq!(1, vm.intern_pool_stats().hits);
+
+    let result = invoke(
+        &mut vm,
+        "rjvm/InternedStrings",
+        "greeting",
+        "()Ljava/lang/String;",
+    );
+    let Ok(Some(Value::Object(loaded))) = result else {
+        panic!("should have returned a string, got {result:?}");
+    };
+    assert_eq!(seeded, loaded);
+    let stats = vm.intern_pool_stats();
+    assert_eq!((2, 2, 0), (stats.size, stats.hits, stats.misses));
+
+    vm.clear_intern_pool();
+    assert_eq!(0, vm.intern_pool_stats().size);
+    let result = invoke(
+        &mut vm,
+        "rjvm/InternedStrings",
+        "greeting",
+        "()Ljava/lang/String;",
+    );
+    let Ok(Some(Value::Object(reloaded))) = result else {
+        panic!("should have returned a string, got {result:?}");
+    };
+    assert_ne!(seeded, reloaded);
+    let stats = vm.intern_pool_stats();
+    assert_eq!((1, 0, 1), (stats.size, stats.hits, stats.misses));
+}
+
+#[test_log::test]
+fn identical_literals_are_the_same_object() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/InternedStrings",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(
+        vec![
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(0),
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(1)
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn interned_string_contents() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack();
+    let expected = vm
+        .intern_string(call_stack, "a\0\u{1F600}")
+        .expect("should be able to intern a string");
+
+    let result = invoke(
+        &mut vm,
+        "rjvm/InternedStrings",
+        "unusualCharacters",
+        "()Ljava/lang/String;",
+    );
+    let Ok(Some(Value::Object(loaded))) = result else {
+        panic!("should have returned a string, got {result:?}");
+    };
+    assert_eq!(expected, loaded);
+
+    let value = vm
+        .get_instance_field(&loaded, "value")
+        .expect("should have a value field");
+    let Value::Object(chars) = value else {
+        panic!("value should be an array, got {value:?}");
+    };
+    assert_eq!(4, chars.len());
+    assert_eq!(Ok(Value::Int(0xD83D)), chars.get_element(2));
+    assert_eq!(
+        "a\0\u{1F600}",
+        extract_str_from_java_lang_string(&vm, &loaded).expect("should be a valid string")
+    );
+}
+
+#[test_log::test]
+fn static_initialization_on_first_field_access() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/LazyStaticInitialization",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            Value::Int(1),
+            Value::Int(2),
+            Value::Int(3),
+            Value::Int(4),
+            Value::Int(4),
+            Value::Int(6),
+            Value::Int(5),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn interface_string_constants() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/InterfaceConstants",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(4, vm.printed.len());
+    assert_eq!("hello", extract_printed_string(&vm, 0));
+    assert_eq!(Value::Int(1), vm.printed[1]);
+    assert_eq!(Value::Int(42), vm.printed[2]);
+    assert_eq!(Value::Int(1), vm.printed[3]);
+}
+
+#[test_log::test]
+fn static_field_resolution_matches_static_fields_with_the_same_type() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/StaticFieldResolution",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(vec![Value::Int(7), Value::Int(7)], vm.printed);
+}
+
+#[test_log::test]
+fn lazy_verification_ignores_missing_classes_on_untaken_branches() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/LazyVerification", "takenBranch", "()I");
+    assert_eq!(Ok(Some(Value::Int(1))), result);
+
+    let result = invoke(&mut vm, "rjvm/LazyVerification", "untakenBranch", "()I");
+    assert_throws(&vm, result, "java/lang/NoClassDefFoundError", "");
+}
+
+#[test_log::test]
+fn eager_verification_fails_on_missing_classes() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.set_lazy_verification(false);
+    let call_stack = vm.allocate_call_stack();
+    // Resolving the method initializes the class, which is what fails
+    let result = vm.resolve_class_method(call_stack, "rjvm/LazyVerification", "takenBranch", "()I");
+    assert_throws(&vm, result, "java/lang/NoClassDefFoundError", "");
+}
+
+#[test_log::test]
+fn initialize_all_loaded_classes() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let class_names = [
+        "rjvm/LazyStaticInitialization$Counter",
+        "rjvm/LazyStaticInitialization$Holder",
+        "rjvm/StaticFields",
+    ];
+    let classes = class_names.map(|class_name| {
+        vm.get_or_load_class(class_name)
+            .expect("should be able to load the class")
+    });
+    assert!(classes
+        .iter()
+        .all(|class| !vm.is_class_initialized(class.id)));
+
+    let call_stack = vm.allocate_call_stack();
+    vm.initialize_all_loaded(call_stack)
+        .expect("should be able to initialize all classes");
+    assert!(classes
+        .iter()
+        .all(|class| vm.is_class_initialized(class.id)));
+}
+
+#[test_log::test]
+fn initialize_all_loaded_classes_reports_failures() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let failing = vm
+        .get_or_load_class("rjvm/ClassInitializationFailure$Failing")
+        .expect("should be able to load the class");
+
+    let call_stack = vm.allocate_call_stack();
+    let result = vm.initialize_all_loaded(call_stack);
+    assert_throws(&vm, result, "java/lang/ExceptionInInitializerError", "");
+    assert_eq!(ClassInitState::Erroneous, failing.init_state());
+}
+
+#[test_log::test]
+fn class_init_state() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let holder = vm
+        .get_or_load_class("rjvm/LazyStaticInitialization$Holder")
+        .expect("should be able to load the class");
+    assert_eq!(ClassInitState::Unprepared, holder.init_state());
+    assert!(!vm.is_class_initialized(holder.id));
+
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/LazyStaticInitialization",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(ClassInitState::Initialized, holder.init_state());
+    assert!(vm.is_class_initialized(holder.id));
+}
+
+#[test_log::test]
+fn superinterface_initialization() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/SuperinterfaceInitialization",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(
+        vec![
+            Value::Int(2),
+            Value::Int(3),
+            Value::Int(4),
+            Value::Int(1),
+            Value::Int(1),
+        ],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn null_array_store() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/NullArrayStore",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+
+    assert_eq!(3, vm.printed.len());
+    assert_eq!("a", extract_printed_string(&vm, 0));
+    assert_eq!(Value::Int(1), vm.printed[1]);
+    assert_eq!(Value::Int(1), vm.printed[2]);
+}
+
+#[test_log::test]
+fn execution_counters() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    // The first invocation loads and initializes the classes, which allocates objects
+    invoke(&mut vm, "rjvm/AllocationCounting", "allocateObjects", "()I")
+        .expect("should be able to warm up");
+
+    vm.reset_execution_counters();
+    let result = invoke(
+        &mut vm,
+        "rjvm/AllocationCounting",
+        "sumWithoutAllocations",
+        "()I",
+    );
+    assert_eq!(Ok(Some(Value::Int(4950))), result);
+    let counters = vm.execution_counters();
+    assert_eq!(0, counters.objects_allocated);
+    assert_eq!(0, counters.garbage_collections);
+    assert_eq!(0, counters.native_calls);
+    assert!(counters.instructions_executed > 100);
+
+    vm.reset_execution_counters();
+    let result = invoke(&mut vm, "rjvm/AllocationCounting", "allocateObjects", "()I");
+    assert_eq!(Ok(Some(Value::Int(10))), result);
+    assert_eq!(20, vm.execution_counters().objects_allocated);
+}
+
+#[test_log::test]
+fn instruction_stats() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    invoke(&mut vm, "rjvm/Profiling", "sumOf10", "()I").expect("should be able to warm up");
+    assert!(vm.instruction_stats().is_empty());
+
+    let mut count_instructions = |method_name| {
+        vm.enable_profiling(true);
+        let result = invoke(&mut vm, "rjvm/Profiling", method_name, "()I");
+        assert!(result.is_ok());
+        let stats = vm.instruction_stats();
+        vm.enable_profiling(false);
+        assert_eq!(
+            Some(&3),
+            stats.get(&format!("rjvm.Profiling.{method_name}()I"))
+        );
+        *stats
+            .get("rjvm.Profiling.sum(I)I")
+            .expect("should have profiled the loop")
+    };
+    let with_10_iterations = count_instructions("sumOf10");
+    let with_20_iterations = count_instructions("sumOf20");
+    let with_30_iterations = count_instructions("sumOf30");
+
+    let per_10_iterations = with_20_iterations - with_10_iterations;
+    assert!(per_10_iterations >= 10 * 5);
+    assert_eq!(per_10_iterations, with_30_iterations - with_20_iterations);
+}
+
+/// Returns the program counters of the instructions of the given method that allocate an
+/// object or an array, in order
+fn allocation_pcs(vm: &mut Vm, class_name: &str, method_name: &str) -> Vec<u16> {
+    let call_stack = vm.allocate_call_stack();
+    let class_and_method = vm
+        .resolve_class_method(call_stack, class_name, method_name, "()V")
+        .expect("should find the method");
+    let code = &class_and_method
+        .method
+        .code
+        .as_ref()
+        .expect("should have code")
+        .code;
+    let mut pcs = Vec::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let (instruction, next_pc) =
+            Instruction::parse(code, pc).expect("should be able to decode the instruction");
+        if matches!(
+            instruction,
+            Instruction::New(_) | Instruction::Newarray(_) | Instruction::Anewarray(_)
+        ) {
+            pcs.push(pc as u16);
+        }
+        pc = next_pc;
+    }
+    pcs
+}
+
+#[test_log::test]
+fn live_objects_by_allocation_site() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.set_leak_diagnosis(true);
+    let result = invoke(&mut vm, "rjvm/AllocationSites", "allocate", "()V");
+    assert_eq!(Ok(None), result);
+
+    let live_objects = vm
+        .live_objects_by_allocation_site()
+        .expect("should be able to collect the garbage");
+    let mut sites_of_allocate = live_objects
+        .iter()
+        .filter(|(site, _)| site.method == "rjvm.AllocationSites.allocate()V")
+        .map(|(site, live_objects)| (site.pc, live_objects.objects))
+        .collect::<Vec<_>>();
+    sites_of_allocate.sort();
+    let [first_loop, second_loop, _garbage_loop] =
+        allocation_pcs(&mut vm, "rjvm/AllocationSites", "allocate")[..]
+    else {
+        panic!("allocate should have three allocating instructions");
+    };
+    assert_eq!(vec![(first_loop, 6), (second_loop, 4)], sites_of_allocate);
+    let retained_array_site = AllocationSite {
+        method: "rjvm.AllocationSites.<clinit>()V".to_string(),
+        pc: allocation_pcs(&mut vm, "rjvm/AllocationSites", "<clinit>")[0],
+    };
+    assert_eq!(
+        Some(1),
+        live_objects.get(&retained_array_site).map(|l| l.objects)
+    );
+
+    vm.set_leak_diagnosis(false);
+    assert!(vm
+        .live_objects_by_allocation_site()
+        .expect("should be able to collect the garbage")
+        .is_empty());
+}
+
+#[test_log::test]
+fn allocation_site_is_restored_when_an_invoked_method_returns() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    vm.native_methods_registry.register(
+        "rjvm/NativeCallback",
+        "callAllocate",
+        "()I",
+        |vm, call_stack, _, _| {
+            let method =
+                vm.resolve_class_method(call_stack, "rjvm/NativeCallback", "allocate", "()I")?;
+            vm.invoke(call_stack, method, None, vec![])?;
+            // Class objects are gc roots, so this one stays alive
+            vm.get_class_object(call_stack, "rjvm/NativeCallback")?;
+            Ok(Some(Value::Int(0)))
+        },
+    );
+    vm.set_leak_diagnosis(true);
+    let result = invoke(&mut vm, "rjvm/NativeCallback", "callAllocate", "()I");
+    assert_eq!(Ok(Some(Value::Int(0))), result);
+
+    // The class object has been allocated by the native method, invoked by no instruction
+    let live_objects = vm
+        .live_objects_by_allocation_site()
+        .expect("should be able to collect the garbage");
+    assert!(
+        live_objects
+            .keys()
+            .all(|site| site.method != "rjvm.NativeCallback.allocate()I"),
+        "no live object was allocated by allocate(), got {live_objects:?}"
+    );
+}
+
+#[test_log::test]
+fn new_initializes_the_class_once() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/NewInstances",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(vec![Value::Int(0), Value::Int(1)], vm.printed);
+}
+
+#[test_log::test]
+fn new_of_abstract_class() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/NewInstances", "newAbstract", "()V");
+    assert_throws(&vm, result, "java/lang/InstantiationError", "");
+    // The class must not have been initialized
+    assert!(vm.printed.is_empty());
+}
+
+#[test_log::test]
+fn new_of_interface() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(&mut vm, "rjvm/NewInstances", "newInterface", "()V");
+    assert_throws(&vm, result, "java/lang/InstantiationError", "");
+}
+
+#[test_log::test]
+fn constructor_throwing_midway() {
+    let mut vm = create_base_vm(10_000_000);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/ThrowingConstructors",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(
+        vec![Value::Int(1), Value::Int(2), Value::Int(20), Value::Int(1)],
+        vm.printed
+    );
+    assert!(vm.execution_counters().garbage_collections > 0);
+}
+
+#[test_log::test]
+fn default_methods_maximally_specific() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/DefaultMethods",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(
+        vec![Value::Int(1), Value::Int(3), Value::Int(3), Value::Int(3)],
+        vm.printed
+    );
+}
+
+#[test_log::test]
+fn synchronized_method_throwing() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(
+        &mut vm,
+        "rjvm/SynchronizedMethods",
+        "main",
+        "([Ljava/lang/String;)V",
+    );
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(2, vm.printed.len());
+    assert_eq!("failed", extract_printed_string(&vm, 0));
+    assert_eq!(Value::Int(3), vm.printed[1]);
+}
+
+#[test_log::test]
+fn method_references_are_resolved_once() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    // Initializing the classes invokes some methods, which we do not want to count
+    let call_stack = vm.allocate_call_stack();
+    vm.get_or_resolve_class(call_stack, "rjvm/MethodResolutionCache")
+        .expect("should be able to load the class");
+    vm.reset_execution_counters();
+    let result = invoke(&mut vm, "rjvm/MethodResolutionCache", "sumOfSquares", "()I");
+    assert_eq!(Ok(Some(Value::Int(328350))), result);
+    assert_eq!(1, vm.execution_counters().method_references_resolved);
+
+    vm.reset_execution_counters();
+    let result = invoke(&mut vm, "rjvm/MethodResolutionCache", "sumOfSquares", "()I");
+    assert_eq!(Ok(Some(Value::Int(328350))), result);
+    assert_eq!(0, vm.execution_counters().method_references_resolved);
+}
+
+#[test_log::test]
+fn static_field_references_are_resolved_once() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack();
+    vm.get_or_resolve_class(call_stack, "rjvm/MethodResolutionCache")
+        .expect("should be able to load the class");
+    vm.reset_execution_counters();
+    let result = invoke(&mut vm, "rjvm/MethodResolutionCache", "countTo100", "()I");
+    assert_eq!(Ok(Some(Value::Int(100))), result);
+    assert_eq!(1, vm.execution_counters().static_field_references_resolved);
+
+    vm.reset_execution_counters();
+    let result = invoke(&mut vm, "rjvm/MethodResolutionCache", "countTo100", "()I");
+    assert_eq!(Ok(Some(Value::Int(200))), result);
+    assert_eq!(0, vm.execution_counters().static_field_references_resolved);
+
+    // The cached field must not outlive the class declaring it
+    assert!(vm.forget_class("rjvm/MethodResolutionCache"));
+    let result = invoke(&mut vm, "rjvm/MethodResolutionCache", "countTo100", "()I");
+    assert_eq!(Ok(Some(Value::Int(100))), result);
+    assert_eq!(1, vm.execution_counters().static_field_references_resolved);
+}
+
+#[test_log::test]
+fn redefining_a_class_invalidates_the_resolved_method_references() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let result = invoke(
+        &mut vm,
+        "rjvm/MethodResolutionCache",
+        "redefinableValue",
+        "()I",
+    );
+    assert_eq!(Ok(Some(Value::Int(11))), result);
+
+    vm.redefine_class(
+        "rjvm/Redefinable",
+        include_bytes!("../resources/redefinitions/body_changed/rjvm/Redefinable.class"),
+    )
+    .expect("should be able to redefine the class");
+
+    let result = invoke(
+        &mut vm,
+        "rjvm/MethodResolutionCache",
+        "redefinableValue",
+        "()I",
+    );
+    assert_eq!(Ok(Some(Value::Int(20))), result);
+}
+
+#[test_log::test]
+fn string_format() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let main_result = invoke(&mut vm, "rjvm/Printf", "main", "([Ljava/lang/String;)V");
+    assert_eq!(Ok(None), main_result);
+    assert_eq!(3, vm.printed.len());
+    assert_eq!("cart has 007 items", extract_printed_string(&vm, 0));
+    assert_eq!("d != java.lang.String", extract_printed_string(&vm, 1));
+    assert_eq!("null arguments", extract_printed_string(&vm, 2));
+}
+
+#[test_log::test]
+fn string_format_while_collecting() {
+    let mut vm = create_base_vm(2 * ONE_MEGABYTE);
+    let call_stack = vm.allocate_call_stack();
+    let stream = vm
+        .new_object(call_stack, "java/io/PrintStream")
+        .expect("should be able to create a stream");
+    let format_while_collecting = vm
+        .resolve_class_method(
+            call_stack,
+            "rjvm/Printf",
+            "formatWhileCollecting",
+            "(Ljava/io/PrintStream;)I",
+        )
+        .expect("should find the method");
+    let result = vm.invoke(
+        call_stack,
+        format_while_collecting,
+        None,
+        vec![Value::Object(stream)],
+    );
+    assert_eq!(Ok(Some(Value::Int(0))), result);
+    assert!(vm.execution_counters().garbage_collections > 0);
+}
+
+#[test_log::test]
+fn print_stream_printf() {
+    let mut vm = create_base_vm(DEFAULT_MAX_MEMORY);
+    let call_stack = vm.allocate_call_stack();
+    let stream = vm
+        .new_object(call_stack, "java/io/PrintStream")
+        .expect("should be able to create a stream");
+    let print_to = vm
+        .resolve_class_method(
+            call_stack,
+            "rjvm/Printf",
+            "printTo",
+            "(Ljava/io/PrintStream;)Ljava/io/PrintStream;",
+        )
+        .expect("should find the method");
+    let result = vm.invoke(
+        call_stack,
+        print_to,
+        None,
+        vec![Value::Object(stream.clone())],
+    );
+    assert_eq!(Ok(Some(Value::Object(stream))), result);
+
+    assert_eq!(2, vm.printed.len());
+    assert_eq!("42-x\n", extract_printed_string(&vm, 0));
+    assert_eq!("  2.3|z", extract_printed_string(&vm, 1));
+}
@@ -1,2 +1,18 @@
 #!/usr/bin/env sh
-javac -source 6 -target 6 rjvm/*.java
+javac -source 6 -target 6 $(ls rjvm/*.java | grep -v -e InterfaceConstants -e StaticFieldResolution \
+  -e ReturnSubtype -e 'StaticMismatch\.' -e SuperinterfaceInitialization -e 'NewInstances\.' \
+  -e DefaultMethods -e Switches)
+# These classes are compiled against the different versions, found in compile_time, of the
+# classes they use
+javac -source 6 -target 6 -implicit:none -cp compile_time -sourcepath compile_time:. \
+  rjvm/InterfaceConstants.java rjvm/StaticFieldResolution.java rjvm/ReturnSubtype.java \
+  rjvm/StaticMismatch.java rjvm/NewInstances.java
+# Switching on strings requires java 7
+javac -source 7 -target 7 rjvm/Switches.java
+# Default methods require java 8
+javac --release 8 rjvm/SuperinterfaceInitialization.java rjvm/DefaultMethods.java
+# The redefined versions of Redefinable, which the tests load in place of the original one
+javac -source 6 -target 6 -d redefinitions/body_changed redefinitions/body_changed/rjvm/Redefinable.java
+javac -source 6 -target 6 -d redefinitions/field_added redefinitions/field_added/rjvm/Redefinable.java
+# LazyVerification tests that a missing class is only resolved when the code using it runs
+rm 'rjvm/LazyVerification$Missing.class'
@@ -0,0 +1,8 @@
+package rjvm;
+
+// The version of ConstantHolder that InterfaceConstants is compiled against: its fields are
+// not compile-time constants, so that javac reads them with getstatic instead of inlining them
+public interface ConstantHolder {
+    String GREETING = String.valueOf("hello");
+    int ANSWER = Integer.valueOf(42);
+}
@@ -0,0 +1,5 @@
+package rjvm;
+
+// The version of IncompatibleReturnValue that ReturnSubtype is compiled against
+public class IncompatibleReturnValue extends ReturnSubtype.Base {
+}
@@ -0,0 +1,5 @@
+package rjvm;
+
+// The version of InstanceFieldShadow that StaticFieldResolution is compiled against
+public class InstanceFieldShadow extends StaticFieldBase {
+}
@@ -0,0 +1,5 @@
+package rjvm;
+
+// The version of NewInstancesAbstract that NewInstances is compiled against
+public class NewInstancesAbstract {
+}
@@ -0,0 +1,5 @@
+package rjvm;
+
+// The version of NewInstancesInterface that NewInstances is compiled against
+public class NewInstancesInterface {
+}
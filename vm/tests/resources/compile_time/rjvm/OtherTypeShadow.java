@@ -0,0 +1,5 @@
+package rjvm;
+
+// The version of OtherTypeShadow that StaticFieldResolution is compiled against
+public class OtherTypeShadow extends StaticFieldBase {
+}
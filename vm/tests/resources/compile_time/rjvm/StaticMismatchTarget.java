@@ -0,0 +1,12 @@
+package rjvm;
+
+// The version of StaticMismatchTarget that StaticMismatch is compiled against
+public class StaticMismatchTarget {
+    static int value() {
+        return 1;
+    }
+
+    int other() {
+        return 2;
+    }
+}
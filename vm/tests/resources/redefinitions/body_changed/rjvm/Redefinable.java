@@ -0,0 +1,17 @@
+package rjvm;
+
+public class Redefinable {
+    private final int base;
+
+    public Redefinable(int base) {
+        this.base = base;
+    }
+
+    public static Redefinable create() {
+        return new Redefinable(10);
+    }
+
+    public int value() {
+        return base * 2;
+    }
+}
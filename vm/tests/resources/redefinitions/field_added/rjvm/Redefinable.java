@@ -0,0 +1,18 @@
+package rjvm;
+
+public class Redefinable {
+    private final int base;
+    private int anotherField;
+
+    public Redefinable(int base) {
+        this.base = base;
+    }
+
+    public static Redefinable create() {
+        return new Redefinable(10);
+    }
+
+    public int value() {
+        return base + 1;
+    }
+}
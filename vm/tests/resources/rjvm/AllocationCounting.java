@@ -0,0 +1,19 @@
+package rjvm;
+
+public class AllocationCounting {
+    public static int sumWithoutAllocations() {
+        int sum = 0;
+        for (int i = 0; i < 100; ++i) {
+            sum += i;
+        }
+        return sum;
+    }
+
+    public static int allocateObjects() {
+        int sum = 0;
+        for (int i = 0; i < 10; ++i) {
+            sum += new Object[] {new Object()}.length;
+        }
+        return sum;
+    }
+}
@@ -0,0 +1,17 @@
+package rjvm;
+
+public class AllocationSites {
+    private static final Object[] retained = new Object[10];
+
+    public static void allocate() {
+        for (int i = 0; i < 6; ++i) {
+            retained[i] = new Object();
+        }
+        for (int i = 6; i < 10; ++i) {
+            retained[i] = new int[i];
+        }
+        for (int i = 0; i < 100; ++i) {
+            Object garbage = new Object();
+        }
+    }
+}
@@ -0,0 +1,38 @@
+package rjvm;
+
+public class ArrayBounds {
+    public static void main(String[] args) {
+        int[] ints = new int[3];
+        try {
+            ints[3] = 1;
+        } catch (ArrayIndexOutOfBoundsException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            tempPrint(ints[-1]);
+        } catch (ArrayIndexOutOfBoundsException e) {
+            tempPrint(e.getMessage());
+        }
+        String[] strings = new String[1];
+        try {
+            strings[1] = "a";
+        } catch (ArrayIndexOutOfBoundsException e) {
+            tempPrint(e.getMessage());
+        }
+        long[] longs = new long[0];
+        try {
+            tempPrint(longs[0]);
+        } catch (ArrayIndexOutOfBoundsException e) {
+            tempPrint(e.getMessage());
+        }
+    }
+
+    public static void storePastTheEnd() {
+        int[] ints = new int[3];
+        ints[3] = 1;
+    }
+
+    private static native void tempPrint(int value);
+    private static native void tempPrint(long value);
+    private static native void tempPrint(String value);
+}
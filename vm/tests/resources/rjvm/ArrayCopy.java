@@ -0,0 +1,108 @@
+package rjvm;
+
+public class ArrayCopy {
+    public static void main(String[] args) {
+        int[] forward = new int[]{1, 2, 3, 4, 5};
+        System.arraycopy(forward, 0, forward, 1, 4);
+        printInts(forward);
+
+        int[] backward = new int[]{1, 2, 3, 4, 5};
+        System.arraycopy(backward, 1, backward, 0, 4);
+        printInts(backward);
+
+        String[] strings = new String[]{"x", "y"};
+        Object[] objects = new Object[3];
+        System.arraycopy(strings, 0, objects, 1, 2);
+        tempPrint(objects[0] == null && objects[1] == strings[0] && objects[2] == strings[1]);
+
+        Object[] mixed = new Object[]{"a", new Object(), "c"};
+        String[] target = new String[3];
+        try {
+            System.arraycopy(mixed, 0, target, 0, 3);
+            tempPrint(false);
+        } catch (ArrayStoreException e) {
+            tempPrint(target[0] == mixed[0] && target[1] == null && target[2] == null);
+        }
+
+        try {
+            System.arraycopy(new int[2], 0, new long[2], 0, 2);
+            tempPrint(false);
+        } catch (ArrayStoreException e) {
+            tempPrint(true);
+        }
+
+        try {
+            System.arraycopy(new int[2], 0, new Object[2], 0, 2);
+            tempPrint(false);
+        } catch (ArrayStoreException e) {
+            tempPrint(true);
+        }
+
+        try {
+            System.arraycopy(null, 0, new int[2], 0, 2);
+            tempPrint(false);
+        } catch (NullPointerException e) {
+            tempPrint(true);
+        }
+
+        int[] source = new int[]{1, 2, 3};
+        int[] destination = new int[]{0, 0, 0};
+        try {
+            System.arraycopy(source, 2, destination, 0, 2);
+            tempPrint(false);
+        } catch (IndexOutOfBoundsException e) {
+            tempPrint(destination[0] == 0);
+        }
+
+        try {
+            System.arraycopy(source, 0, destination, 0, -1);
+            tempPrint(false);
+        } catch (IndexOutOfBoundsException e) {
+            tempPrint(true);
+        }
+    }
+
+    public static void arraysOfArrays() {
+        int[][] matrix = new int[][]{{1}, {2}, {3}};
+        Object[] objects = new Object[3];
+        System.arraycopy(matrix, 0, objects, 0, 3);
+        tempPrint(objects[2] == matrix[2]);
+
+        int[][] target = new int[3][];
+        System.arraycopy(objects, 1, target, 0, 2);
+        tempPrint(target[0] == matrix[1] && target[1] == matrix[2] && target[2] == null);
+
+        Object[] notArrays = new Object[]{new int[0], "not an array"};
+        try {
+            System.arraycopy(notArrays, 0, target, 0, 2);
+            tempPrint(false);
+        } catch (ArrayStoreException e) {
+            tempPrint(target[0] == notArrays[0]);
+        }
+
+        try {
+            System.arraycopy("not an array", 0, target, 0, 1);
+            tempPrint(false);
+        } catch (ArrayStoreException e) {
+            tempPrint(true);
+        }
+    }
+
+    public static void nullDestinationOfAnotherType() {
+        System.arraycopy(new Object(), 0, null, 0, 0);
+    }
+
+    public static void outOfBounds() {
+        System.arraycopy(new int[3], 2, new int[3], 0, 2);
+    }
+
+    private static void printInts(int[] array) {
+        for (int value : array) {
+            tempPrint(value);
+        }
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(boolean value);
+}
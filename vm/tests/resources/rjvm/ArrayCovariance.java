@@ -0,0 +1,36 @@
+package rjvm;
+
+public class ArrayCovariance {
+    private static Object[] objects;
+    private static CharSequence[] sequences;
+    private static Object object;
+    private static Cloneable cloneable;
+    private Object[] instanceField;
+
+    public static void main(String[] args) {
+        objects = new String[] {"a"};
+        tempPrint(objects.length);
+
+        sequences = new String[2];
+        tempPrint(sequences.length);
+
+        ArrayCovariance instance = new ArrayCovariance();
+        instance.instanceField = new ArrayCovariance[3];
+        tempPrint(instance.instanceField.length);
+
+        tempPrint(identity(new Integer[4]).length);
+
+        object = new int[5];
+        tempPrint(((int[]) object).length);
+
+        cloneable = new long[6];
+        tempPrint(cloneable != null);
+    }
+
+    private static Object[] identity(Object[] array) {
+        return array;
+    }
+
+    private static native void tempPrint(int value);
+    private static native void tempPrint(boolean value);
+}
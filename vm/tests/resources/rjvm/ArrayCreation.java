@@ -0,0 +1,39 @@
+package rjvm;
+
+public class ArrayCreation {
+    public static void main(String[] args) {
+        int[] ints = new int[2];
+        tempPrint(ints.length + ints[0] + ints[1]);
+        String[] strings = new String[3];
+        tempPrint(strings.length);
+        tempPrint(strings[2] == null);
+        tempPrint(count("a", "b", "c", "d"));
+
+        try {
+            tempPrint(new int[length(-1)].length);
+        } catch (NegativeArraySizeException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            tempPrint(new String[length(-2)].length);
+        } catch (NegativeArraySizeException e) {
+            tempPrint(e.getMessage());
+        }
+    }
+
+    public static int negativeLength() {
+        return new long[length(-3)].length;
+    }
+
+    private static int count(String... values) {
+        return values.length;
+    }
+
+    private static int length(int value) {
+        return value;
+    }
+
+    private static native void tempPrint(int value);
+    private static native void tempPrint(boolean value);
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,24 @@
+package rjvm;
+
+public class ArrayMethods {
+    public static void main(String[] args) {
+        int[] ints = new int[]{1, 2, 3};
+        tempPrint(ints.getClass().getName());
+        tempPrint(new String[0].getClass().getName());
+        tempPrint(new Object().getClass().getName());
+
+        int[] copy = ints.clone();
+        tempPrint(copy[2]);
+        tempPrint(copy != ints);
+
+        tempPrint(ints.hashCode() == System.identityHashCode(ints));
+        tempPrint(ints.equals(ints));
+        tempPrint(ints.equals(copy));
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(boolean value);
+
+    private static native void tempPrint(String value);
+}
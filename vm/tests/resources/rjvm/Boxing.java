@@ -0,0 +1,63 @@
+package rjvm;
+
+public class Boxing {
+    public static void main(String[] args) {
+        Integer small = 100;
+        Integer sameSmall = Integer.valueOf(100);
+        tempPrint(small == sameSmall);
+
+        Integer large = 1000;
+        Integer sameLarge = Integer.valueOf(1000);
+        tempPrint(large != sameLarge);
+        tempPrint(large.equals(sameLarge));
+        tempPrint(large.intValue() + sameLarge);
+
+        Long boxedLong = 1L << 40;
+        tempPrint(boxedLong.longValue());
+        tempPrint(Long.valueOf(7L) == Long.valueOf(7L));
+
+        Double boxedDouble = 2.5;
+        tempPrint(boxedDouble * 2);
+
+        Boolean boxedBoolean = true;
+        tempPrint(boxedBoolean == Boolean.TRUE);
+        tempPrint(!Boolean.valueOf(false));
+
+        Character boxedChar = 'x';
+        tempPrint(boxedChar.charValue() == 'x');
+
+        Float boxedFloat = 1.5f;
+        tempPrint(boxedFloat.floatValue() + boxedFloat.intValue());
+        tempPrint(Integer.valueOf(1000).longValue());
+
+        tempPrint(Integer.parseInt("-123"));
+        tempPrint(Long.parseLong("9000000000"));
+        try {
+            Integer.parseInt("12x");
+        } catch (NumberFormatException e) {
+            tempPrint(e.getMessage());
+        }
+
+        java.util.List<Integer> list = new java.util.ArrayList<>();
+        for (int i = 0; i < 5; ++i) {
+            list.add(i * i);
+        }
+        int total = 0;
+        for (int value : list) {
+            total += value;
+        }
+        tempPrint(total);
+    }
+
+    private static native void tempPrint(float value);
+
+    private static native void tempPrint(String value);
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(long value);
+
+    private static native void tempPrint(double value);
+
+    private static native void tempPrint(boolean value);
+}
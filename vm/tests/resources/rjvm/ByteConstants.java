@@ -0,0 +1,11 @@
+package rjvm;
+
+public class ByteConstants {
+    public static void main(String[] args) {
+        tempPrint(-10);
+        tempPrint(-128);
+        tempPrint(127);
+    }
+
+    private static native void tempPrint(int value);
+}
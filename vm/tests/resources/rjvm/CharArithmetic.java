@@ -0,0 +1,31 @@
+package rjvm;
+
+public class CharArithmetic {
+    private static char field = Character.MAX_VALUE;
+
+    public static void main(String[] args) {
+        char c = 'a';
+        c += 1;
+        tempPrint(c);
+
+        // Arithmetic is done on ints, and the store narrows the result back to a char
+        char max = Character.MAX_VALUE;
+        int promoted = max + 1;
+        tempPrint(promoted);
+        max++;
+        tempPrint(max);
+
+        char[] chars = new char[]{Character.MAX_VALUE};
+        chars[0] += 2;
+        tempPrint(chars[0]);
+
+        field++;
+        tempPrint(field);
+
+        char below = 0;
+        below--;
+        tempPrint(below);
+    }
+
+    private static native void tempPrint(int value);
+}
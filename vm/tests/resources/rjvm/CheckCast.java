@@ -7,6 +7,18 @@ public class CheckCast {
 
     public static void main(String[] args) {
         checkCast(new C2());
+        checkCastToSameType(new C2());
+        checkCastToSuperType(new C2());
+        checkCastNull(null);
+        checkCastFailure(new C1());
+        checkCastToInterfaceFailure(new C2());
+        checkCastToInterfaceFailure(new String[0]);
+        checkCastToInterfaceFailure(new int[0]);
+    }
+
+    public static void uncaughtCastFailure() {
+        Object o = new C1();
+        tempPrint(((C2) o) != null);
     }
 
     private static void checkCast(C1 c) {
@@ -17,5 +29,37 @@ public class CheckCast {
         tempPrint(c != null);
     }
 
+    private static void checkCastToSameType(Object o) {
+        C2 c = (C2) o;
+        tempPrint(c == o);
+    }
+
+    private static void checkCastToSuperType(Object o) {
+        C1 c = (C1) o;
+        tempPrint(c == o);
+    }
+
+    private static void checkCastNull(Object o) {
+        C2 c = (C2) o;
+        tempPrint(c == null);
+    }
+
+    private static void checkCastFailure(Object o) {
+        try {
+            tempPrint(((C2) o) != null);
+        } catch (ClassCastException e) {
+            tempPrint(e.getMessage());
+        }
+    }
+
+    private static void checkCastToInterfaceFailure(Object o) {
+        try {
+            tempPrint(((Runnable) o) != null);
+        } catch (ClassCastException e) {
+            tempPrint(e.getMessage());
+        }
+    }
+
     private static native void tempPrint(boolean value);
+    private static native void tempPrint(String value);
 }
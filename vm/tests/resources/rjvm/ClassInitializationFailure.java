@@ -0,0 +1,37 @@
+package rjvm;
+
+public class ClassInitializationFailure {
+    public static void main(String[] args) {
+        try {
+            Failing.getValue();
+        } catch (ExceptionInInitializerError e) {
+            tempPrint(1);
+            tempPrint(e.getCause() instanceof IllegalStateException);
+        }
+
+        try {
+            Failing.getValue();
+        } catch (NoClassDefFoundError e) {
+            tempPrint(2);
+            tempPrint(e.getMessage());
+        }
+    }
+
+    private static class Failing {
+        private static int value = computeValue();
+
+        private static int computeValue() {
+            throw new IllegalStateException("cannot initialize");
+        }
+
+        public static int getValue() {
+            return value;
+        }
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(boolean value);
+
+    private static native void tempPrint(String value);
+}
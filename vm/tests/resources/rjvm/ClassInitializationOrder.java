@@ -0,0 +1,18 @@
+package rjvm;
+
+public class ClassInitializationOrder {
+    public static void main(String[] args) {
+        tempPrint(Child.valueSeenByChild);
+        tempPrint(Parent.value);
+    }
+
+    private static class Parent {
+        static int value = 7;
+    }
+
+    private static class Child extends Parent {
+        static int valueSeenByChild = Parent.value * 10;
+    }
+
+    private static native void tempPrint(int value);
+}
@@ -0,0 +1,25 @@
+package rjvm;
+
+public class ClassObjects {
+    public static void main(String[] args) {
+        tempPrint("a".getClass() == "b".getClass());
+        tempPrint("a".getClass() == String.class);
+        tempPrint(new Object().getClass() != String.class);
+        tempPrint(new int[1].getClass() == int[].class);
+        Object longs = new long[1];
+        tempPrint(new int[1].getClass() != longs.getClass());
+        tempPrint(int[].class.getComponentType() == int.class);
+        tempPrint(new ClassObjects().getClass() == ClassObjects.class);
+
+        Class<?> before = new ClassObjects().getClass();
+        for (int i = 0; i < 100; ++i) {
+            Object garbage = new int[16];
+        }
+        tempPrint(new ClassObjects().getClass() == before);
+        tempPrint(before.getName());
+    }
+
+    private static native void tempPrint(boolean value);
+
+    private static native void tempPrint(String value);
+}
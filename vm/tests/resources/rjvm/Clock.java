@@ -0,0 +1,10 @@
+package rjvm;
+
+public class Clock {
+    public static void main(String[] args) {
+        tempPrint(System.currentTimeMillis());
+        tempPrint(System.nanoTime());
+    }
+
+    private static native void tempPrint(long value);
+}
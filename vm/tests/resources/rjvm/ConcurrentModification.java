@@ -0,0 +1,53 @@
+package rjvm;
+
+import java.util.ArrayList;
+import java.util.ConcurrentModificationException;
+import java.util.Iterator;
+import java.util.List;
+
+public class ConcurrentModification {
+    public static void main(String[] args) {
+        List<String> list = new ArrayList<>();
+        list.add("a");
+        list.add("b");
+        list.add("c");
+        try {
+            for (String value : list) {
+                if (value.equals("a")) {
+                    list.add("d");
+                }
+            }
+        } catch (ConcurrentModificationException e) {
+            tempPrint(1);
+        }
+
+        try {
+            for (String value : list) {
+                list.remove(value);
+            }
+        } catch (ConcurrentModificationException e) {
+            tempPrint(2);
+        }
+
+        // Modifications through the iterator itself are allowed
+        Iterator<String> iterator = list.iterator();
+        while (iterator.hasNext()) {
+            if (!iterator.next().equals("b")) {
+                iterator.remove();
+            }
+        }
+        tempPrint(list.size());
+    }
+
+    public static void uncaught() {
+        List<String> list = new ArrayList<>();
+        list.add("a");
+        list.add("b");
+        list.add("c");
+        for (String value : list) {
+            list.remove(value);
+        }
+    }
+
+    private static native void tempPrint(int value);
+}
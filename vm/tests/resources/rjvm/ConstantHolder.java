@@ -0,0 +1,6 @@
+package rjvm;
+
+public interface ConstantHolder {
+    String GREETING = "hello";
+    int ANSWER = 42;
+}
@@ -0,0 +1,23 @@
+package rjvm;
+
+public class CyclicToString {
+    private final String name;
+    private CyclicToString next;
+
+    private CyclicToString(String name) {
+        this.name = name;
+    }
+
+    public static CyclicToString createCycle() {
+        CyclicToString first = new CyclicToString("first");
+        CyclicToString second = new CyclicToString("second");
+        first.next = second;
+        second.next = first;
+        return first;
+    }
+
+    @Override
+    public String toString() {
+        return name + " -> " + next;
+    }
+}
@@ -0,0 +1,45 @@
+package rjvm;
+
+public class DefaultMethods {
+    interface Grandparent {
+        default int value() {
+            return 1;
+        }
+    }
+
+    interface Parent extends Grandparent {
+    }
+
+    interface Child extends Parent {
+        default int value() {
+            return 3;
+        }
+    }
+
+    static class InheritsFromGrandparent implements Parent {
+    }
+
+    // Grandparent is listed explicitly, but Child's implementation is more specific
+    static class InheritsFromChild implements Grandparent, Child {
+    }
+
+    static class Base implements Grandparent {
+    }
+
+    static class Derived extends Base implements Child {
+    }
+
+    public static void main(String[] args) {
+        Parent parent = new InheritsFromGrandparent();
+        tempPrint(parent.value());
+
+        Grandparent grandparent = new InheritsFromChild();
+        tempPrint(grandparent.value());
+        tempPrint(new InheritsFromChild().value());
+
+        Base base = new Derived();
+        tempPrint(base.value());
+    }
+
+    private static native void tempPrint(int value);
+}
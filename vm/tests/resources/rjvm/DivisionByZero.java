@@ -0,0 +1,71 @@
+package rjvm;
+
+public class DivisionByZero {
+    public static void main(String[] args) {
+        try {
+            tempPrint(intDivision(5, 0));
+        } catch (ArithmeticException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            tempPrint(intRemainder(5, 0));
+        } catch (ArithmeticException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            tempPrint(longDivision(5L, 0L));
+        } catch (ArithmeticException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            tempPrint(longRemainder(5L, 0L));
+        } catch (ArithmeticException e) {
+            tempPrint(e.getMessage());
+        }
+
+        tempPrint(intDivision(Integer.MIN_VALUE, -1));
+        tempPrint(longRemainder(Long.MIN_VALUE, -1L));
+
+        tempPrint(floatDivision(5.0f, 0.0f));
+        tempPrint(floatDivision(0.0f, 0.0f));
+        tempPrint(doubleDivision(-5.0, 0.0));
+    }
+
+    public static int uncaught() {
+        return intDivision(5, 0);
+    }
+
+    private static int intDivision(int a, int b) {
+        return a / b;
+    }
+
+    private static int intRemainder(int a, int b) {
+        return a % b;
+    }
+
+    private static long longDivision(long a, long b) {
+        return a / b;
+    }
+
+    private static long longRemainder(long a, long b) {
+        return a % b;
+    }
+
+    private static float floatDivision(float a, float b) {
+        return a / b;
+    }
+
+    private static double doubleDivision(double a, double b) {
+        return a / b;
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(long value);
+
+    private static native void tempPrint(float value);
+
+    private static native void tempPrint(double value);
+
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,26 @@
+package rjvm;
+
+public class EnumValueOf {
+    enum Color {
+        RED,
+        GREEN
+    }
+
+    public static void main(String[] args) {
+        tempPrint(Color.valueOf("GREEN").ordinal());
+        try {
+            Color.valueOf("PURPLE");
+        } catch (IllegalArgumentException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            Enum.valueOf(Color.class, "BLUE");
+        } catch (IllegalArgumentException e) {
+            tempPrint(e.getMessage());
+        }
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,48 @@
+package rjvm;
+
+public class ExceptionPropagation {
+    static class Outer extends RuntimeException {}
+    static class Inner extends Outer {}
+
+    public static void main(String[] args) {
+        // Caught two frames above the one throwing it
+        try {
+            first();
+        } catch (Inner e) {
+            tempPrint(1);
+        }
+
+        // Nested, i.e. overlapping, ranges: the innermost matching handler must be chosen
+        tempPrint(nested(new Inner()));
+        tempPrint(nested(new Outer()));
+        tempPrint(nested(new IllegalStateException()));
+    }
+
+    public static void uncaught() {
+        first();
+    }
+
+    private static void first() {
+        second();
+    }
+
+    private static void second() {
+        throw new Inner();
+    }
+
+    private static int nested(RuntimeException toThrow) {
+        try {
+            try {
+                throw toThrow;
+            } catch (Inner e) {
+                return 2;
+            }
+        } catch (Outer e) {
+            return 3;
+        } catch (RuntimeException e) {
+            return 4;
+        }
+    }
+
+    private static native void tempPrint(int value);
+}
@@ -0,0 +1,19 @@
+package rjvm;
+
+public class FieldAccess {
+    public static Derived create() {
+        return new Derived();
+    }
+
+    static class Base {
+        int x = 1;
+    }
+
+    static class Derived extends Base {
+        long y = 2;
+
+        long sum() {
+            return x + y;
+        }
+    }
+}
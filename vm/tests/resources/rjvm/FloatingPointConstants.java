@@ -0,0 +1,15 @@
+package rjvm;
+
+public class FloatingPointConstants {
+    public static void main(String[] args) {
+        tempPrint(Float.floatToRawIntBits(-0.0f));
+        tempPrint(Float.floatToRawIntBits(Float.MIN_VALUE));
+        tempPrint(Double.doubleToRawLongBits(-0.0));
+        tempPrint(Double.doubleToRawLongBits(Double.MIN_VALUE));
+        tempPrint(Double.doubleToRawLongBits(2.2250738585072E-308));
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(long value);
+}
@@ -0,0 +1,33 @@
+package rjvm;
+
+public class FloatingPointRemainder {
+    public static void main(String[] args) {
+        doubleRemainder(-5.0, 3.0);
+        doubleRemainder(5.0, -3.0);
+        doubleRemainder(5.0, Double.POSITIVE_INFINITY);
+        doubleRemainder(Double.NEGATIVE_INFINITY, 3.0);
+        doubleRemainder(Double.NaN, 3.0);
+        doubleRemainder(5.0, Double.NaN);
+        doubleRemainder(5.0, 0.0);
+
+        floatRemainder(-5.0f, 3.0f);
+        floatRemainder(5.0f, -3.0f);
+        floatRemainder(5.0f, Float.POSITIVE_INFINITY);
+        floatRemainder(Float.NEGATIVE_INFINITY, 3.0f);
+        floatRemainder(Float.NaN, 3.0f);
+        floatRemainder(5.0f, Float.NaN);
+        floatRemainder(5.0f, 0.0f);
+    }
+
+    private static void doubleRemainder(double a, double b) {
+        tempPrint(a % b);
+    }
+
+    private static void floatRemainder(float a, float b) {
+        tempPrint(a % b);
+    }
+
+    private static native void tempPrint(float value);
+
+    private static native void tempPrint(double value);
+}
@@ -0,0 +1,12 @@
+package rjvm;
+
+public class ForgottenStatics {
+    // Three megabytes: two copies do not fit in the semi-space of the test vm
+    private static long[] data = new long[3 * 1024 * 1024 / 8];
+
+    public static void touch() {
+        tempPrint(data.length);
+    }
+
+    private static native void tempPrint(int value);
+}
@@ -0,0 +1,8 @@
+package rjvm;
+
+public final class Host {
+    private Host() {
+    }
+
+    public static native int sum(int a, int b);
+}
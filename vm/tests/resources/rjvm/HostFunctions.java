@@ -0,0 +1,10 @@
+package rjvm;
+
+public class HostFunctions {
+    public static void main(String[] args) {
+        tempPrint(Host.sum(3, 4));
+        tempPrint(Host.sum(-10, 2));
+    }
+
+    private static native void tempPrint(int value);
+}
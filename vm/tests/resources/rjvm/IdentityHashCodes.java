@@ -0,0 +1,37 @@
+package rjvm;
+
+public class IdentityHashCodes {
+    public static void main(String[] args) {
+        Object first = new Object();
+        Object second = new Object();
+        tempPrint(first.hashCode() != second.hashCode());
+
+        int hashBeforeGc = first.hashCode();
+        for (int i = 0; i < 100; ++i) {
+            Object garbage = new int[16];
+        }
+        tempPrint(first.hashCode() == hashBeforeGc);
+
+        Object third = new Object();
+        tempPrint(third.hashCode() != first.hashCode() && third.hashCode() != second.hashCode());
+
+        NotOverriding notOverriding = new NotOverriding();
+        tempPrint(notOverriding.hashCode() == System.identityHashCode(notOverriding));
+
+        Overriding overriding = new Overriding();
+        tempPrint(overriding.hashCode() == 42);
+        tempPrint(System.identityHashCode(overriding) != 42);
+    }
+
+    private static class NotOverriding {
+    }
+
+    private static class Overriding {
+        @Override
+        public int hashCode() {
+            return 42;
+        }
+    }
+
+    private static native void tempPrint(boolean value);
+}
@@ -0,0 +1,6 @@
+package rjvm;
+
+// ReturnSubtype is compiled against a version of this class that extends ReturnSubtype.Base,
+// to be able to test returning a value of an incompatible type
+public class IncompatibleReturnValue {
+}
@@ -0,0 +1,26 @@
+package rjvm;
+
+public class Increments {
+    public static void main(String[] args) {
+        int i = 10;
+        i--;
+        tempPrint(i);
+        i -= 100;
+        tempPrint(i);
+
+        // Increments that do not fit in a byte are encoded as "wide iinc"
+        i += 1000;
+        tempPrint(i);
+        i -= 30000;
+        tempPrint(i);
+
+        int max = Integer.MAX_VALUE;
+        max++;
+        tempPrint(max);
+        int almostMax = Integer.MAX_VALUE - 10;
+        almostMax += 1000;
+        tempPrint(almostMax);
+    }
+
+    private static native void tempPrint(int value);
+}
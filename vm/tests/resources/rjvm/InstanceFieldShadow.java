@@ -0,0 +1,5 @@
+package rjvm;
+
+public class InstanceFieldShadow extends StaticFieldBase {
+    public int count = 3;
+}
@@ -19,6 +19,23 @@ public class InstanceOf {
         checkInstanceOfInterfaces(new C3());
         checkInstanceOfInterfaces(new C4());
         checkInstanceOfInterfaces(new C5());
+
+        checkNull(null);
+    }
+
+    // instanceof is false for null whatever the type, while checkcast lets it through
+    private static void checkNull(Object v) {
+        tempPrint(v instanceof Object);
+        tempPrint(v instanceof C1);
+        tempPrint(v instanceof Intf1);
+        tempPrint(v instanceof int[]);
+        tempPrint(v instanceof Object[][]);
+
+        C1 c1 = (C1) v;
+        Intf1 intf1 = (Intf1) v;
+        int[] ints = (int[]) v;
+        Object[][] arrays = (Object[][]) v;
+        tempPrint(c1 == null && intf1 == null && ints == null && arrays == null);
     }
 
     private static void checkInstanceOfInterfaces(Object v) {
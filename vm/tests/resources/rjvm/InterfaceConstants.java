@@ -0,0 +1,22 @@
+package rjvm;
+
+// This class is compiled against the version of ConstantHolder in compile_time, whose fields
+// are not compile-time constants, so that javac does not inline them and uses getstatic instead
+public class InterfaceConstants {
+    public static void main(String[] args) {
+        tempPrint(Implementation.GREETING);
+        tempPrint(Implementation.GREETING == "hello");
+        tempPrint(Implementation.ANSWER);
+        tempPrint(ConstantHolder.GREETING == Implementation.GREETING);
+    }
+
+    static class Base {
+    }
+
+    static class Implementation extends Base implements ConstantHolder {
+    }
+
+    private static native void tempPrint(String value);
+    private static native void tempPrint(int value);
+    private static native void tempPrint(boolean value);
+}
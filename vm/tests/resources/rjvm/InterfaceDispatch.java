@@ -0,0 +1,39 @@
+package rjvm;
+
+public class InterfaceDispatch {
+    interface Shape {
+        int sides();
+    }
+
+    // The method is declared only by the superinterface
+    interface Quadrilateral extends Shape {
+    }
+
+    static class Base {
+        public int sides() {
+            return 4;
+        }
+    }
+
+    // The implementation is inherited from a superclass that does not implement the interface
+    static class Square extends Base implements Quadrilateral {
+    }
+
+    static class Triangle implements Shape {
+        @Override
+        public int sides() {
+            return 3;
+        }
+    }
+
+    public static void main(String[] args) {
+        Quadrilateral quadrilateral = new Square();
+        tempPrint(quadrilateral.sides());
+        Shape[] shapes = new Shape[]{new Triangle(), new Square()};
+        for (Shape shape : shapes) {
+            tempPrint(shape.sides());
+        }
+    }
+
+    private static native void tempPrint(int value);
+}
@@ -0,0 +1,39 @@
+package rjvm;
+
+public class InternedStrings {
+    public static String greeting() {
+        return "hello";
+    }
+
+    public static void main(String[] args) {
+        String literal = "hello";
+        tempPrint(literal == greeting());
+        tempPrint(literal == Other.greeting());
+
+        String built = new String(new char[] {'h', 'e', 'l', 'l', 'o'});
+        tempPrint(built == literal);
+        tempPrint(built.intern() == literal);
+        tempPrint(new String("other").intern() == Other.other());
+        // The first string interned with some content becomes the interned one
+        String unique = new String(new char[] {'u', 'n', 'i', 'q', 'u', 'e'});
+        tempPrint(unique.intern() == unique);
+    }
+
+    // Both characters are encoded specially in the class file: the null as two bytes,
+    // and the one outside the basic multilingual plane as a surrogate pair
+    public static String unusualCharacters() {
+        return "a\u0000\uD83D\uDE00";
+    }
+
+    private static native void tempPrint(boolean value);
+
+    private static class Other {
+        static String greeting() {
+            return "hello";
+        }
+
+        static String other() {
+            return "other";
+        }
+    }
+}
@@ -0,0 +1,23 @@
+package rjvm;
+
+public class LargeArrays {
+    public static void main(String[] args) {
+        tempPrint(new int[1000].length);
+        try {
+            int[] array = new int[1001];
+            tempPrint(array.length);
+        } catch (OutOfMemoryError e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            Object[] array = new Object[5000];
+            tempPrint(array.length);
+        } catch (OutOfMemoryError e) {
+            tempPrint(e.getMessage());
+        }
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(String value);
+}
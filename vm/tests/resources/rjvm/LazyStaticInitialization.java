@@ -0,0 +1,39 @@
+package rjvm;
+
+public class LazyStaticInitialization {
+    static class Holder {
+        static int value;
+
+        static {
+            tempPrint(3);
+            value = 4;
+        }
+    }
+
+    static class Counter {
+        static int count;
+
+        static {
+            tempPrint(6);
+        }
+    }
+
+    public static void main(String[] args) {
+        tempPrint(1);
+        // Neither creating an array nor a type check initialize the class
+        Holder[] holders = new Holder[1];
+        Object object = holders;
+        if (object instanceof Holder) {
+            tempPrint(-1);
+        }
+        tempPrint(2);
+
+        tempPrint(Holder.value);
+        tempPrint(Holder.value);
+
+        Counter.count = 5;
+        tempPrint(Counter.count);
+    }
+
+    static native void tempPrint(int value);
+}
@@ -0,0 +1,23 @@
+package rjvm;
+
+// The class file of Missing has been deleted after compiling this class
+public class LazyVerification {
+    static class Missing {
+        int value = 2;
+    }
+
+    public static int takenBranch() {
+        return choose(false);
+    }
+
+    public static int untakenBranch() {
+        return choose(true);
+    }
+
+    private static int choose(boolean useMissing) {
+        if (useMissing) {
+            return new Missing().value;
+        }
+        return 1;
+    }
+}
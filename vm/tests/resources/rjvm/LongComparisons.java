@@ -0,0 +1,23 @@
+package rjvm;
+
+public class LongComparisons {
+    public static void main(String[] args) {
+        long min = Long.MIN_VALUE;
+        long max = Long.MAX_VALUE;
+        long minusOne = -1L;
+
+        // Naively computing a - b would overflow for all of these
+        tempPrint(min < max);
+        tempPrint(max > min);
+        tempPrint(min < 1L);
+        tempPrint(max > minusOne);
+        tempPrint(min <= min);
+        tempPrint(max >= max);
+        tempPrint(min == max);
+        tempPrint(min != max);
+        tempPrint(max < min);
+        tempPrint(1L < min);
+    }
+
+    private static native void tempPrint(boolean value);
+}
@@ -0,0 +1,43 @@
+package rjvm;
+
+public class MathFunctions {
+    public static void main(String[] args) {
+        tempPrint(Math.sqrt(2.0));
+        tempPrint(Math.sqrt(-1.0));
+        tempPrint(Math.sin(1.0));
+        tempPrint(Math.cos(1.0));
+        tempPrint(Math.pow(2.0, 10.0));
+        tempPrint(Math.pow(1.0, Double.NaN));
+        tempPrint(Math.floor(-2.5));
+        tempPrint(Math.ceil(-2.5));
+        tempPrint(Math.abs(-2.5));
+        tempPrint(Math.abs(-2.5f));
+        tempPrint(Math.abs(-7));
+        tempPrint(Math.abs(Integer.MIN_VALUE));
+        tempPrint(Math.abs(-7L));
+        tempPrint(Math.exp(1.0));
+        tempPrint(Math.log(10.0));
+        tempPrint(Math.atan2(1.0, 2.0));
+    }
+
+    public static void minAndMax() {
+        tempPrint(Math.min(3, -4));
+        tempPrint(Math.max(3, -4));
+        tempPrint(Math.min(3L, 1L << 40));
+        tempPrint(Math.max(3L, 1L << 40));
+        tempPrint(Math.min(1.5f, -2.5f));
+        tempPrint(Math.max(1.5f, Float.NaN));
+        tempPrint(Math.min(0.0, -0.0));
+        tempPrint(Math.max(-0.0, 0.0));
+        tempPrint(Math.max(1.5, 2.5));
+        tempPrint(Math.log(Math.E));
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(long value);
+
+    private static native void tempPrint(float value);
+
+    private static native void tempPrint(double value);
+}
@@ -0,0 +1,28 @@
+package rjvm;
+
+public class MethodResolutionCache {
+    private static int counter;
+
+    private static int square(int value) {
+        return value * value;
+    }
+
+    public static int sumOfSquares() {
+        int sum = 0;
+        for (int i = 0; i < 100; ++i) {
+            sum += square(i);
+        }
+        return sum;
+    }
+
+    public static int countTo100() {
+        for (int i = 0; i < 100; ++i) {
+            counter += 1;
+        }
+        return counter;
+    }
+
+    public static int redefinableValue() {
+        return Redefinable.create().value();
+    }
+}
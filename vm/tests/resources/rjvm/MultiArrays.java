@@ -0,0 +1,109 @@
+package rjvm;
+
+public class MultiArrays {
+    public static void main(String[] args) {
+        int[][] matrix = new int[2][3];
+        tempPrint(matrix.length);
+        tempPrint(matrix[1].length);
+        for (int i = 0; i < 2; ++i) {
+            for (int j = 0; j < 3; ++j) {
+                matrix[i][j] = i * 10 + j;
+            }
+        }
+        tempPrint(sum(matrix));
+
+        long[][][] cube = new long[2][2][2];
+        cube[1][1][1] = 42L;
+        tempPrint(cube[1][1][1]);
+        tempPrint(cube[0][1].length);
+
+        String[][] partial = new String[2][];
+        tempPrint(partial[0] == null);
+        partial[1] = new String[]{"hello"};
+        tempPrint(partial[1][0].length());
+
+        Object[] rows = matrix;
+        tempPrint(rows instanceof int[][]);
+
+        for (int i = 0; i < 100; ++i) {
+            int[][] garbage = new int[10][10];
+            garbage[9][9] = i;
+        }
+        tempPrint(matrix[1][2]);
+    }
+
+    public static void negativeDimension() {
+        try {
+            int[][] matrix = new int[2][-1];
+            tempPrint(matrix.length);
+        } catch (NegativeArraySizeException e) {
+            tempPrint(e.getMessage());
+        }
+    }
+
+    public static void shallowClone() {
+        int[][] matrix = new int[][]{{1, 2}, {3, 4}};
+        int[][] clone = matrix.clone();
+        tempPrint(clone != matrix);
+        tempPrint(clone[0] == matrix[0] && clone[1] == matrix[1]);
+
+        clone[1][0] = 30;
+        tempPrint(matrix[1][0]);
+        clone[0] = new int[]{10, 20};
+        tempPrint(matrix[0][0]);
+
+        Object copy = matrix.clone();
+        tempPrint(copy instanceof int[][]);
+    }
+
+    public static void nestedTypes() {
+        Object strings = new String[1][];
+        tempPrint(strings instanceof String[][]);
+        tempPrint(strings instanceof Object[][]);
+        tempPrint(strings instanceof Integer[][]);
+        tempPrint(strings instanceof int[][]);
+
+        Object cube = new int[1][1][1];
+        tempPrint(cube instanceof int[][][]);
+        tempPrint(cube instanceof long[][][]);
+        tempPrint(cube instanceof Object[][]);
+        tempPrint(cube instanceof Object[][][]);
+        tempPrint(cube.getClass().getName());
+
+        try {
+            int[][] matrix = (int[][]) strings;
+            tempPrint(matrix.length);
+        } catch (ClassCastException e) {
+            tempPrint(e.getMessage());
+        }
+
+        Object[][] rows = new String[1][];
+        try {
+            rows[0] = new Integer[0];
+        } catch (ArrayStoreException e) {
+            tempPrint(e.getMessage());
+        }
+
+        Object reflected = java.lang.reflect.Array.newInstance(String[].class, 1);
+        tempPrint(reflected instanceof String[][]);
+        tempPrint(reflected instanceof Integer[][]);
+    }
+
+    private static int sum(int[][] matrix) {
+        int sum = 0;
+        for (int[] row : matrix) {
+            for (int value : row) {
+                sum += value;
+            }
+        }
+        return sum;
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(long value);
+
+    private static native void tempPrint(boolean value);
+
+    private static native void tempPrint(String value);
+}
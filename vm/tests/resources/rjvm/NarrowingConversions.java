@@ -0,0 +1,19 @@
+package rjvm;
+
+public class NarrowingConversions {
+    public static int d2i(double value) {
+        return (int) value;
+    }
+
+    public static long d2l(double value) {
+        return (long) value;
+    }
+
+    public static int f2i(float value) {
+        return (int) value;
+    }
+
+    public static long f2l(float value) {
+        return (long) value;
+    }
+}
@@ -0,0 +1,37 @@
+package rjvm;
+
+public class NativeCallback {
+    public static void main(String[] args) {
+        int local = 7;
+        long wide = 1L << 40;
+        // 1000 stays on the operand stack while the native method executes
+        int result = 1000 + callBack(4) * 10 + local;
+        tempPrint(result);
+        tempPrint(wide);
+        tempPrint(local);
+    }
+
+    private static int step(int value) {
+        if (value == 0) {
+            return 0;
+        }
+        int previous = callBack(value - 1);
+        return value + previous;
+    }
+
+    public static int allocate() {
+        int[] array = null;
+        for (int i = 0; i < 100; ++i) {
+            array = new int[100];
+        }
+        return array.length;
+    }
+
+    private static native int callBack(int value);
+
+    public static native int callAllocate();
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(long value);
+}
@@ -0,0 +1,25 @@
+package rjvm;
+
+public class NewInstances {
+    static class Counted {
+        static {
+            tempPrint(0);
+        }
+    }
+
+    public static void main(String[] args) {
+        new Counted();
+        new Counted();
+        tempPrint(1);
+    }
+
+    public static void newAbstract() {
+        new NewInstancesAbstract();
+    }
+
+    public static void newInterface() {
+        new NewInstancesInterface();
+    }
+
+    static native void tempPrint(int value);
+}
@@ -0,0 +1,11 @@
+package rjvm;
+
+// NewInstances is compiled against the version of this class in compile_time, which is not
+// abstract, so that it can try to instantiate it
+public abstract class NewInstancesAbstract {
+    static {
+        tempPrint(-1);
+    }
+
+    private static native void tempPrint(int value);
+}
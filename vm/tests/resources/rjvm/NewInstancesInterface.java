@@ -0,0 +1,6 @@
+package rjvm;
+
+// NewInstances is compiled against the version of this type in compile_time, which is a class,
+// so that it can try to instantiate it
+public interface NewInstancesInterface {
+}
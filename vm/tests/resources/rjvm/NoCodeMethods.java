@@ -0,0 +1,24 @@
+package rjvm;
+
+public class NoCodeMethods {
+    abstract static class Shape {
+        abstract int sides();
+    }
+
+    static class Square extends Shape {
+        int sides() {
+            return 4;
+        }
+    }
+
+    static native int answer();
+
+    public static void main(String[] args) {
+        tempPrint(answer());
+
+        Shape shape = new Square();
+        tempPrint(shape.sides());
+    }
+
+    private static native void tempPrint(int value);
+}
@@ -0,0 +1,18 @@
+package rjvm;
+
+public class NullArrayStore {
+    public static void main(String[] args) {
+        String[] strings = new String[] {"a", "b"};
+        strings[1] = null;
+        tempPrint(strings[0]);
+        tempPrint(strings[1] == null);
+
+        Object[] objects = new Object[] {new Object()};
+        objects[0] = null;
+        tempPrint(objects[0] == null);
+    }
+
+    private static native void tempPrint(String value);
+
+    private static native void tempPrint(boolean value);
+}
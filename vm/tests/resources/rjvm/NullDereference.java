@@ -0,0 +1,110 @@
+package rjvm;
+
+public class NullDereference {
+    private int value;
+
+    public static void main(String[] args) {
+        NullDereference nothing = null;
+        try {
+            tempPrint(nothing.value);
+        } catch (NullPointerException e) {
+            tempPrint(1);
+        }
+        try {
+            nothing.value = 2;
+        } catch (NullPointerException e) {
+            tempPrint(2);
+        }
+        try {
+            tempPrint(nothing.getValue());
+        } catch (NullPointerException e) {
+            tempPrint(3);
+        }
+        int[] array = null;
+        try {
+            tempPrint(array.length);
+        } catch (NullPointerException e) {
+            tempPrint(4);
+        }
+        try {
+            throw null;
+        } catch (NullPointerException e) {
+            tempPrint(5);
+        }
+        try {
+            tempPrint(array[0]);
+        } catch (NullPointerException e) {
+            tempPrint(6);
+        }
+        try {
+            array[0] = 1;
+        } catch (NullPointerException e) {
+            tempPrint(7);
+        }
+        Runnable runnable = null;
+        try {
+            runnable.run();
+        } catch (NullPointerException e) {
+            tempPrint(8);
+        }
+        try {
+            synchronized (nothing) {
+                tempPrint(-1);
+            }
+        } catch (NullPointerException e) {
+            tempPrint(9);
+        }
+    }
+
+    public static int uncaughtGetField() {
+        NullDereference nothing = null;
+        return nothing.value;
+    }
+
+    public static void uncaughtPutField() {
+        NullDereference nothing = null;
+        nothing.value = 1;
+    }
+
+    public static int uncaughtInvokeVirtual() {
+        NullDereference nothing = null;
+        return nothing.getValue();
+    }
+
+    public static int uncaughtArrayLength() {
+        int[] array = null;
+        return array.length;
+    }
+
+    public static int uncaughtArrayLoad() {
+        int[] array = null;
+        return array[0];
+    }
+
+    public static void uncaughtArrayStore() {
+        Object[] array = null;
+        array[0] = "value";
+    }
+
+    public static void uncaughtInvokeInterface() {
+        Runnable runnable = null;
+        runnable.run();
+    }
+
+    public static void uncaughtMonitorEnter() {
+        Object lock = null;
+        synchronized (lock) {
+            tempPrint(-1);
+        }
+    }
+
+    public static void uncaughtThrowNull() {
+        throw null;
+    }
+
+    public int getValue() {
+        return value;
+    }
+
+    private static native void tempPrint(int value);
+}
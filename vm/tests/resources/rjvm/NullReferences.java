@@ -0,0 +1,24 @@
+package rjvm;
+
+public class NullReferences {
+    private static Object staticField;
+    private Object field;
+    private int[] array;
+
+    public static void main(String[] args) {
+        NullReferences instance = new NullReferences();
+        tempPrint(instance.field == null);
+        tempPrint(instance.array == null);
+        tempPrint(staticField == null);
+        tempPrint((new String[1])[0] == null);
+
+        Object first = null;
+        Object second = null;
+        tempPrint(first == second);
+        tempPrint(first != instance);
+        tempPrint(instance != null);
+        tempPrint(instance == instance);
+    }
+
+    private static native void tempPrint(boolean value);
+}
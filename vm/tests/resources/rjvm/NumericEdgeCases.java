@@ -0,0 +1,34 @@
+package rjvm;
+
+public class NumericEdgeCases {
+    public static void main(String[] args) {
+        double nan = Double.NaN;
+        double one = 1.0;
+        tempPrint(one < 2.0);
+        tempPrint(one > 2.0);
+        tempPrint(nan < one);
+        tempPrint(nan > one);
+        tempPrint(nan == nan);
+        tempPrint(nan != nan);
+        float floatNan = Float.NaN;
+        tempPrint(floatNan <= 1.0f);
+        tempPrint(floatNan >= 1.0f);
+
+        long bits = 1L << 62;
+        int distance = 52;
+        tempPrint(bits >> distance);
+        tempPrint(-bits >>> distance);
+        tempPrint(1L << distance);
+
+        int min = Integer.MIN_VALUE;
+        tempPrint(-min);
+        long longMin = Long.MIN_VALUE;
+        tempPrint(-longMin);
+    }
+
+    private static native void tempPrint(boolean value);
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(long value);
+}
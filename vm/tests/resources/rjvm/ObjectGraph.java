@@ -0,0 +1,31 @@
+package rjvm;
+
+public class ObjectGraph {
+    private static Node kept;
+
+    public static void buildAndDrop() {
+        build(100);
+    }
+
+    public static void buildAndKeep() {
+        kept = build(100);
+    }
+
+    private static Node build(int length) {
+        Node head = null;
+        for (int i = 0; i < length; ++i) {
+            head = new Node(head, new int[]{i});
+        }
+        return head;
+    }
+
+    static class Node {
+        private final Node next;
+        private final int[] payload;
+
+        Node(Node next, int[] payload) {
+            this.next = next;
+            this.payload = payload;
+        }
+    }
+}
@@ -0,0 +1,5 @@
+package rjvm;
+
+public class OtherTypeShadow extends StaticFieldBase {
+    public static long count = 5;
+}
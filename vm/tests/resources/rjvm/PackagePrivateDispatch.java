@@ -0,0 +1,42 @@
+package rjvm;
+
+import rjvm.other.OtherPackageSubclass;
+import rjvm.other.TransitiveOverride;
+
+public class PackagePrivateDispatch {
+    public static void main(String[] args) {
+        tempPrint(new PackagePrivateDispatch().callValue());
+        tempPrint(new SamePackageSubclass().callValue());
+        // The subclass cannot access value(), thus its own declaration does not override it
+        OtherPackageSubclass other = new OtherPackageSubclass();
+        tempPrint(other.callValue());
+        tempPrint(other.callOwnValue());
+        tempPrint(new PublicOverride().callValue());
+        // Overrides value() through PublicOverride's declaration, which can access it
+        tempPrint(new TransitiveOverride().callValue());
+    }
+
+    int value() {
+        return 1;
+    }
+
+    public int callValue() {
+        return value();
+    }
+
+    static class SamePackageSubclass extends PackagePrivateDispatch {
+        @Override
+        int value() {
+            return 2;
+        }
+    }
+
+    public static class PublicOverride extends PackagePrivateDispatch {
+        @Override
+        public int value() {
+            return 4;
+        }
+    }
+
+    private static native void tempPrint(int value);
+}
@@ -0,0 +1,11 @@
+package rjvm;
+
+public class PrintCharArray {
+    public static void main(String[] args) {
+        char[] chars = new char[]{'h', 'e', 'l', 'l', 'o'};
+        tempPrint(chars);
+        tempPrint(new char[0]);
+    }
+
+    private static native void tempPrint(char[] value);
+}
@@ -0,0 +1,50 @@
+package rjvm;
+
+import java.io.PrintStream;
+import java.util.IllegalFormatConversionException;
+
+public class Printf {
+    public static PrintStream printTo(PrintStream stream) {
+        return stream.printf("%d-%s%n", new Integer(42), "x").format("%5.1f|%c", 2.25, 'z');
+    }
+
+    public static void main(String[] args) {
+        tempPrint(String.format("%s has %03d items", "cart", new Integer(7)));
+        try {
+            String.format("%d", "text");
+        } catch (IllegalFormatConversionException e) {
+            tempPrint(e.getMessage());
+        }
+        try {
+            String.format("%s", (Object[]) null);
+        } catch (NullPointerException e) {
+            tempPrint("null arguments");
+        }
+    }
+
+    // Its toString allocates enough to run the gc, when the heap is small
+    static class Garbage {
+        public String toString() {
+            int[] garbage = null;
+            for (int i = 0; i < 20; ++i) {
+                garbage = new int[1000];
+            }
+            return "garbage" + garbage.length;
+        }
+    }
+
+    public static int formatWhileCollecting(PrintStream stream) {
+        int mismatches = 0;
+        for (int i = 0; i < 100; ++i) {
+            if (!String.format("%s %d", new Garbage(), new Integer(i)).equals("garbage1000 " + i)) {
+                ++mismatches;
+            }
+            if (stream.printf("%s", new Garbage()) != stream) {
+                ++mismatches;
+            }
+        }
+        return mismatches;
+    }
+
+    private static native void tempPrint(String value);
+}
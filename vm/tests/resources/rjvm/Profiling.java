@@ -0,0 +1,23 @@
+package rjvm;
+
+public class Profiling {
+    public static int sumOf10() {
+        return sum(10);
+    }
+
+    public static int sumOf20() {
+        return sum(20);
+    }
+
+    public static int sumOf30() {
+        return sum(30);
+    }
+
+    private static int sum(int n) {
+        int total = 0;
+        for (int i = 0; i < n; ++i) {
+            total += i;
+        }
+        return total;
+    }
+}
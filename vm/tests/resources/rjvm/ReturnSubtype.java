@@ -0,0 +1,30 @@
+package rjvm;
+
+public class ReturnSubtype {
+    public static void main(String[] args) {
+        Object o = returnString();
+        tempPrint((String) o);
+        tempPrint(returnNull() == null);
+    }
+
+    private static Object returnString() {
+        return "hello";
+    }
+
+    private static Base returnNull() {
+        return null;
+    }
+
+    // Compiled against the version of IncompatibleReturnValue in compile_time, which extends
+    // Base. The bytecode does not have a checkcast, so the vm must detect the wrong return value.
+    public static Base returnIncompatible() {
+        return new IncompatibleReturnValue();
+    }
+
+    public static class Base {
+    }
+
+    private static native void tempPrint(boolean value);
+
+    private static native void tempPrint(String value);
+}
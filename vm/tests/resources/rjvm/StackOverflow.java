@@ -0,0 +1,24 @@
+package rjvm;
+
+public class StackOverflow {
+    private static int depth = 0;
+
+    public static void main(String[] args) {
+        try {
+            recurse();
+        } catch (StackOverflowError e) {
+            tempPrint(depth);
+        }
+    }
+
+    public static void uncaught() {
+        recurse();
+    }
+
+    private static void recurse() {
+        ++depth;
+        recurse();
+    }
+
+    private static native void tempPrint(int value);
+}
@@ -0,0 +1,65 @@
+package rjvm;
+
+public class StaticFieldAccess {
+    private static int counter;
+    private static long aLong;
+    private static double aDouble;
+    private static String aString;
+
+    static {
+        counter = 42;
+        aLong = 1L << 40;
+        aDouble = 2.5;
+        aString = "set in clinit";
+    }
+
+    static class Base {
+        static int inherited;
+
+        static {
+            inherited = 10;
+            tempPrint(1);
+        }
+    }
+
+    static class Derived extends Base {
+        static {
+            tempPrint(2);
+        }
+    }
+
+    interface Constants {
+        int[] VALUES = {3, 4};
+    }
+
+    static class Implementation implements Constants {
+    }
+
+    public static void main(String[] args) {
+        tempPrint(counter());
+        counter += 1;
+        tempPrint(counter());
+        tempPrint(aLong);
+        tempPrint(aDouble);
+        tempPrint(aString);
+
+        // Only the class declaring the field is initialized
+        tempPrint(Derived.inherited);
+        Derived.inherited = 20;
+        tempPrint(Base.inherited);
+
+        tempPrint(Implementation.VALUES[1]);
+    }
+
+    private static int counter() {
+        return counter;
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(long value);
+
+    private static native void tempPrint(double value);
+
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,5 @@
+package rjvm;
+
+public class StaticFieldBase {
+    public static int count = 7;
+}
@@ -0,0 +1,13 @@
+package rjvm;
+
+// This class is compiled against the versions of InstanceFieldShadow and OtherTypeShadow in
+// compile_time, which do not declare any field, so that both references to count resolve to
+// the static int field of StaticFieldBase, skipping the fields the subclasses declare at runtime
+public class StaticFieldResolution {
+    public static void main(String[] args) {
+        tempPrint(InstanceFieldShadow.count);
+        tempPrint(OtherTypeShadow.count);
+    }
+
+    private static native void tempPrint(int value);
+}
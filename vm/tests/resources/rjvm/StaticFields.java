@@ -0,0 +1,13 @@
+package rjvm;
+
+public class StaticFields {
+    private static int anInt = 42;
+    private static long aLong = 123456789012L;
+    private static double aDouble = 3.5;
+    private static String aString = "hello";
+    private static Object aNull;
+    private int notStatic = 1;
+
+    public static void main(String[] args) {
+    }
+}
@@ -0,0 +1,11 @@
+package rjvm;
+
+public class StaticMismatch {
+    public static int invokeStatic() {
+        return StaticMismatchTarget.value();
+    }
+
+    public static int invokeVirtual() {
+        return new StaticMismatchTarget().other();
+    }
+}
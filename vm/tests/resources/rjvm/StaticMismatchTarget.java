@@ -0,0 +1,13 @@
+package rjvm;
+
+// StaticMismatch is compiled against the version of this class in compile_time, where
+// value() is static and other() is not, so that its invocations are invalid
+public class StaticMismatchTarget {
+    int value() {
+        return 1;
+    }
+
+    static int other() {
+        return 2;
+    }
+}
@@ -0,0 +1,40 @@
+package rjvm;
+
+public class StringConcatenation {
+    public static void main(String[] args) {
+        int i = -42;
+        long l = 1L << 40;
+        float f = 1.5f;
+        double d = -0.25;
+        boolean b = true;
+        char c = 'x';
+        Object nothing = null;
+        tempPrint("i=" + i + " l=" + l + " f=" + f + " d=" + d + " b=" + b + " c=" + c + " " + nothing);
+
+        StringBuilder builder = new StringBuilder();
+        for (int n = 0; n < 20; ++n) {
+            builder.append(n % 10);
+        }
+        tempPrint(builder.reverse().toString());
+        tempPrint(builder.length());
+
+        tempPrint(Double.toString(1e10) + " " + 0.1f + " " + (0.0 / 0.0) + " " + 1e-5);
+    }
+
+    public static void concatenateInLoop() {
+        float f = 1.5f;
+        double d = -0.25;
+        int mismatches = 0;
+        for (int n = 0; n < 2000; ++n) {
+            String text = "f=" + f + " d=" + d;
+            if (!text.equals("f=1.5 d=-0.25")) {
+                ++mismatches;
+            }
+        }
+        tempPrint(mismatches);
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,39 @@
+package rjvm;
+
+public class StringMethods {
+    public static void main(String[] args) {
+        String text = "hello, world";
+        tempPrint(text.length());
+        tempPrint(text.charAt(4));
+        tempPrint(text.equals("hello, " + "world".substring(0)));
+        tempPrint(text.equals("hello"));
+        tempPrint(text.equals(null));
+        tempPrint(text.indexOf('o'));
+        tempPrint(text.indexOf('o', 5));
+        tempPrint(text.indexOf('z'));
+        tempPrint("abc".compareTo("abd"));
+        tempPrint("abc".compareTo("ab"));
+        tempPrint("abc".compareTo("abc"));
+
+        String world = text.substring(7, 12);
+        tempPrint(world);
+        tempPrint(world.equals("world"));
+        tempPrint(world != "world");
+        tempPrint(world.intern() == "world");
+
+        try {
+            text.charAt(12);
+            tempPrint(false);
+        } catch (StringIndexOutOfBoundsException e) {
+            tempPrint(true);
+        }
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(char value);
+
+    private static native void tempPrint(boolean value);
+
+    private static native void tempPrint(String value);
+}
@@ -0,0 +1,38 @@
+package rjvm;
+
+public class SuperinterfaceInitialization {
+    interface WithoutDefaultMethods {
+        int VALUE = record(1);
+    }
+
+    interface WithDefaultMethods {
+        int VALUE = record(2);
+
+        default int value() {
+            return VALUE;
+        }
+    }
+
+    interface InheritsDefaultMethods extends WithDefaultMethods {
+    }
+
+    static class Implementation implements WithoutDefaultMethods, InheritsDefaultMethods {
+        static {
+            record(3);
+        }
+    }
+
+    static int record(int value) {
+        tempPrint(value);
+        return value;
+    }
+
+    public static void main(String[] args) {
+        // Only the superinterfaces declaring default methods are initialized with the class
+        new Implementation();
+        tempPrint(4);
+        tempPrint(WithoutDefaultMethods.VALUE);
+    }
+
+    private static native void tempPrint(int value);
+}
@@ -0,0 +1,108 @@
+package rjvm;
+
+public class Switches {
+    enum Color {
+        RED,
+        GREEN,
+        BLUE
+    }
+
+    public static void main(String[] args) {
+        for (int i = -1; i <= 5; ++i) {
+            tempPrint(dense(i));
+        }
+        int[] sparseKeys = {Integer.MIN_VALUE, -100, 0, 1000, 1001, Integer.MAX_VALUE};
+        for (int key : sparseKeys) {
+            tempPrint(sparse(key));
+        }
+        tempPrint(fallThrough(1));
+        tempPrint(fallThrough(2));
+        tempPrint(onChar('b'));
+        tempPrint(onString("two"));
+        tempPrint(onString("three"));
+        tempPrint(onEnum(Color.BLUE));
+    }
+
+    private static int dense(int n) {
+        switch (n) {
+            case 0:
+                return 10;
+            case 1:
+                return 11;
+            case 2:
+                return 12;
+            case 3:
+                return 13;
+            default:
+                return -1;
+        }
+    }
+
+    private static int sparse(int n) {
+        switch (n) {
+            case Integer.MIN_VALUE:
+                return 1;
+            case -100:
+                return 2;
+            case 1000:
+                return 3;
+            case Integer.MAX_VALUE:
+                return 4;
+            default:
+                return 0;
+        }
+    }
+
+    private static int fallThrough(int n) {
+        int result = 0;
+        switch (n) {
+            case 1:
+                result += 1;
+            case 2:
+                result += 2;
+                break;
+            case 3:
+                result += 3;
+        }
+        return result;
+    }
+
+    private static int onChar(char c) {
+        switch (c) {
+            case 'a':
+                return 1;
+            case 'b':
+                return 2;
+            case 'c':
+                return 3;
+            default:
+                return 0;
+        }
+    }
+
+    private static int onString(String s) {
+        switch (s) {
+            case "one":
+                return 1;
+            case "two":
+                return 2;
+            default:
+                return 0;
+        }
+    }
+
+    private static int onEnum(Color color) {
+        switch (color) {
+            case RED:
+                return 1;
+            case GREEN:
+                return 2;
+            case BLUE:
+                return 3;
+            default:
+                return 0;
+        }
+    }
+
+    private static native void tempPrint(int value);
+}
@@ -0,0 +1,34 @@
+package rjvm;
+
+public class SynchronizedMethods {
+    private int counter;
+
+    private synchronized void incrementAndThrow() {
+        ++counter;
+        throw new IllegalStateException("failed");
+    }
+
+    private synchronized void increment() {
+        ++counter;
+    }
+
+    public static void main(String[] args) {
+        SynchronizedMethods object = new SynchronizedMethods();
+        try {
+            object.incrementAndThrow();
+        } catch (IllegalStateException e) {
+            tempPrint(e.getMessage());
+        }
+
+        // The monitor must have been released, so we can acquire it again
+        object.increment();
+        synchronized (object) {
+            ++object.counter;
+        }
+        tempPrint(object.counter);
+    }
+
+    private static native void tempPrint(String value);
+
+    private static native void tempPrint(int value);
+}
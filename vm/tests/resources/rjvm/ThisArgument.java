@@ -0,0 +1,13 @@
+package rjvm;
+
+public class ThisArgument {
+    private final int base;
+
+    public ThisArgument(int base) {
+        this.base = base;
+    }
+
+    public int offset(int delta, long big, int small) {
+        return base + delta + (int) big + small;
+    }
+}
@@ -0,0 +1,42 @@
+package rjvm;
+
+public class ThrowingConstructors {
+    public static void main(String[] args) {
+        ThrowingConstructors complete = new ThrowingConstructors(false);
+        tempPrint(complete.before);
+        tempPrint(complete.after);
+
+        // If the objects that failed their construction were still reachable,
+        // we would run out of memory well before the end of the loop
+        ThrowingConstructors failed = null;
+        int failures = 0;
+        for (int i = 0; i < 20; ++i) {
+            try {
+                failed = new ThrowingConstructors(true);
+            } catch (IllegalStateException e) {
+                ++failures;
+            }
+        }
+        tempPrint(failures);
+        tempPrint(failed == null);
+    }
+
+    private final long[] oneMegabyteOfData = new long[1024 * 1024 / 8];
+    private final int before;
+    private int after;
+
+    private ThrowingConstructors(boolean fail) {
+        this.before = 1;
+        if (fail) {
+            throw new IllegalStateException("failed midway");
+        }
+        this.after = 2;
+        if (this.oneMegabyteOfData.length > 0) {
+            return;
+        }
+        this.after = 3;
+    }
+
+    private static native void tempPrint(int value);
+    private static native void tempPrint(boolean value);
+}
@@ -0,0 +1,38 @@
+package rjvm;
+
+public class TraceRecording {
+    private final int base;
+
+    TraceRecording(int base) {
+        this.base = base;
+    }
+
+    long add(long value) {
+        return base + value;
+    }
+
+    static int square(int x) {
+        return x * x;
+    }
+
+    static String pick(boolean first, String a, String b) {
+        return first ? a : b;
+    }
+
+    static double half(double value) {
+        return value / 2;
+    }
+
+    public static void main(String[] args) {
+        TraceRecording recording = new TraceRecording(square(3));
+        tempPrint(recording.add(1L));
+        tempPrint(pick(false, "a", "b"));
+        tempPrint(half(3));
+    }
+
+    private static native void tempPrint(long value);
+
+    private static native void tempPrint(double value);
+
+    private static native void tempPrint(Object value);
+}
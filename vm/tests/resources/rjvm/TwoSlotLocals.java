@@ -0,0 +1,38 @@
+package rjvm;
+
+public class TwoSlotLocals {
+    public static void main(String[] args) {
+        interleaved(1L, 2, 3.5);
+
+        // The two ints reuse the slots of the long, once it goes out of scope
+        {
+            long wide = Long.MAX_VALUE;
+            tempPrint(wide);
+        }
+        {
+            int low = 4;
+            int high = 5;
+            tempPrint(low + high);
+        }
+    }
+
+    private static void interleaved(long first, int second, double third) {
+        long fourth = first + 10;
+        int fifth = second + 10;
+        double sixth = third + 10;
+        first = -first;
+        second = -second;
+        tempPrint(first);
+        tempPrint(second);
+        tempPrint(third);
+        tempPrint(fourth);
+        tempPrint(fifth);
+        tempPrint(sixth);
+    }
+
+    private static native void tempPrint(long value);
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(double value);
+}
@@ -0,0 +1,53 @@
+package rjvm;
+
+public class WideConstants {
+    // Fills the constant pool, so that the constants used in main get an index above 255
+    // and must be loaded with ldc_w rather than ldc
+    private static int[] padding() {
+        return new int[] {
+            100000, 100001, 100002, 100003, 100004, 100005, 100006, 100007, 100008, 100009,
+            100010, 100011, 100012, 100013, 100014, 100015, 100016, 100017, 100018, 100019,
+            100020, 100021, 100022, 100023, 100024, 100025, 100026, 100027, 100028, 100029,
+            100030, 100031, 100032, 100033, 100034, 100035, 100036, 100037, 100038, 100039,
+            100040, 100041, 100042, 100043, 100044, 100045, 100046, 100047, 100048, 100049,
+            100050, 100051, 100052, 100053, 100054, 100055, 100056, 100057, 100058, 100059,
+            100060, 100061, 100062, 100063, 100064, 100065, 100066, 100067, 100068, 100069,
+            100070, 100071, 100072, 100073, 100074, 100075, 100076, 100077, 100078, 100079,
+            100080, 100081, 100082, 100083, 100084, 100085, 100086, 100087, 100088, 100089,
+            100090, 100091, 100092, 100093, 100094, 100095, 100096, 100097, 100098, 100099,
+            100100, 100101, 100102, 100103, 100104, 100105, 100106, 100107, 100108, 100109,
+            100110, 100111, 100112, 100113, 100114, 100115, 100116, 100117, 100118, 100119,
+            100120, 100121, 100122, 100123, 100124, 100125, 100126, 100127, 100128, 100129,
+            100130, 100131, 100132, 100133, 100134, 100135, 100136, 100137, 100138, 100139,
+            100140, 100141, 100142, 100143, 100144, 100145, 100146, 100147, 100148, 100149,
+            100150, 100151, 100152, 100153, 100154, 100155, 100156, 100157, 100158, 100159,
+            100160, 100161, 100162, 100163, 100164, 100165, 100166, 100167, 100168, 100169,
+            100170, 100171, 100172, 100173, 100174, 100175, 100176, 100177, 100178, 100179,
+            100180, 100181, 100182, 100183, 100184, 100185, 100186, 100187, 100188, 100189,
+            100190, 100191, 100192, 100193, 100194, 100195, 100196, 100197, 100198, 100199,
+            100200, 100201, 100202, 100203, 100204, 100205, 100206, 100207, 100208, 100209,
+            100210, 100211, 100212, 100213, 100214, 100215, 100216, 100217, 100218, 100219,
+            100220, 100221, 100222, 100223, 100224, 100225, 100226, 100227, 100228, 100229,
+            100230, 100231, 100232, 100233, 100234, 100235, 100236, 100237, 100238, 100239,
+            100240, 100241, 100242, 100243, 100244, 100245, 100246, 100247, 100248, 100249,
+            100250, 100251, 100252, 100253, 100254, 100255, 100256, 100257, 100258, 100259,
+            100260, 100261, 100262, 100263, 100264, 100265, 100266, 100267, 100268, 100269,
+            100270, 100271, 100272, 100273, 100274, 100275, 100276, 100277, 100278, 100279,
+            100280, 100281, 100282, 100283, 100284, 100285, 100286, 100287, 100288, 100289,
+            100290, 100291, 100292, 100293, 100294, 100295, 100296, 100297, 100298, 100299
+        };
+    }
+
+    public static void main(String[] args) {
+        tempPrint("wide string");
+        tempPrint(123456789);
+        tempPrint(1.5f);
+        tempPrint(WideConstants.class.getName());
+    }
+
+    private static native void tempPrint(int value);
+
+    private static native void tempPrint(float value);
+
+    private static native void tempPrint(Object value);
+}
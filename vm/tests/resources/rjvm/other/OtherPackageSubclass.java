@@ -0,0 +1,13 @@
+package rjvm.other;
+
+import rjvm.PackagePrivateDispatch;
+
+public class OtherPackageSubclass extends PackagePrivateDispatch {
+    int value() {
+        return 3;
+    }
+
+    public int callOwnValue() {
+        return value();
+    }
+}
@@ -0,0 +1,10 @@
+package rjvm.other;
+
+import rjvm.PackagePrivateDispatch;
+
+public class TransitiveOverride extends PackagePrivateDispatch.PublicOverride {
+    @Override
+    public int value() {
+        return 5;
+    }
+}